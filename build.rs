@@ -0,0 +1,5 @@
+// Rebuild when a migration is added so the embedded migrations stay up to date
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+    println!("cargo:rerun-if-changed=migrations_sqlite");
+}
@@ -0,0 +1,33 @@
+-- Add migration script here
+CREATE TABLE public.disabled_commands
+(
+    guild_id bigint NOT NULL,
+    name text COLLATE pg_catalog."default" NOT NULL,
+    CONSTRAINT disabled_commands_pkey PRIMARY KEY (guild_id, name),
+    CONSTRAINT "FK_disabled_commands_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES public.guild_info (guild_id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.disabled_commands
+    OWNER to postgres;
+
+CREATE TABLE public.command_channels
+(
+    guild_id bigint NOT NULL,
+    name text COLLATE pg_catalog."default" NOT NULL,
+    channel_id bigint NOT NULL,
+    CONSTRAINT command_channels_pkey PRIMARY KEY (guild_id, name, channel_id),
+    CONSTRAINT "FK_command_channels_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES public.guild_info (guild_id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.command_channels
+    OWNER to postgres;
@@ -0,0 +1,18 @@
+-- Add migration script here
+CREATE TABLE public.command_cooldowns
+(
+    guild_id bigint NOT NULL,
+    name text COLLATE pg_catalog."default" NOT NULL,
+    scope text COLLATE pg_catalog."default" NOT NULL,
+    seconds bigint NOT NULL,
+    CONSTRAINT command_cooldowns_pkey PRIMARY KEY (guild_id, name),
+    CONSTRAINT "FK_command_cooldowns_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES public.guild_info (guild_id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.command_cooldowns
+    OWNER to postgres;
@@ -0,0 +1,23 @@
+-- Add migration script here
+CREATE TABLE public.command_usage
+(
+    guild_id bigint,
+    channel_id bigint NOT NULL,
+    user_id bigint NOT NULL,
+    command text COLLATE pg_catalog."default" NOT NULL,
+    duration_ms bigint NOT NULL,
+    success boolean NOT NULL,
+    error text COLLATE pg_catalog."default",
+    used_at bigint NOT NULL
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.command_usage
+    OWNER to postgres;
+
+CREATE INDEX command_usage_guild_id_idx
+    ON public.command_usage (guild_id, used_at);
+
+CREATE INDEX command_usage_used_at_idx
+    ON public.command_usage (used_at);
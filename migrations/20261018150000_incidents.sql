@@ -0,0 +1,19 @@
+-- Add migration script here
+CREATE TABLE public.incidents
+(
+    id text COLLATE pg_catalog."default" NOT NULL,
+    command text COLLATE pg_catalog."default" NOT NULL,
+    args text COLLATE pg_catalog."default" NOT NULL,
+    guild_id bigint,
+    channel_id bigint NOT NULL,
+    user_id bigint NOT NULL,
+    error text COLLATE pg_catalog."default" NOT NULL,
+    source_chain text COLLATE pg_catalog."default" NOT NULL,
+    created_at bigint NOT NULL,
+    CONSTRAINT incidents_pkey PRIMARY KEY (id)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.incidents
+    OWNER to postgres;
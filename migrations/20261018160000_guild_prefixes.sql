@@ -0,0 +1,23 @@
+-- Add migration script here
+CREATE TABLE public.guild_prefixes
+(
+    guild_id bigint NOT NULL,
+    prefix text COLLATE pg_catalog."default" NOT NULL,
+    CONSTRAINT guild_prefixes_pkey PRIMARY KEY (guild_id, prefix),
+    CONSTRAINT "FK_guild_prefixes_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES public.guild_info (guild_id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.guild_prefixes
+    OWNER to postgres;
+
+INSERT INTO public.guild_prefixes(guild_id, prefix)
+    SELECT guild_id, LOWER(prefix) FROM public.guild_info
+    WHERE prefix IS NOT NULL
+    ON CONFLICT DO NOTHING;
+
+ALTER TABLE public.guild_info DROP COLUMN prefix;
@@ -0,0 +1,23 @@
+-- Add migration script here
+ALTER TABLE public.commands
+    ADD COLUMN kind text COLLATE pg_catalog."default" NOT NULL DEFAULT 'text',
+    ADD COLUMN embed text COLLATE pg_catalog."default",
+    ADD COLUMN image_url text COLLATE pg_catalog."default",
+    ADD COLUMN reply boolean NOT NULL DEFAULT false;
+
+CREATE TABLE public.command_aliases
+(
+    guild_id bigint NOT NULL,
+    alias text COLLATE pg_catalog."default" NOT NULL,
+    name text COLLATE pg_catalog."default" NOT NULL,
+    CONSTRAINT command_aliases_pkey PRIMARY KEY (guild_id, alias),
+    CONSTRAINT "FK_command_aliases_commands_guild_id_name" FOREIGN KEY (guild_id, name)
+        REFERENCES public.commands (guild_id, name) MATCH SIMPLE
+        ON UPDATE CASCADE
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.command_aliases
+    OWNER to postgres;
@@ -0,0 +1,18 @@
+-- Add migration script here
+CREATE TABLE public.command_requirements
+(
+    guild_id bigint NOT NULL,
+    name text COLLATE pg_catalog."default" NOT NULL,
+    kind text COLLATE pg_catalog."default" NOT NULL,
+    value bigint NOT NULL,
+    CONSTRAINT command_requirements_pkey PRIMARY KEY (guild_id, name, kind, value),
+    CONSTRAINT "FK_command_requirements_commands_guild_id_name" FOREIGN KEY (guild_id, name)
+        REFERENCES public.commands (guild_id, name) MATCH SIMPLE
+        ON UPDATE CASCADE
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.command_requirements
+    OWNER to postgres;
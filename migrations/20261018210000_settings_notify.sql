@@ -0,0 +1,36 @@
+-- Add migration script here
+CREATE OR REPLACE FUNCTION public.notify_guild_settings() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('guild_settings', OLD.guild_id::text);
+    ELSE
+        PERFORM pg_notify('guild_settings', NEW.guild_id::text);
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER guild_info_notify
+    AFTER INSERT OR UPDATE OR DELETE ON public.guild_info
+    FOR EACH ROW EXECUTE PROCEDURE public.notify_guild_settings();
+
+CREATE TRIGGER guild_prefixes_notify
+    AFTER INSERT OR UPDATE OR DELETE ON public.guild_prefixes
+    FOR EACH ROW EXECUTE PROCEDURE public.notify_guild_settings();
+
+CREATE TRIGGER text_channels_notify
+    AFTER INSERT OR UPDATE OR DELETE ON public.text_channels
+    FOR EACH ROW EXECUTE PROCEDURE public.notify_guild_settings();
+
+CREATE TRIGGER disabled_commands_notify
+    AFTER INSERT OR UPDATE OR DELETE ON public.disabled_commands
+    FOR EACH ROW EXECUTE PROCEDURE public.notify_guild_settings();
+
+CREATE TRIGGER command_channels_notify
+    AFTER INSERT OR UPDATE OR DELETE ON public.command_channels
+    FOR EACH ROW EXECUTE PROCEDURE public.notify_guild_settings();
+
+CREATE TRIGGER command_cooldowns_notify
+    AFTER INSERT OR UPDATE OR DELETE ON public.command_cooldowns
+    FOR EACH ROW EXECUTE PROCEDURE public.notify_guild_settings();
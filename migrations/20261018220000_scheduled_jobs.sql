@@ -0,0 +1,50 @@
+-- Add migration script here
+CREATE TABLE public.scheduled_jobs
+(
+    id bigserial NOT NULL,
+    name text COLLATE pg_catalog."default",
+    kind text COLLATE pg_catalog."default" NOT NULL,
+    payload text COLLATE pg_catalog."default" NOT NULL,
+    guild_id bigint,
+    next_run bigint NOT NULL,
+    interval_secs bigint,
+    attempts integer NOT NULL DEFAULT 0,
+    max_attempts integer NOT NULL,
+    locked_until bigint,
+    created_at bigint NOT NULL,
+    CONSTRAINT scheduled_jobs_pkey PRIMARY KEY (id),
+    CONSTRAINT scheduled_jobs_name_key UNIQUE (name),
+    CONSTRAINT "FK_scheduled_jobs_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES public.guild_info (guild_id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.scheduled_jobs
+    OWNER to postgres;
+
+CREATE INDEX scheduled_jobs_next_run_idx
+    ON public.scheduled_jobs (next_run);
+
+CREATE TABLE public.job_runs
+(
+    job_id bigint NOT NULL,
+    kind text COLLATE pg_catalog."default" NOT NULL,
+    started_at bigint NOT NULL,
+    duration_ms bigint NOT NULL,
+    success boolean NOT NULL,
+    error text COLLATE pg_catalog."default"
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.job_runs
+    OWNER to postgres;
+
+CREATE INDEX job_runs_job_id_idx
+    ON public.job_runs (job_id, started_at);
+
+CREATE INDEX job_runs_started_at_idx
+    ON public.job_runs (started_at);
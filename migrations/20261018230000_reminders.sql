@@ -0,0 +1,29 @@
+-- Add migration script here
+CREATE TABLE public.reminders
+(
+    id bigserial NOT NULL,
+    job_id bigint NOT NULL,
+    user_id bigint NOT NULL,
+    guild_id bigint,
+    channel_id bigint NOT NULL,
+    content text COLLATE pg_catalog."default" NOT NULL,
+    message_url text COLLATE pg_catalog."default",
+    remind_at bigint NOT NULL,
+    created_at bigint NOT NULL,
+    CONSTRAINT reminders_pkey PRIMARY KEY (id),
+    CONSTRAINT "FK_reminders_scheduled_jobs_job_id" FOREIGN KEY (job_id)
+        REFERENCES public.scheduled_jobs (id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.reminders
+    OWNER to postgres;
+
+CREATE INDEX reminders_job_id_idx
+    ON public.reminders (job_id);
+
+CREATE INDEX reminders_user_id_idx
+    ON public.reminders (user_id, remind_at);
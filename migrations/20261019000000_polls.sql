@@ -0,0 +1,75 @@
+-- Add migration script here
+CREATE TABLE public.polls
+(
+    id bigserial NOT NULL,
+    guild_id bigint NOT NULL,
+    channel_id bigint NOT NULL,
+    message_id bigint,
+    author_id bigint NOT NULL,
+    question text COLLATE pg_catalog."default" NOT NULL,
+    multiple_choice boolean NOT NULL,
+    end_time bigint,
+    job_id bigint,
+    closed boolean NOT NULL DEFAULT false,
+    created_at bigint NOT NULL,
+    CONSTRAINT polls_pkey PRIMARY KEY (id),
+    CONSTRAINT "FK_polls_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES public.guild_info (guild_id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE,
+    CONSTRAINT "FK_polls_scheduled_jobs_job_id" FOREIGN KEY (job_id)
+        REFERENCES public.scheduled_jobs (id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE SET NULL
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.polls
+    OWNER to postgres;
+
+CREATE INDEX polls_message_id_idx
+    ON public.polls (message_id);
+
+CREATE INDEX polls_job_id_idx
+    ON public.polls (job_id);
+
+CREATE TABLE public.poll_options
+(
+    poll_id bigint NOT NULL,
+    position integer NOT NULL,
+    content text COLLATE pg_catalog."default" NOT NULL,
+    CONSTRAINT poll_options_pkey PRIMARY KEY (poll_id, position),
+    CONSTRAINT "FK_poll_options_polls_poll_id" FOREIGN KEY (poll_id)
+        REFERENCES public.polls (id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.poll_options
+    OWNER to postgres;
+
+CREATE TABLE public.poll_votes
+(
+    poll_id bigint NOT NULL,
+    user_id bigint NOT NULL,
+    position integer NOT NULL,
+    single_choice boolean NOT NULL DEFAULT false,
+    CONSTRAINT poll_votes_pkey PRIMARY KEY (poll_id, user_id, position),
+    CONSTRAINT "FK_poll_votes_poll_options" FOREIGN KEY (poll_id, position)
+        REFERENCES public.poll_options (poll_id, position) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.poll_votes
+    OWNER to postgres;
+
+-- Users only get one vote in single choice polls, even when their reactions race
+CREATE UNIQUE INDEX poll_votes_single_choice_idx
+    ON public.poll_votes (poll_id, user_id)
+    WHERE single_choice;
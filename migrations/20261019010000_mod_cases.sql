@@ -0,0 +1,41 @@
+-- Add migration script here
+CREATE TABLE public.mod_cases
+(
+    guild_id bigint NOT NULL,
+    case_number bigint NOT NULL,
+    action text COLLATE pg_catalog."default" NOT NULL,
+    user_id bigint NOT NULL,
+    moderator_id bigint NOT NULL,
+    reason text COLLATE pg_catalog."default",
+    duration_secs bigint,
+    created_at bigint NOT NULL,
+    CONSTRAINT mod_cases_pkey PRIMARY KEY (guild_id, case_number),
+    CONSTRAINT "FK_mod_cases_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES public.guild_info (guild_id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.mod_cases
+    OWNER to postgres;
+
+CREATE INDEX mod_cases_user_id_idx
+    ON public.mod_cases (guild_id, user_id);
+
+CREATE TABLE public.mod_case_counters
+(
+    guild_id bigint NOT NULL,
+    last_case bigint NOT NULL,
+    CONSTRAINT mod_case_counters_pkey PRIMARY KEY (guild_id),
+    CONSTRAINT "FK_mod_case_counters_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES public.guild_info (guild_id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.mod_case_counters
+    OWNER to postgres;
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE public.text_channels
+    ADD COLUMN mod_log_id bigint;
@@ -0,0 +1,67 @@
+-- Add migration script here
+CREATE TABLE public.automod_rules
+(
+    guild_id bigint NOT NULL,
+    kind text COLLATE pg_catalog."default" NOT NULL,
+    action text COLLATE pg_catalog."default" NOT NULL,
+    threshold bigint,
+    interval_secs bigint,
+    timeout_secs bigint,
+    CONSTRAINT automod_rules_pkey PRIMARY KEY (guild_id, kind),
+    CONSTRAINT "FK_automod_rules_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES public.guild_info (guild_id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.automod_rules
+    OWNER to postgres;
+
+CREATE TABLE public.automod_filters
+(
+    guild_id bigint NOT NULL,
+    list text COLLATE pg_catalog."default" NOT NULL,
+    pattern text COLLATE pg_catalog."default" NOT NULL,
+    CONSTRAINT automod_filters_pkey PRIMARY KEY (guild_id, list, pattern),
+    CONSTRAINT "FK_automod_filters_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES public.guild_info (guild_id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.automod_filters
+    OWNER to postgres;
+
+CREATE TABLE public.automod_exemptions
+(
+    guild_id bigint NOT NULL,
+    kind text COLLATE pg_catalog."default" NOT NULL,
+    exempt_id bigint NOT NULL,
+    CONSTRAINT automod_exemptions_pkey PRIMARY KEY (guild_id, kind, exempt_id),
+    CONSTRAINT "FK_automod_exemptions_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES public.guild_info (guild_id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE public.automod_exemptions
+    OWNER to postgres;
+
+-- Automod settings are cached with the rest of the guild settings
+CREATE TRIGGER automod_rules_notify
+    AFTER INSERT OR UPDATE OR DELETE ON public.automod_rules
+    FOR EACH ROW EXECUTE PROCEDURE public.notify_guild_settings();
+
+CREATE TRIGGER automod_filters_notify
+    AFTER INSERT OR UPDATE OR DELETE ON public.automod_filters
+    FOR EACH ROW EXECUTE PROCEDURE public.notify_guild_settings();
+
+CREATE TRIGGER automod_exemptions_notify
+    AFTER INSERT OR UPDATE OR DELETE ON public.automod_exemptions
+    FOR EACH ROW EXECUTE PROCEDURE public.notify_guild_settings();
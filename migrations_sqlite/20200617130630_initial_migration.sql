@@ -0,0 +1,33 @@
+-- Add migration script here
+CREATE TABLE guild_info
+(
+    guild_id bigint NOT NULL,
+    prefix text,
+    CONSTRAINT guild_info_pkey PRIMARY KEY (guild_id)
+);
+
+CREATE TABLE commands
+(
+    "Id" text NOT NULL,
+    guild_id bigint NOT NULL,
+    name text,
+    content text,
+    CONSTRAINT commands_pkey PRIMARY KEY ("Id"),
+    CONSTRAINT "FK_commands_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
+
+CREATE TABLE text_channels
+(
+    guild_id bigint NOT NULL,
+    nice_id bigint NOT NULL,
+    bruh_id bigint NOT NULL,
+    quote_id bigint NOT NULL,
+    CONSTRAINT text_channels_pkey PRIMARY KEY (guild_id),
+    CONSTRAINT "FK_text_channels_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
@@ -0,0 +1,14 @@
+-- Add migration script here
+DROP TABLE commands;
+
+CREATE TABLE commands
+(
+    guild_id bigint NOT NULL,
+    name text NOT NULL,
+    content text,
+    CONSTRAINT commands_pkey PRIMARY KEY (guild_id, name),
+    CONSTRAINT "FK_commands_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
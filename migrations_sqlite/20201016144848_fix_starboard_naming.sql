@@ -0,0 +1,10 @@
+-- Add migration script here
+ALTER TABLE starbot
+    RENAME TO starboard;
+
+-- SQLite can't add a NOT NULL column without a default
+ALTER TABLE starboard
+    ADD COLUMN delete_time bigint NOT NULL DEFAULT 0;
+
+ALTER TABLE guild_info
+    RENAME starbot_threshold TO starboard_threshold;
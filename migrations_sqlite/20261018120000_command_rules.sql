@@ -0,0 +1,23 @@
+-- Add migration script here
+CREATE TABLE disabled_commands
+(
+    guild_id bigint NOT NULL,
+    name text NOT NULL,
+    CONSTRAINT disabled_commands_pkey PRIMARY KEY (guild_id, name),
+    CONSTRAINT "FK_disabled_commands_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
+
+CREATE TABLE command_channels
+(
+    guild_id bigint NOT NULL,
+    name text NOT NULL,
+    channel_id bigint NOT NULL,
+    CONSTRAINT command_channels_pkey PRIMARY KEY (guild_id, name, channel_id),
+    CONSTRAINT "FK_command_channels_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
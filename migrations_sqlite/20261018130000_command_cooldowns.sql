@@ -0,0 +1,13 @@
+-- Add migration script here
+CREATE TABLE command_cooldowns
+(
+    guild_id bigint NOT NULL,
+    name text NOT NULL,
+    scope text NOT NULL,
+    seconds bigint NOT NULL,
+    CONSTRAINT command_cooldowns_pkey PRIMARY KEY (guild_id, name),
+    CONSTRAINT "FK_command_cooldowns_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
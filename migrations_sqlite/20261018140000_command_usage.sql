@@ -0,0 +1,18 @@
+-- Add migration script here
+CREATE TABLE command_usage
+(
+    guild_id bigint,
+    channel_id bigint NOT NULL,
+    user_id bigint NOT NULL,
+    command text NOT NULL,
+    duration_ms bigint NOT NULL,
+    success boolean NOT NULL,
+    error text,
+    used_at bigint NOT NULL
+);
+
+CREATE INDEX command_usage_guild_id_idx
+    ON command_usage (guild_id, used_at);
+
+CREATE INDEX command_usage_used_at_idx
+    ON command_usage (used_at);
@@ -0,0 +1,14 @@
+-- Add migration script here
+CREATE TABLE incidents
+(
+    id text NOT NULL,
+    command text NOT NULL,
+    args text NOT NULL,
+    guild_id bigint,
+    channel_id bigint NOT NULL,
+    user_id bigint NOT NULL,
+    error text NOT NULL,
+    source_chain text NOT NULL,
+    created_at bigint NOT NULL,
+    CONSTRAINT incidents_pkey PRIMARY KEY (id)
+);
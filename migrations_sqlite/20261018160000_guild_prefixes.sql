@@ -0,0 +1,18 @@
+-- Add migration script here
+CREATE TABLE guild_prefixes
+(
+    guild_id bigint NOT NULL,
+    prefix text NOT NULL,
+    CONSTRAINT guild_prefixes_pkey PRIMARY KEY (guild_id, prefix),
+    CONSTRAINT "FK_guild_prefixes_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
+
+INSERT INTO guild_prefixes(guild_id, prefix)
+    SELECT guild_id, LOWER(prefix) FROM guild_info
+    WHERE prefix IS NOT NULL
+    ON CONFLICT DO NOTHING;
+
+ALTER TABLE guild_info DROP COLUMN prefix;
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE commands
+    ADD COLUMN uses bigint NOT NULL DEFAULT 0;
@@ -0,0 +1,25 @@
+-- Add migration script here
+-- SQLite only adds one column per statement
+ALTER TABLE commands
+    ADD COLUMN kind text NOT NULL DEFAULT 'text';
+
+ALTER TABLE commands
+    ADD COLUMN embed text;
+
+ALTER TABLE commands
+    ADD COLUMN image_url text;
+
+ALTER TABLE commands
+    ADD COLUMN reply boolean NOT NULL DEFAULT false;
+
+CREATE TABLE command_aliases
+(
+    guild_id bigint NOT NULL,
+    alias text NOT NULL,
+    name text NOT NULL,
+    CONSTRAINT command_aliases_pkey PRIMARY KEY (guild_id, alias),
+    CONSTRAINT "FK_command_aliases_commands_guild_id_name" FOREIGN KEY (guild_id, name)
+        REFERENCES commands (guild_id, name)
+        ON UPDATE CASCADE
+        ON DELETE CASCADE
+);
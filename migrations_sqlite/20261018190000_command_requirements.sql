@@ -0,0 +1,13 @@
+-- Add migration script here
+CREATE TABLE command_requirements
+(
+    guild_id bigint NOT NULL,
+    name text NOT NULL,
+    kind text NOT NULL,
+    value bigint NOT NULL,
+    CONSTRAINT command_requirements_pkey PRIMARY KEY (guild_id, name, kind, value),
+    CONSTRAINT "FK_command_requirements_commands_guild_id_name" FOREIGN KEY (guild_id, name)
+        REFERENCES commands (guild_id, name)
+        ON UPDATE CASCADE
+        ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE guild_info
+    ADD COLUMN locale text;
@@ -0,0 +1,4 @@
+-- Add migration script here
+-- Postgres notifies other processes when guild settings change
+-- SQLite databases are only used by one process, so there's nothing to add here
+SELECT 1;
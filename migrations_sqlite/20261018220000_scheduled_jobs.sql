@@ -0,0 +1,38 @@
+-- Add migration script here
+CREATE TABLE scheduled_jobs
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name text,
+    kind text NOT NULL,
+    payload text NOT NULL,
+    guild_id bigint,
+    next_run bigint NOT NULL,
+    interval_secs bigint,
+    attempts integer NOT NULL DEFAULT 0,
+    max_attempts integer NOT NULL,
+    locked_until bigint,
+    created_at bigint NOT NULL,
+    CONSTRAINT scheduled_jobs_name_key UNIQUE (name),
+    CONSTRAINT "FK_scheduled_jobs_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX scheduled_jobs_next_run_idx
+    ON scheduled_jobs (next_run);
+
+CREATE TABLE job_runs
+(
+    job_id bigint NOT NULL,
+    kind text NOT NULL,
+    started_at bigint NOT NULL,
+    duration_ms bigint NOT NULL,
+    success boolean NOT NULL,
+    error text
+);
+
+CREATE INDEX job_runs_job_id_idx
+    ON job_runs (job_id, started_at);
+
+CREATE INDEX job_runs_started_at_idx
+    ON job_runs (started_at);
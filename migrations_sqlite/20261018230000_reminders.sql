@@ -0,0 +1,22 @@
+-- Add migration script here
+CREATE TABLE reminders
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    job_id bigint NOT NULL,
+    user_id bigint NOT NULL,
+    guild_id bigint,
+    channel_id bigint NOT NULL,
+    content text NOT NULL,
+    message_url text,
+    remind_at bigint NOT NULL,
+    created_at bigint NOT NULL,
+    CONSTRAINT "FK_reminders_scheduled_jobs_job_id" FOREIGN KEY (job_id)
+        REFERENCES scheduled_jobs (id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX reminders_job_id_idx
+    ON reminders (job_id);
+
+CREATE INDEX reminders_user_id_idx
+    ON reminders (user_id, remind_at);
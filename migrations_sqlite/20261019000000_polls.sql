@@ -0,0 +1,55 @@
+-- Add migration script here
+CREATE TABLE polls
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id bigint NOT NULL,
+    channel_id bigint NOT NULL,
+    message_id bigint,
+    author_id bigint NOT NULL,
+    question text NOT NULL,
+    multiple_choice boolean NOT NULL,
+    end_time bigint,
+    job_id bigint,
+    closed boolean NOT NULL DEFAULT false,
+    created_at bigint NOT NULL,
+    CONSTRAINT "FK_polls_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON DELETE CASCADE,
+    CONSTRAINT "FK_polls_scheduled_jobs_job_id" FOREIGN KEY (job_id)
+        REFERENCES scheduled_jobs (id)
+        ON DELETE SET NULL
+);
+
+CREATE INDEX polls_message_id_idx
+    ON polls (message_id);
+
+CREATE INDEX polls_job_id_idx
+    ON polls (job_id);
+
+CREATE TABLE poll_options
+(
+    poll_id bigint NOT NULL,
+    position integer NOT NULL,
+    content text NOT NULL,
+    PRIMARY KEY (poll_id, position),
+    CONSTRAINT "FK_poll_options_polls_poll_id" FOREIGN KEY (poll_id)
+        REFERENCES polls (id)
+        ON DELETE CASCADE
+);
+
+CREATE TABLE poll_votes
+(
+    poll_id bigint NOT NULL,
+    user_id bigint NOT NULL,
+    position integer NOT NULL,
+    single_choice boolean NOT NULL DEFAULT false,
+    PRIMARY KEY (poll_id, user_id, position),
+    CONSTRAINT "FK_poll_votes_poll_options" FOREIGN KEY (poll_id, position)
+        REFERENCES poll_options (poll_id, position)
+        ON DELETE CASCADE
+);
+
+-- Users only get one vote in single choice polls, even when their reactions race
+CREATE UNIQUE INDEX poll_votes_single_choice_idx
+    ON poll_votes (poll_id, user_id)
+    WHERE single_choice;
@@ -0,0 +1,28 @@
+-- Add migration script here
+CREATE TABLE mod_cases
+(
+    guild_id bigint NOT NULL,
+    case_number bigint NOT NULL,
+    action text NOT NULL,
+    user_id bigint NOT NULL,
+    moderator_id bigint NOT NULL,
+    reason text,
+    duration_secs bigint,
+    created_at bigint NOT NULL,
+    PRIMARY KEY (guild_id, case_number),
+    CONSTRAINT "FK_mod_cases_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX mod_cases_user_id_idx
+    ON mod_cases (guild_id, user_id);
+
+CREATE TABLE mod_case_counters
+(
+    guild_id bigint NOT NULL PRIMARY KEY,
+    last_case bigint NOT NULL,
+    CONSTRAINT "FK_mod_case_counters_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON DELETE CASCADE
+);
@@ -0,0 +1,36 @@
+-- Add migration script here
+CREATE TABLE automod_rules
+(
+    guild_id bigint NOT NULL,
+    kind text NOT NULL,
+    action text NOT NULL,
+    threshold bigint,
+    interval_secs bigint,
+    timeout_secs bigint,
+    PRIMARY KEY (guild_id, kind),
+    CONSTRAINT "FK_automod_rules_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON DELETE CASCADE
+);
+
+CREATE TABLE automod_filters
+(
+    guild_id bigint NOT NULL,
+    list text NOT NULL,
+    pattern text NOT NULL,
+    PRIMARY KEY (guild_id, list, pattern),
+    CONSTRAINT "FK_automod_filters_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON DELETE CASCADE
+);
+
+CREATE TABLE automod_exemptions
+(
+    guild_id bigint NOT NULL,
+    kind text NOT NULL,
+    exempt_id bigint NOT NULL,
+    PRIMARY KEY (guild_id, kind, exempt_id),
+    CONSTRAINT "FK_automod_exemptions_guild_info_guild_id" FOREIGN KEY (guild_id)
+        REFERENCES guild_info (guild_id)
+        ON DELETE CASCADE
+);
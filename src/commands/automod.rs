@@ -0,0 +1,864 @@
+use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        prelude::*,
+    },
+    prelude::*,
+    utils::{parse_channel, parse_role},
+};
+
+use crate::{
+    helpers::{
+        automod::{
+            self, AutomodAction, AutomodExemption, FilterList, RuleKind, DEFAULT_TIMEOUT_SECS,
+            MAX_INTERVAL_SECS, MAX_TRACKED_MESSAGES,
+        },
+        command_utils, guild_settings, interaction_utils, locale,
+    },
+    repositories::AutomodRule,
+    structures::{command_source::CommandSource, errors::JesterError},
+    RepositoryContainer,
+};
+
+// Discord's limit for timeouts
+const MAX_TIMEOUT_SECS: i64 = 2419200;
+
+// Limits for each filter list
+const MAX_FILTERS: usize = 50;
+const MAX_PATTERN_LENGTH: usize = 100;
+
+const MAX_MENTIONS: i64 = 50;
+
+/// Shows the server's automod rules, filter lists, and exemptions
+#[command]
+#[required_permissions("ADMINISTRATOR")]
+#[sub_commands(automod_set, automod_remove, automod_filter, automod_exempt)]
+async fn automod(ctx: &Context, msg: &Message) -> CommandResult {
+    automod_status_internal(ctx, &CommandSource::Prefix(msg)).await
+}
+
+// Durations in the status embed are shown like 1h30m
+fn format_secs(secs: i64) -> String {
+    let units = [(86400, "d"), (3600, "h"), (60, "m"), (1, "s")];
+
+    let mut remaining = secs;
+    let mut output = String::new();
+
+    for (unit_secs, unit) in units {
+        if remaining >= unit_secs {
+            output.push_str(&format!("{}{}", remaining / unit_secs, unit));
+            remaining %= unit_secs;
+        }
+    }
+
+    if output.is_empty() {
+        "0s".to_owned()
+    } else {
+        output
+    }
+}
+
+fn format_rule(rule: &AutomodRule) -> String {
+    let threshold = rule.threshold.or_else(|| rule.kind.default_threshold());
+    let interval = rule.interval_secs.or_else(|| rule.kind.default_interval());
+
+    let limit = match (rule.kind, threshold, interval) {
+        (RuleKind::RateLimit, Some(threshold), Some(interval)) => {
+            format!(
+                " (over {} messages in {})",
+                threshold,
+                format_secs(interval)
+            )
+        }
+        (RuleKind::Duplicates, Some(threshold), Some(interval)) => {
+            format!(" ({} copies in {})", threshold, format_secs(interval))
+        }
+        (RuleKind::Mentions, Some(threshold), _) => format!(" (over {} mentions)", threshold),
+        _ => String::new(),
+    };
+
+    let action = match rule.action {
+        AutomodAction::Timeout => format!(
+            "timeout for {}",
+            format_secs(rule.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS))
+        ),
+        action => action.to_string(),
+    };
+
+    format!("`{}`{}: {}", rule.kind, limit, action)
+}
+
+async fn automod_status_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+
+    let settings = repository
+        .fetch_automod_settings(source.guild_id().unwrap())
+        .await?;
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0xe74c3c);
+    eb.title("Automod");
+
+    if settings.rules.is_empty() {
+        eb.description("There are no automod rules. Add one with `automod set`!");
+    } else {
+        let rule_list = RuleKind::ALL
+            .iter()
+            .filter_map(|kind| settings.rules.iter().find(|x| x.kind == *kind))
+            .map(format_rule)
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        eb.field("Rules", rule_list, false);
+    }
+
+    for list in FilterList::ALL {
+        let patterns = settings
+            .filters
+            .iter()
+            .filter(|(x, _)| *x == list)
+            .map(|(_, pattern)| format!("`{}`", pattern))
+            .collect::<Vec<String>>();
+
+        if !patterns.is_empty() {
+            eb.field(list.title(), patterns.join(", "), false);
+        }
+    }
+
+    if !settings.exemptions.is_empty() {
+        let exemption_list = settings
+            .exemptions
+            .iter()
+            .map(|x| match *x {
+                AutomodExemption::Role(role_id) => role_id.mention().to_string(),
+                AutomodExemption::Channel(channel_id) => channel_id.mention().to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        eb.field("Exempt", exemption_list, false);
+    }
+
+    eb.footer(|f| {
+        f.text("Members with the manage messages permission are always exempt");
+        f
+    });
+
+    source.send_embed(ctx, eb).await?;
+
+    Ok(())
+}
+
+/// Usage: `automod set <rule> <action> (limit) (window)`
+/// Timeouts can be given a length right after the action, like `automod set mentions timeout 1h 5`
+#[command("set")]
+async fn automod_set(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let kind = match args
+        .single::<String>()
+        .map(|x| x.to_lowercase().parse::<RuleKind>())
+    {
+        Ok(Ok(kind)) => kind,
+        Ok(Err(e)) => {
+            msg.channel_id.say(ctx, e).await?;
+
+            return Ok(());
+        }
+        Err(_) => {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
+            msg.channel_id
+                .say(
+                    ctx,
+                    JesterError::MissingError("automod_rule").localized(&locale),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let action = match args
+        .single::<String>()
+        .map(|x| x.to_lowercase().parse::<AutomodAction>())
+    {
+        Ok(Ok(action)) => action,
+        Ok(Err(e)) => {
+            msg.channel_id.say(ctx, e).await?;
+
+            return Ok(());
+        }
+        Err(_) => {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
+            msg.channel_id
+                .say(
+                    ctx,
+                    JesterError::MissingError("automod_action").localized(&locale),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let mut timeout_secs = None;
+    if action == AutomodAction::Timeout {
+        if let Some(duration) = args.current().and_then(command_utils::parse_duration) {
+            timeout_secs = Some(duration);
+            args.advance();
+        }
+    }
+
+    // The rest can come in either order, since only the window has a unit
+    let mut threshold = None;
+    let mut interval_secs = None;
+
+    for arg in args.iter::<String>().flatten() {
+        if let Ok(number) = arg.parse::<i64>() {
+            threshold = Some(number);
+        } else if let Some(duration) = command_utils::parse_duration(&arg) {
+            interval_secs = Some(duration);
+        } else {
+            msg.channel_id
+                .say(
+                    ctx,
+                    format!(
+                        "I don't understand `{}`! Use a number for the limit and a duration like `10s` for the window",
+                        arg
+                    ),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    }
+
+    let rule = AutomodRule {
+        kind,
+        action,
+        threshold,
+        interval_secs,
+        timeout_secs,
+    };
+
+    automod_set_internal(ctx, &CommandSource::Prefix(msg), rule).await
+}
+
+// Returns the error to show if the rule's numbers don't fit its kind
+fn validate_rule(rule: &AutomodRule) -> Result<(), String> {
+    if let Some(threshold) = rule.threshold {
+        let range = match rule.kind {
+            RuleKind::RateLimit | RuleKind::Duplicates => 2..=MAX_TRACKED_MESSAGES as i64,
+            RuleKind::Mentions => 1..=MAX_MENTIONS,
+            kind => return Err(format!("The {} rule doesn't take a limit!", kind)),
+        };
+
+        if !range.contains(&threshold) {
+            return Err(format!(
+                "The limit for the {} rule has to be between {} and {}!",
+                rule.kind,
+                range.start(),
+                range.end()
+            ));
+        }
+    }
+
+    if let Some(interval) = rule.interval_secs {
+        if rule.kind.default_interval().is_none() {
+            return Err(format!("The {} rule doesn't take a window!", rule.kind));
+        }
+
+        if !(1..=MAX_INTERVAL_SECS).contains(&interval) {
+            return Err(format!(
+                "The window has to be between 1 second and {}!",
+                format_secs(MAX_INTERVAL_SECS)
+            ));
+        }
+    }
+
+    if let Some(timeout) = rule.timeout_secs {
+        if !(1..=MAX_TIMEOUT_SECS).contains(&timeout) {
+            return Err("Timeouts can last up to 4 weeks!".to_owned());
+        }
+    }
+
+    Ok(())
+}
+
+async fn automod_set_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    rule: AutomodRule,
+) -> CommandResult {
+    if let Err(e) = validate_rule(&rule) {
+        source.say(ctx, e).await?;
+
+        return Ok(());
+    }
+
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    let rule_info = format_rule(&rule);
+
+    repository.set_automod_rule(guild_id, rule).await?;
+    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+    source
+        .say(ctx, format!("Automod rule set! {}", rule_info))
+        .await?;
+
+    Ok(())
+}
+
+/// Usage: `automod remove <rule>`
+#[command("remove")]
+async fn automod_remove(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let kind = match args
+        .single::<String>()
+        .map(|x| x.to_lowercase().parse::<RuleKind>())
+    {
+        Ok(Ok(kind)) => kind,
+        Ok(Err(e)) => {
+            msg.channel_id.say(ctx, e).await?;
+
+            return Ok(());
+        }
+        Err(_) => {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
+            msg.channel_id
+                .say(
+                    ctx,
+                    JesterError::MissingError("automod_rule").localized(&locale),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    automod_remove_internal(ctx, &CommandSource::Prefix(msg), kind).await
+}
+
+async fn automod_remove_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    kind: RuleKind,
+) -> CommandResult {
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    if repository.remove_automod_rule(guild_id, kind).await? {
+        guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+        source
+            .say(ctx, format!("Removed the {} rule!", kind))
+            .await?;
+    } else {
+        source
+            .say(ctx, format!("The {} rule isn't set!", kind))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Usage: `automod filter <add/remove> <list> <value>`
+#[command("filter")]
+async fn automod_filter(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let add = match args.single::<String>().map(|x| x.to_lowercase()).as_deref() {
+        Ok("add") => true,
+        Ok("remove") => false,
+        _ => {
+            msg.channel_id
+                .say(ctx, "Please specify if you want to add or remove a filter!")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let list = match args
+        .single::<String>()
+        .map(|x| x.to_lowercase().parse::<FilterList>())
+    {
+        Ok(Ok(list)) => list,
+        Ok(Err(e)) => {
+            msg.channel_id.say(ctx, e).await?;
+
+            return Ok(());
+        }
+        Err(_) => {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
+            msg.channel_id
+                .say(
+                    ctx,
+                    JesterError::MissingError("automod_list").localized(&locale),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    automod_filter_internal(
+        ctx,
+        &CommandSource::Prefix(msg),
+        add,
+        list,
+        args.rest().trim(),
+    )
+    .await
+}
+
+// Turns what the user typed into what's stored, or the error to show
+fn parse_pattern(list: FilterList, value: &str) -> Result<String, String> {
+    if value.is_empty() || value.chars().count() > MAX_PATTERN_LENGTH {
+        return Err(format!(
+            "Filters have to be between 1 and {} characters long!",
+            MAX_PATTERN_LENGTH
+        ));
+    }
+
+    match list {
+        FilterList::Invites => {
+            // Full invite links are allowed, but only the code is saved
+            let code = value.rsplit('/').next().unwrap_or_default().to_lowercase();
+
+            if code.is_empty() || !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                Err("That isn't an invite code or link!".to_owned())
+            } else {
+                Ok(code)
+            }
+        }
+        FilterList::AllowedLinks | FilterList::DeniedLinks => {
+            let domain = automod::normalize_domain(value);
+
+            if !domain.contains('.') || domain.contains(char::is_whitespace) {
+                Err("That isn't a domain! Use something like `example.com`".to_owned())
+            } else {
+                Ok(domain)
+            }
+        }
+        FilterList::Words => match automod::compile_filter(value) {
+            Ok(_) => Ok(value.to_owned()),
+            Err(_) => Err(format!("`{}` isn't a valid regex!", value)),
+        },
+    }
+}
+
+async fn automod_filter_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    add: bool,
+    list: FilterList,
+    value: &str,
+) -> CommandResult {
+    let pattern = match parse_pattern(list, value) {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            source.say(ctx, e).await?;
+
+            return Ok(());
+        }
+    };
+
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    if add {
+        let filter_count = repository
+            .fetch_automod_settings(guild_id)
+            .await?
+            .filters
+            .iter()
+            .filter(|(x, _)| *x == list)
+            .count();
+
+        if filter_count >= MAX_FILTERS {
+            source
+                .say(
+                    ctx,
+                    format!("Each list can only have {} filters!", MAX_FILTERS),
+                )
+                .await?;
+
+            return Ok(());
+        }
+
+        if repository
+            .add_automod_filter(guild_id, list, &pattern)
+            .await?
+        {
+            guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+            source
+                .say(ctx, format!("Added `{}` to the {} list!", pattern, list))
+                .await?;
+        } else {
+            source
+                .say(
+                    ctx,
+                    format!("`{}` is already in the {} list!", pattern, list),
+                )
+                .await?;
+        }
+    } else if repository
+        .remove_automod_filter(guild_id, list, &pattern)
+        .await?
+    {
+        guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+        source
+            .say(
+                ctx,
+                format!("Removed `{}` from the {} list!", pattern, list),
+            )
+            .await?;
+    } else {
+        source
+            .say(ctx, format!("`{}` isn't in the {} list!", pattern, list))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Usage: `automod exempt <role/channel mention>`
+/// Running it again removes the exemption
+#[command("exempt")]
+async fn automod_exempt(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let value = match args.single::<String>() {
+        Ok(value) => value,
+        Err(_) => {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
+            msg.channel_id
+                .say(
+                    ctx,
+                    JesterError::MissingError("role_or_channel").localized(&locale),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let exemption = if let Some(role_id) = parse_role(&value) {
+        AutomodExemption::Role(RoleId(role_id))
+    } else if let Some(channel_id) = parse_channel(&value) {
+        AutomodExemption::Channel(ChannelId(channel_id))
+    } else {
+        msg.channel_id
+            .say(ctx, "Please mention a role or channel!")
+            .await?;
+
+        return Ok(());
+    };
+
+    automod_exempt_internal(ctx, &CommandSource::Prefix(msg), exemption).await
+}
+
+async fn automod_exempt_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    exemption: AutomodExemption,
+) -> CommandResult {
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    let exempt = repository
+        .toggle_automod_exemption(guild_id, exemption)
+        .await?;
+    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+    // Roles are shown by name so nobody gets pinged
+    let name = match exemption {
+        AutomodExemption::Role(role_id) => format!(
+            "The `{}` role",
+            role_id
+                .to_role_cached(&ctx.cache)
+                .map_or_else(|| role_id.0.to_string(), |x| x.name)
+        ),
+        AutomodExemption::Channel(channel_id) => channel_id.mention().to_string(),
+    };
+
+    if exempt {
+        source
+            .say(ctx, format!("{} is now exempt from automod!", name))
+            .await?;
+    } else {
+        source
+            .say(ctx, format!("{} is no longer exempt from automod!", name))
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub fn automod_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "automod")
+}
+
+pub fn create_automod_commands(commands: &mut CreateApplicationCommands) {
+    commands.create_application_command(|c| {
+        c.name("automod")
+            .description("Configures automatic moderation")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .create_option(|o| {
+                o.name("status")
+                    .description("Shows the automod rules, filter lists, and exemptions")
+                    .kind(CommandOptionType::SubCommand)
+            })
+            .create_option(|o| {
+                o.name("set")
+                    .description("Adds or replaces an automod rule")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("rule")
+                            .description("What the rule looks for")
+                            .kind(CommandOptionType::String)
+                            .required(true);
+
+                        for kind in RuleKind::ALL {
+                            so.add_string_choice(kind, kind);
+                        }
+
+                        so
+                    })
+                    .create_sub_option(|so| {
+                        so.name("action")
+                            .description("What happens to the message and its author")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                            .add_string_choice("delete", "delete")
+                            .add_string_choice("warn", "warn")
+                            .add_string_choice("timeout", "timeout")
+                    })
+                    .create_sub_option(|so| {
+                        so.name("limit")
+                            .description(
+                                "Messages for ratelimit and duplicates, mentions for mentions",
+                            )
+                            .kind(CommandOptionType::Integer)
+                            .min_int_value(1)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("window")
+                            .description("The time window for ratelimit and duplicates, like 10s")
+                            .kind(CommandOptionType::String)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("length")
+                            .description("How long timeouts last, like 10m or 1d")
+                            .kind(CommandOptionType::String)
+                    })
+            })
+            .create_option(|o| {
+                o.name("remove")
+                    .description("Removes an automod rule")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("rule")
+                            .description("The rule to remove")
+                            .kind(CommandOptionType::String)
+                            .required(true);
+
+                        for kind in RuleKind::ALL {
+                            so.add_string_choice(kind, kind);
+                        }
+
+                        so
+                    })
+            })
+            .create_option(|o| {
+                o.name("filter")
+                    .description("Adds or removes an invite, link, or word filter")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("list")
+                            .description("The list to change")
+                            .kind(CommandOptionType::String)
+                            .required(true);
+
+                        for list in FilterList::ALL {
+                            so.add_string_choice(list, list);
+                        }
+
+                        so
+                    })
+                    .create_sub_option(|so| {
+                        so.name("value")
+                            .description("An invite code, a domain, or a regex for words")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("remove")
+                            .description("Removes the filter instead of adding it")
+                            .kind(CommandOptionType::Boolean)
+                    })
+            })
+            .create_option(|o| {
+                o.name("exempt")
+                    .description("Exempts a role or channel from automod, or removes its exemption")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("role")
+                            .description("The role to exempt")
+                            .kind(CommandOptionType::Role)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("channel")
+                            .description("The channel to exempt")
+                            .kind(CommandOptionType::Channel)
+                            .channel_types(&[ChannelType::Text])
+                    })
+            })
+    });
+}
+
+pub async fn automod_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+
+    let subcommand = match interaction_utils::get_subcommand(&command.data.options) {
+        Some(subcommand) => subcommand,
+        None => return Ok(()),
+    };
+    let options = &subcommand.options;
+
+    match subcommand.name.as_str() {
+        "status" => automod_status_internal(ctx, &source).await,
+        "set" => {
+            let kind = interaction_utils::get_string_option(options, "rule")
+                .and_then(|x| x.parse::<RuleKind>().ok());
+            let action = interaction_utils::get_string_option(options, "action")
+                .and_then(|x| x.parse::<AutomodAction>().ok());
+
+            let (kind, action) = match (kind, action) {
+                (Some(kind), Some(action)) => (kind, action),
+                _ => return Ok(()),
+            };
+
+            let mut durations = [None, None];
+
+            for (index, name) in ["window", "length"].iter().enumerate() {
+                if let Some(duration) = interaction_utils::get_string_option(options, name) {
+                    match command_utils::parse_duration(duration.trim()) {
+                        Some(secs) => durations[index] = Some(secs),
+                        None => {
+                            source
+                                .say(
+                                    ctx,
+                                    format!(
+                                        "I don't understand `{}`! Use a duration like `10s` or `1h`",
+                                        duration
+                                    ),
+                                )
+                                .await?;
+
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+
+            if durations[1].is_some() && action != AutomodAction::Timeout {
+                source
+                    .say(ctx, "Only the timeout action takes a length!")
+                    .await?;
+
+                return Ok(());
+            }
+
+            let rule = AutomodRule {
+                kind,
+                action,
+                threshold: interaction_utils::get_integer_option(options, "limit"),
+                interval_secs: durations[0],
+                timeout_secs: durations[1],
+            };
+
+            automod_set_internal(ctx, &source, rule).await
+        }
+        "remove" => match interaction_utils::get_string_option(options, "rule")
+            .and_then(|x| x.parse::<RuleKind>().ok())
+        {
+            Some(kind) => automod_remove_internal(ctx, &source, kind).await,
+            None => Ok(()),
+        },
+        "filter" => {
+            let list = match interaction_utils::get_string_option(options, "list")
+                .and_then(|x| x.parse::<FilterList>().ok())
+            {
+                Some(list) => list,
+                None => return Ok(()),
+            };
+            let value = interaction_utils::get_string_option(options, "value").unwrap_or_default();
+            let remove = interaction_utils::get_bool_option(options, "remove").unwrap_or(false);
+
+            automod_filter_internal(ctx, &source, !remove, list, value.trim()).await
+        }
+        "exempt" => {
+            let exemption = match (
+                interaction_utils::get_role_option(options, "role"),
+                interaction_utils::get_channel_option(options, "channel"),
+            ) {
+                (Some(role_id), None) => AutomodExemption::Role(role_id),
+                (None, Some(channel_id)) => AutomodExemption::Channel(channel_id),
+                _ => {
+                    source
+                        .say(ctx, "Please choose either a role or a channel!")
+                        .await?;
+
+                    return Ok(());
+                }
+            };
+
+            automod_exempt_internal(ctx, &source, exemption).await
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,747 @@
+use serde::{Deserialize, Serialize};
+use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
+    framework::standard::{macros::command, CommandResult},
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        channel::AttachmentType,
+        prelude::*,
+    },
+    prelude::*,
+};
+use std::{borrow::Cow, collections::HashSet};
+
+use crate::{
+    commands::config::{validate_prefix, MAX_PREFIXES},
+    helpers::{
+        custom_commands::{self, CustomKind, EmbedTemplate, Requirement},
+        guild_settings, interaction_utils, locale,
+        templates::Template,
+    },
+    repositories::{CommandConfig, CommandRow, GuildConfig, Repository, SenderChannels},
+    structures::command_source::CommandSource,
+    CommandNameMap, PubCreds, RepositoryContainer,
+};
+
+// Bump this when the backup format changes
+const BACKUP_VERSION: u32 = 1;
+const MAX_BACKUP_SIZE: u64 = 1_000_000;
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GuildBackup {
+    version: u32,
+    #[serde(default)]
+    prefixes: Vec<String>,
+    #[serde(default)]
+    custom_commands: Vec<CustomCommandBackup>,
+    #[serde(default)]
+    text_channels: TextChannelBackup,
+    starboard_threshold: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CustomCommandBackup {
+    name: String,
+    #[serde(default)]
+    content: String,
+    #[serde(default = "default_kind")]
+    kind: String,
+    embed: Option<String>,
+    image_url: Option<String>,
+    #[serde(default)]
+    reply: bool,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    requirements: Vec<RequirementBackup>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RequirementBackup {
+    kind: String,
+    value: u64,
+}
+
+// The quote channel is also where starboard messages go
+#[derive(Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct TextChannelBackup {
+    nice: Option<u64>,
+    bruh: Option<u64>,
+    quote: Option<u64>,
+    mod_log: Option<u64>,
+}
+
+fn default_kind() -> String {
+    CustomKind::Text.to_string()
+}
+
+/// Exports the server's configuration as a JSON file
+/// Usage: `export`
+#[command]
+#[required_permissions("ADMINISTRATOR")]
+async fn export(ctx: &Context, msg: &Message) -> CommandResult {
+    export_internal(ctx, &CommandSource::Prefix(msg)).await
+}
+
+async fn export_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    let guild_id = source.guild_id().unwrap();
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+
+    let backup = fetch_backup(repository.as_ref(), guild_id).await?;
+
+    source
+        .send_file(
+            ctx,
+            "Here's the server's configuration! Use the import command to load it in any server.",
+            get_backup_file(&backup, guild_id, "config")?,
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Replaces the server's configuration with an exported JSON file
+/// Usage: `import` with the file attached
+#[command]
+#[required_permissions("ADMINISTRATOR")]
+async fn import(ctx: &Context, msg: &Message) -> CommandResult {
+    let attachment = match msg.attachments.first() {
+        Some(attachment) => attachment.to_owned(),
+        None => {
+            msg.channel_id
+                .say(
+                    ctx,
+                    "Please attach a configuration file from the export command!",
+                )
+                .await?;
+            return Ok(());
+        }
+    };
+
+    import_internal(ctx, &CommandSource::Prefix(msg), attachment).await
+}
+
+async fn import_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    attachment: Attachment,
+) -> CommandResult {
+    let guild_id = source.guild_id().unwrap();
+
+    if attachment.size > MAX_BACKUP_SIZE {
+        source
+            .say(ctx, "This file is too big to be a configuration file!")
+            .await?;
+        return Ok(());
+    }
+
+    let data = attachment.download().await?;
+
+    let backup: GuildBackup = match serde_json::from_slice(&data) {
+        Ok(backup) => backup,
+        Err(e) => {
+            source
+                .say(ctx, format!("This configuration file is invalid! ({})", e))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let skipped = match validate_backup(ctx, guild_id, &backup).await? {
+        Ok(skipped) => skipped,
+        Err(reason) => {
+            source
+                .say(
+                    ctx,
+                    format!(
+                        "This configuration file is invalid! Nothing was changed. \n{}",
+                        reason
+                    ),
+                )
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+
+    // Keep the old configuration in case the import was a mistake
+    let old_backup = fetch_backup(repository.as_ref(), guild_id).await?;
+
+    apply_backup(ctx, guild_id, &backup, &skipped).await?;
+
+    let mut content = format!(
+        "Imported {} prefixes and {} custom commands! The old configuration is attached.",
+        backup.prefixes.len(),
+        backup.custom_commands.len()
+    );
+
+    if !skipped.is_empty() {
+        let skipped_list = skipped
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        content.push_str(&format!(
+            "\nThese channels and roles aren't in this server and were skipped: {}",
+            skipped_list
+        ));
+    }
+
+    source
+        .send_file(
+            ctx,
+            content,
+            get_backup_file(&old_backup, guild_id, "old-config")?,
+        )
+        .await?;
+
+    Ok(())
+}
+
+fn get_backup_file(
+    backup: &GuildBackup,
+    guild_id: GuildId,
+    name: &str,
+) -> CommandResult<AttachmentType<'static>> {
+    let data = serde_json::to_vec_pretty(backup)?;
+
+    Ok(AttachmentType::Bytes {
+        data: Cow::Owned(data),
+        filename: format!("{}-{}.json", name, guild_id.0),
+    })
+}
+
+async fn fetch_backup(
+    repository: &dyn Repository,
+    guild_id: GuildId,
+) -> CommandResult<GuildBackup> {
+    let prefixes = repository.fetch_prefixes(guild_id).await?;
+    let alias_data = repository.fetch_aliases(guild_id).await?;
+
+    let mut custom_commands = Vec::new();
+
+    for x in repository.fetch_commands(guild_id).await? {
+        let requirements = repository
+            .fetch_requirements(guild_id, &x.name)
+            .await?
+            .into_iter()
+            .map(|requirement| RequirementBackup {
+                kind: requirement.kind().to_owned(),
+                value: requirement.value() as u64,
+            })
+            .collect();
+
+        custom_commands.push(CustomCommandBackup {
+            aliases: alias_data
+                .iter()
+                .filter(|(_, name)| *name == x.name)
+                .map(|(alias, _)| alias.to_owned())
+                .collect(),
+            requirements,
+            name: x.name,
+            content: x.content.unwrap_or_default(),
+            kind: x.kind,
+            embed: x.embed,
+            image_url: x.image_url,
+            reply: x.reply,
+        });
+    }
+
+    let text_channels = match repository.fetch_sender_channels(guild_id).await? {
+        Some(channels) => TextChannelBackup {
+            nice: channels.nice.map(|x| x.0),
+            bruh: channels.bruh.map(|x| x.0),
+            quote: channels.quote.map(|x| x.0),
+            mod_log: channels.mod_log.map(|x| x.0),
+        },
+        None => TextChannelBackup::default(),
+    };
+
+    let starboard_threshold = repository.fetch_starboard_threshold(guild_id).await?;
+
+    Ok(GuildBackup {
+        version: BACKUP_VERSION,
+        prefixes,
+        custom_commands,
+        text_channels,
+        starboard_threshold,
+    })
+}
+
+/*
+ * Checks the whole file before anything is changed
+ * Channels and roles from other servers can't be used here, so they're returned to be skipped
+ */
+async fn validate_backup(
+    ctx: &Context,
+    guild_id: GuildId,
+    backup: &GuildBackup,
+) -> CommandResult<Result<Vec<u64>, String>> {
+    let command_names = ctx
+        .data
+        .read()
+        .await
+        .get::<CommandNameMap>()
+        .cloned()
+        .unwrap();
+
+    if let Err(reason) = check_backup(backup, &command_names) {
+        return Ok(Err(reason));
+    }
+
+    let channels = guild_id.channels(ctx).await?;
+    let roles = guild_id.roles(ctx).await?;
+
+    Ok(Ok(get_skipped_ids(
+        backup,
+        |channel_id| channels.contains_key(&channel_id),
+        |role_id| roles.contains_key(&role_id),
+    )))
+}
+
+// Everything that can be checked without looking at the server
+fn check_backup(backup: &GuildBackup, command_names: &[String]) -> Result<(), String> {
+    if backup.version == 0 || backup.version > BACKUP_VERSION {
+        return Err(format!(
+            "Configuration version {} isn't supported!",
+            backup.version
+        ));
+    }
+
+    if backup.prefixes.len() > MAX_PREFIXES {
+        return Err(format!(
+            "A server can only have up to {} prefixes!",
+            MAX_PREFIXES
+        ));
+    }
+
+    for prefix in &backup.prefixes {
+        if let Err(reason) = validate_prefix(prefix) {
+            return Err(format!("Prefix `{}`: {}", prefix, reason));
+        }
+    }
+
+    if let Some(threshold) = backup.starboard_threshold {
+        if threshold < 1 {
+            return Err("The starboard threshold must be at least 1!".to_owned());
+        }
+    }
+
+    let mut taken_names = HashSet::new();
+
+    for command in &backup.custom_commands {
+        let mut names = vec![&command.name];
+        names.extend(&command.aliases);
+
+        for name in names {
+            if name.is_empty() || name.chars().any(char::is_whitespace) {
+                return Err(format!("`{}` isn't a valid command name!", name));
+            }
+
+            if command_names.contains(name) {
+                return Err(format!("`{}` is already a hardcoded command!", name));
+            }
+
+            if !taken_names.insert(name.to_owned()) {
+                return Err(format!("`{}` is used more than once!", name));
+            }
+        }
+
+        if let Err(reason) = validate_command(command) {
+            return Err(format!("Command `{}`: {}", command.name, reason));
+        }
+    }
+
+    Ok(())
+}
+
+// IDs of the channels and roles that don't exist in the server
+fn get_skipped_ids(
+    backup: &GuildBackup,
+    channel_exists: impl Fn(ChannelId) -> bool,
+    role_exists: impl Fn(RoleId) -> bool,
+) -> Vec<u64> {
+    let text_channels = &backup.text_channels;
+
+    let mut skipped = [
+        text_channels.nice,
+        text_channels.bruh,
+        text_channels.quote,
+        text_channels.mod_log,
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|x| !channel_exists(ChannelId(*x)))
+    .collect::<Vec<u64>>();
+
+    for command in &backup.custom_commands {
+        for requirement in &command.requirements {
+            // Requirement types were checked with the rest of the file
+            let exists = match Requirement::from_row(&requirement.kind, requirement.value as i64) {
+                Some(Requirement::Role(role_id)) => role_exists(role_id),
+                Some(Requirement::Channel(channel_id)) => channel_exists(channel_id),
+                Some(Requirement::Permission(_)) | None => true,
+            };
+
+            if !exists {
+                skipped.push(requirement.value);
+            }
+        }
+    }
+
+    skipped
+}
+
+fn validate_command(command: &CustomCommandBackup) -> Result<(), String> {
+    Template::parse(&command.content)?;
+
+    if let Some(requirement) = command
+        .requirements
+        .iter()
+        .find(|x| Requirement::from_row(&x.kind, x.value as i64).is_none())
+    {
+        return Err(format!("`{}` isn't a requirement type!", requirement.kind));
+    }
+
+    match command.kind.parse::<CustomKind>()? {
+        CustomKind::Text => Ok(()),
+        CustomKind::Embed => match &command.embed {
+            Some(embed) => EmbedTemplate::parse(embed).map(|_| ()),
+            None => Err("Embed commands need an embed!".to_owned()),
+        },
+        CustomKind::Image => match &command.image_url {
+            Some(image_url) => custom_commands::validate_url(image_url),
+            None => Err("Image commands need an image link!".to_owned()),
+        },
+    }
+}
+
+/*
+ * Everything is replaced at once, so a failed import changes nothing
+ * The cached settings are only invalidated once the restore succeeds
+ */
+async fn apply_backup(
+    ctx: &Context,
+    guild_id: GuildId,
+    backup: &GuildBackup,
+    skipped: &[u64],
+) -> CommandResult {
+    let (repository, default_prefix) = {
+        let data = ctx.data.read().await;
+
+        let repository = data.get::<RepositoryContainer>().cloned().unwrap();
+        let default_prefix = data
+            .get::<PubCreds>()
+            .unwrap()
+            .get("default prefix")
+            .cloned()
+            .unwrap();
+
+        (repository, default_prefix)
+    };
+
+    let config = build_config(backup, skipped, &default_prefix);
+
+    repository.restore_guild(guild_id, &config).await?;
+
+    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+    Ok(())
+}
+
+// Turns a validated backup into the configuration that's restored
+fn build_config(backup: &GuildBackup, skipped: &[u64], default_prefix: &str) -> GuildConfig {
+    let mut prefixes: Vec<String> = Vec::new();
+
+    for prefix in &backup.prefixes {
+        let prefix = prefix.to_lowercase();
+
+        if !prefixes.contains(&prefix) {
+            prefixes.push(prefix);
+        }
+    }
+
+    if prefixes == [default_prefix.to_lowercase()] {
+        prefixes.clear();
+    }
+
+    let get_channel =
+        |channel_id: Option<u64>| channel_id.filter(|x| !skipped.contains(x)).map(ChannelId);
+
+    let commands = backup
+        .custom_commands
+        .iter()
+        .map(|command| CommandConfig {
+            command: CommandRow {
+                name: command.name.to_owned(),
+                content: Some(command.content.to_owned()),
+                kind: command.kind.to_owned(),
+                embed: command.embed.to_owned(),
+                image_url: command.image_url.to_owned(),
+                reply: command.reply,
+            },
+            aliases: command.aliases.to_owned(),
+            // Requirement types were checked when the backup was validated
+            requirements: command
+                .requirements
+                .iter()
+                .filter(|x| !skipped.contains(&x.value))
+                .filter_map(|x| Requirement::from_row(&x.kind, x.value as i64))
+                .collect(),
+        })
+        .collect();
+
+    GuildConfig {
+        prefixes,
+        commands,
+        sender_channels: SenderChannels {
+            nice: get_channel(backup.text_channels.nice),
+            bruh: get_channel(backup.text_channels.bruh),
+            quote: get_channel(backup.text_channels.quote),
+            mod_log: get_channel(backup.text_channels.mod_log),
+        },
+        starboard_threshold: backup.starboard_threshold,
+    }
+}
+
+pub fn backup_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "backup")
+}
+
+pub fn create_backup_commands(commands: &mut CreateApplicationCommands) {
+    commands.create_application_command(|c| {
+        c.name("export")
+            .description("Exports the server's configuration as a JSON file")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+    });
+
+    commands.create_application_command(|c| {
+        c.name("import")
+            .description("Replaces the server's configuration with an exported file")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .create_option(|o| {
+                o.name("file")
+                    .description("The configuration file from the export command")
+                    .kind(CommandOptionType::Attachment)
+                    .required(true)
+            })
+    });
+}
+
+pub async fn backup_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+
+    match command.data.name.as_str() {
+        "export" => export_internal(ctx, &source).await,
+        "import" => match interaction_utils::get_attachment_option(&command.data.options, "file") {
+            Some(attachment) => import_internal(ctx, &source, attachment).await,
+            None => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::{memory::MemoryRepository, SenderKind};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    const GUILD: GuildId = GuildId(1);
+    const OTHER_GUILD: GuildId = GuildId(2);
+
+    fn command_names() -> Vec<String> {
+        vec!["ping".to_owned(), "help".to_owned()]
+    }
+
+    // Typed like the bot's repository, so only the Repository trait is needed
+    fn new_repository() -> Arc<dyn Repository> {
+        Arc::new(MemoryRepository::new())
+    }
+
+    fn parse_backup(value: serde_json::Value) -> GuildBackup {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn exported_backups_import_into_another_guild() {
+        let repository = new_repository();
+        repository.add_guild(GUILD).await.unwrap();
+        repository.add_guild(OTHER_GUILD).await.unwrap();
+
+        repository
+            .set_prefixes(GUILD, &["!".to_owned(), "?".to_owned()])
+            .await
+            .unwrap();
+        repository
+            .save_command(GUILD, "hello", "hi {user}", CustomKind::Text, None, None)
+            .await
+            .unwrap();
+        repository.save_alias(GUILD, "hey", "hello").await.unwrap();
+        repository.set_reply(GUILD, "hello", true).await.unwrap();
+        repository
+            .add_requirement(GUILD, "hello", Requirement::Role(RoleId(5)))
+            .await
+            .unwrap();
+        repository
+            .set_sender_channel(GUILD, SenderKind::Quote, Some(ChannelId(7)))
+            .await
+            .unwrap();
+        repository
+            .set_starboard_threshold(GUILD, Some(3))
+            .await
+            .unwrap();
+
+        let exported =
+            serde_json::to_string(&fetch_backup(repository.as_ref(), GUILD).await.unwrap())
+                .unwrap();
+        let backup = serde_json::from_str::<GuildBackup>(&exported).unwrap();
+
+        assert_eq!(check_backup(&backup, &command_names()), Ok(()));
+        assert!(get_skipped_ids(&backup, |_| true, |_| true).is_empty());
+
+        repository
+            .restore_guild(OTHER_GUILD, &build_config(&backup, &[], "j!"))
+            .await
+            .unwrap();
+
+        let imported = serde_json::to_string(
+            &fetch_backup(repository.as_ref(), OTHER_GUILD)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(imported, exported);
+        assert_eq!(
+            repository.resolve_name(OTHER_GUILD, "hey").await.unwrap(),
+            Some("hello".to_owned())
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_channels_and_roles_are_skipped() {
+        let repository = new_repository();
+        repository.add_guild(GUILD).await.unwrap();
+
+        let backup = parse_backup(json!({
+            "version": 1,
+            "custom_commands": [{
+                "name": "hello",
+                "content": "hi",
+                "requirements": [
+                    { "kind": "role", "value": 5 },
+                    { "kind": "channel", "value": 9 },
+                    { "kind": "permission", "value": 8192 },
+                ],
+            }],
+            "text_channels": { "quote": 7, "mod_log": 8 },
+        }));
+
+        let skipped = get_skipped_ids(&backup, |x| x == ChannelId(7), |_| false);
+        assert_eq!(skipped, [8, 5, 9]);
+
+        repository
+            .restore_guild(GUILD, &build_config(&backup, &skipped, "j!"))
+            .await
+            .unwrap();
+
+        let channels = repository
+            .fetch_sender_channels(GUILD)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(channels.quote, Some(ChannelId(7)));
+        assert_eq!(channels.mod_log, None);
+        assert_eq!(
+            repository.fetch_requirements(GUILD, "hello").await.unwrap(),
+            [Requirement::Permission(Permissions::MANAGE_MESSAGES)]
+        );
+    }
+
+    #[tokio::test]
+    async fn only_the_default_prefix_is_not_saved() {
+        let repository = new_repository();
+        repository.add_guild(GUILD).await.unwrap();
+        repository
+            .set_prefixes(GUILD, &["?".to_owned()])
+            .await
+            .unwrap();
+
+        let backup = parse_backup(json!({ "version": 1, "prefixes": ["J!", "j!"] }));
+
+        repository
+            .restore_guild(GUILD, &build_config(&backup, &[], "j!"))
+            .await
+            .unwrap();
+
+        assert!(repository.fetch_prefixes(GUILD).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid_backups_are_rejected() {
+        let invalid = [
+            (json!({ "version": 2 }), "version 2"),
+            (
+                json!({ "version": 1, "prefixes": ["a", "b", "c", "d", "e", "f"] }),
+                "prefixes",
+            ),
+            (
+                json!({ "version": 1, "starboard_threshold": 0 }),
+                "starboard",
+            ),
+            (
+                json!({ "version": 1, "custom_commands": [{ "name": "ping" }] }),
+                "hardcoded",
+            ),
+            (
+                json!({ "version": 1, "custom_commands": [
+                    { "name": "hello" },
+                    { "name": "hi", "aliases": ["hello"] },
+                ] }),
+                "more than once",
+            ),
+            (
+                json!({ "version": 1, "custom_commands": [
+                    { "name": "hello", "requirements": [{ "kind": "mood", "value": 1 }] },
+                ] }),
+                "requirement type",
+            ),
+            (
+                json!({ "version": 1, "custom_commands": [{ "name": "hello", "kind": "image" }] }),
+                "image link",
+            ),
+        ];
+
+        for (value, reason) in invalid {
+            let error = check_backup(&parse_backup(value), &command_names()).unwrap_err();
+            assert!(error.contains(reason), "{}", error);
+        }
+    }
+}
@@ -1,72 +1,128 @@
 use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
     framework::standard::{macros::command, Args, CommandResult},
-    model::prelude::*,
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        prelude::*,
+    },
     prelude::*,
 };
 
-use crate::JesterError;
+use crate::{
+    helpers::{interaction_utils, locale},
+    structures::command_source::CommandSource,
+    JesterError,
+};
 
 /// Encodes a message in base64
 /// Usage `b64encode <message>`
 #[command]
 async fn b64encode(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
-    let b64_string = base64::encode(args.rest());
-
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("Base64 Engine");
-                e.description(format!("Encoded Message: `{}`", b64_string));
-                e
-            })
-        })
-        .await?;
-    Ok(())
+    b64encode_internal(ctx, &CommandSource::Prefix(msg), args.rest()).await
 }
 
 /// Decodes a message in base64
 /// Usage `b64encode <message>`
 #[command]
 async fn b64decode(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
-    let b64_bytes = match base64::decode(args.rest()) {
-        Ok(bytes) => bytes,
-        Err(_error) => {
-            msg.channel_id
-                .say(ctx, JesterError::MissingError("base64 string"))
+    b64decode_internal(ctx, &CommandSource::Prefix(msg), args.rest()).await
+}
+
+async fn b64encode_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    input: &str,
+) -> CommandResult {
+    let b64_string = base64::encode(input);
+
+    source
+        .send_embed(
+            ctx,
+            get_cipher_embed(format!("Encoded Message: `{}`", b64_string)),
+        )
+        .await?;
+
+    Ok(())
+}
+
+async fn b64decode_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    input: &str,
+) -> CommandResult {
+    let decoded_string = match base64::decode(input).map(String::from_utf8) {
+        Ok(Ok(decoded_string)) => decoded_string,
+        _ => {
+            let locale = locale::get_guild_locale(ctx, source.guild_id()).await;
+
+            source
+                .say(ctx, JesterError::MissingError("base64").localized(&locale))
                 .await?;
             return Ok(());
         }
     };
 
-    let decoded_string = String::from_utf8(b64_bytes).unwrap();
-
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("Base64 Engine");
-                e.description(format!("Decoded Message: `{}`", decoded_string));
-                e
-            })
-        })
+    source
+        .send_embed(
+            ctx,
+            get_cipher_embed(format!("Decoded Message: `{}`", decoded_string)),
+        )
         .await?;
 
     Ok(())
 }
 
-pub async fn cipher_help(ctx: &Context, channel_id: ChannelId) {
-    let content = concat!(
-        "b64encode <message>: Encodes a message in base64 \n\n",
-        "b64decode <b64 string>: Decodes a base64 message"
-    );
-
-    let _ = channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("Cipher Help");
-                e.description("Description: Encoding/Decoding messages");
-                e.field("Commands", content, false);
-                e
+fn get_cipher_embed(description: String) -> CreateEmbed {
+    let mut eb = CreateEmbed::default();
+
+    eb.title("Base64 Engine");
+    eb.description(description);
+
+    eb
+}
+
+pub fn create_cipher_commands(commands: &mut CreateApplicationCommands) {
+    commands.create_application_command(|c| {
+        c.name("b64encode")
+            .description("Encodes a message in base64")
+            .create_option(|o| {
+                o.name("message")
+                    .description("The message to encode")
+                    .kind(CommandOptionType::String)
+                    .required(true)
             })
-        })
-        .await;
+    });
+
+    commands.create_application_command(|c| {
+        c.name("b64decode")
+            .description("Decodes a base64 message")
+            .create_option(|o| {
+                o.name("message")
+                    .description("The base64 string to decode")
+                    .kind(CommandOptionType::String)
+                    .required(true)
+            })
+    });
+}
+
+pub async fn cipher_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+    let input =
+        interaction_utils::get_string_option(&command.data.options, "message").unwrap_or_default();
+
+    match command.data.name.as_str() {
+        "b64encode" => b64encode_internal(ctx, &source, &input).await,
+        "b64decode" => b64decode_internal(ctx, &source, &input).await,
+        _ => Ok(()),
+    }
+}
+
+pub fn cipher_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "ciphers")
 }
@@ -1,25 +1,55 @@
 use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
     framework::standard::{macros::command, Args, CommandResult},
-    model::prelude::*,
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::{ApplicationCommandInteraction, CommandDataOption},
+        },
+        prelude::*,
+    },
     prelude::*,
+    utils::{parse_channel, parse_role},
 };
-use std::sync::Arc;
 
 use crate::{
-    helpers::database_helper, helpers::permissions_helper, CommandNameMap, ConnectionPool,
-    PrefixMap, PubCreds,
+    helpers::{
+        command_utils,
+        custom_commands::{self, CustomKind, EmbedTemplate, Requirement},
+        guild_settings, interaction_utils, locale, permissions_helper,
+        templates::Template,
+    },
+    structures::command_source::CommandSource,
+    CommandNameMap, GuildSettingsMap, PubCreds, RepositoryContainer,
 };
 
-/// Sets the prefix for the server using the first message argument
-/// Execute this command with no arguments to get the current prefix
+// Limits for custom prefixes
+pub const MAX_PREFIXES: usize = 5;
+const MAX_PREFIX_LENGTH: usize = 10;
+
+/// Sets the prefixes for the server using the message arguments
+/// Execute this command with no arguments to get the current prefixes
 #[command]
 #[sub_commands(restore)]
 async fn prefix(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let (pool, prefixes, default_prefix) = {
+    let new_prefixes = args
+        .quoted()
+        .iter::<String>()
+        .filter_map(|x| x.ok())
+        .collect::<Vec<String>>();
+
+    prefix_internal(ctx, &CommandSource::Prefix(msg), new_prefixes).await
+}
+
+async fn prefix_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    new_prefixes: Vec<String>,
+) -> CommandResult {
+    let (repository, default_prefix) = {
         let data = ctx.data.read().await;
 
-        let pool = data.get::<ConnectionPool>().cloned().unwrap();
-        let prefixes = data.get::<PrefixMap>().unwrap().clone();
+        let repository = data.get::<RepositoryContainer>().cloned().unwrap();
         let default_prefix = data
             .get::<PubCreds>()
             .unwrap()
@@ -27,71 +57,134 @@ async fn prefix(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
             .cloned()
             .unwrap();
 
-        (pool, prefixes, default_prefix)
+        (repository, default_prefix)
     };
-    let guild_id = msg.guild_id.unwrap();
-    let guild_name = msg.guild(ctx).unwrap().name;
+    let guild_id = source.guild_id().unwrap();
+    let guild_name = guild_id.name(ctx).unwrap_or_default();
 
-    if args.is_empty() {
-        let cur_prefix = match prefixes.get(&guild_id) {
-            Some(prefix_guard) => prefix_guard.value().to_owned(),
-            None => default_prefix,
-        };
+    if new_prefixes.is_empty() {
+        let cur_prefixes = command_utils::get_guild_prefixes(ctx, Some(guild_id)).await;
 
-        msg.channel_id
+        source
             .say(
                 ctx,
-                format!("My prefix for `{}` is `{}`", guild_name, cur_prefix),
+                format!(
+                    "My prefixes for `{}` are {}",
+                    guild_name,
+                    format_prefixes(&cur_prefixes)
+                ),
             )
             .await?;
         return Ok(());
     }
 
-    if !permissions_helper::check_permission(ctx, msg, None, false).await? {
+    if !permissions_helper::check_permission(ctx, source, None, false).await? {
         return Ok(());
     }
 
-    let new_prefix = args.single::<String>().unwrap();
+    // Prefixes are case insensitive, so they're stored in lowercase
+    let mut checked_prefixes: Vec<String> = Vec::new();
 
-    if new_prefix == default_prefix {
-        sqlx::query!(
-            "UPDATE guild_info SET prefix = null WHERE guild_id = $1",
-            guild_id.0 as i64
-        )
-        .execute(&pool)
-        .await?;
+    for new_prefix in new_prefixes {
+        if let Err(reason) = validate_prefix(&new_prefix) {
+            source.say(ctx, reason).await?;
+            return Ok(());
+        }
 
-        prefixes.remove(&guild_id);
-    } else {
-        sqlx::query!(
-            "UPDATE guild_info SET prefix = $1 WHERE guild_id = $2",
-            new_prefix,
-            guild_id.0 as i64
-        )
-        .execute(&pool)
-        .await?;
+        let new_prefix = new_prefix.to_lowercase();
 
-        prefixes.insert(guild_id, new_prefix.to_owned());
+        if !checked_prefixes.contains(&new_prefix) {
+            checked_prefixes.push(new_prefix);
+        }
     }
 
-    msg.channel_id
+    if checked_prefixes.len() > MAX_PREFIXES {
+        source
+            .say(
+                ctx,
+                format!("A server can only have up to {} prefixes!", MAX_PREFIXES),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    if checked_prefixes == [default_prefix.to_lowercase()] {
+        repository.set_prefixes(guild_id, &[]).await?;
+    } else {
+        repository.set_prefixes(guild_id, &checked_prefixes).await?;
+    }
+
+    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+    source
         .say(
             ctx,
-            format!("My new prefix is `{}` for `{}`!", new_prefix, guild_name),
+            format!(
+                "My new prefixes are {} for `{}`!",
+                format_prefixes(&checked_prefixes),
+                guild_name
+            ),
         )
         .await?;
 
     Ok(())
 }
 
+/*
+ * Prefixes can't contain spaces since commands are split by whitespace
+ * Anything that looks like a mention, channel, or emoji would ping or break the parser
+ */
+pub fn validate_prefix(prefix: &str) -> Result<(), String> {
+    if prefix.is_empty() {
+        return Err("A prefix can't be empty!".to_owned());
+    }
+
+    if prefix.chars().count() > MAX_PREFIX_LENGTH {
+        return Err(format!(
+            "Prefixes can only be up to {} characters long!",
+            MAX_PREFIX_LENGTH
+        ));
+    }
+
+    if prefix.chars().any(char::is_whitespace) {
+        return Err("Prefixes can't contain any spaces!".to_owned());
+    }
+
+    if prefix.contains('`') {
+        return Err("Prefixes can't contain backticks!".to_owned());
+    }
+
+    let lowercase_prefix = prefix.to_lowercase();
+    let mention_like = ["<@", "<#", "<:", "<a:", "<t:", "@everyone", "@here"]
+        .iter()
+        .any(|x| lowercase_prefix.contains(x));
+
+    if mention_like {
+        return Err("Prefixes can't look like mentions, channels, or emojis!".to_owned());
+    }
+
+    Ok(())
+}
+
+fn format_prefixes(prefixes: &[String]) -> String {
+    prefixes
+        .iter()
+        .map(|x| format!("`{}`", x))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
 #[command]
 #[required_permissions("ADMINISTRATOR")]
 async fn resetprefix(ctx: &Context, msg: &Message) -> CommandResult {
-    let (pool, prefixes, default_prefix) = {
+    resetprefix_internal(ctx, &CommandSource::Prefix(msg)).await
+}
+
+async fn resetprefix_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    let (repository, default_prefix) = {
         let data = ctx.data.read().await;
 
-        let pool = data.get::<ConnectionPool>().cloned().unwrap();
-        let prefixes = data.get::<PrefixMap>().unwrap().clone();
+        let repository = data.get::<RepositoryContainer>().cloned().unwrap();
         let default_prefix = data
             .get::<PubCreds>()
             .unwrap()
@@ -99,23 +192,16 @@ async fn resetprefix(ctx: &Context, msg: &Message) -> CommandResult {
             .cloned()
             .unwrap();
 
-        (pool, prefixes, default_prefix)
+        (repository, default_prefix)
     };
 
-    let guild_id = msg.guild_id.unwrap();
+    let guild_id = source.guild_id().unwrap();
 
-    if prefixes.contains_key(&guild_id) {
-        prefixes.remove(&guild_id);
+    repository.set_prefixes(guild_id, &[]).await?;
 
-        sqlx::query!(
-            "UPDATE guild_info SET prefix = null WHERE guild_id = $1",
-            guild_id.0 as i64
-        )
-        .execute(&pool)
-        .await?;
-    }
+    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
 
-    msg.channel_id
+    source
         .say(ctx, format!("Reset the prefix back to {}", default_prefix))
         .await?;
 
@@ -125,56 +211,137 @@ async fn resetprefix(ctx: &Context, msg: &Message) -> CommandResult {
 #[command]
 #[owners_only(true)]
 async fn restore(ctx: &Context, msg: &Message) -> CommandResult {
-    let pool = ctx
+    // Every guild's settings are loaded from the database again on their next use
+    ctx.data
+        .read()
+        .await
+        .get::<GuildSettingsMap>()
+        .cloned()
+        .unwrap()
+        .clear();
+
+    msg.channel_id
+        .say(ctx, "Prefixes successfully restored!")
+        .await?;
+
+    Ok(())
+}
+
+pub fn prefix_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "prefix")
+}
+
+/// Sets the language for the server's responses
+/// Execute this command with no arguments to get the current language
+#[command]
+async fn language(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let new_locale = args.single::<String>().ok();
+
+    language_internal(ctx, &CommandSource::Prefix(msg), new_locale).await
+}
+
+async fn language_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    new_locale: Option<String>,
+) -> CommandResult {
+    let repository = ctx
         .data
         .read()
         .await
-        .get::<ConnectionPool>()
+        .get::<RepositoryContainer>()
         .cloned()
         .unwrap();
+    let guild_id = source.guild_id().unwrap();
+    let guild_name = guild_id.name(ctx).unwrap_or_default();
+    let cur_locale = locale::get_guild_locale(ctx, Some(guild_id)).await;
 
-    {
-        let mut data = ctx.data.write().await;
-        let new_prefixes = database_helper::fetch_prefixes(&pool).await?;
+    let new_locale = match new_locale {
+        Some(new_locale) => new_locale.to_lowercase(),
+        None => {
+            source
+                .say(
+                    ctx,
+                    locale::get_formatted(
+                        &cur_locale,
+                        "language.current",
+                        &[
+                            ("guild", &guild_name),
+                            ("language", &locale::get_text(&cur_locale, "language.name")),
+                            ("languages", &locale::get_locale_list()),
+                        ],
+                    ),
+                )
+                .await?;
 
-        data.insert::<PrefixMap>(Arc::new(new_prefixes));
+            return Ok(());
+        }
+    };
+
+    if !permissions_helper::check_permission(ctx, source, None, true).await? {
+        return Ok(());
     }
 
-    msg.channel_id
-        .say(ctx, "Prefixes successfully restored!")
+    if !locale::is_supported(&new_locale) {
+        source
+            .say(
+                ctx,
+                locale::get_formatted(
+                    &cur_locale,
+                    "language.unknown",
+                    &[
+                        ("code", &new_locale),
+                        ("languages", &locale::get_locale_list()),
+                    ],
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    // The default locale is stored as NULL so it follows the bot's default
+    let stored_locale = (new_locale != locale::DEFAULT_LOCALE).then(|| new_locale.clone());
+
+    repository
+        .set_locale(guild_id, stored_locale.as_deref())
+        .await?;
+
+    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+    source
+        .say(
+            ctx,
+            locale::get_formatted(
+                &new_locale,
+                "language.set",
+                &[
+                    ("guild", &guild_name),
+                    ("language", &locale::get_text(&new_locale, "language.name")),
+                ],
+            ),
+        )
         .await?;
 
     Ok(())
 }
 
-pub async fn prefix_help(ctx: &Context, channel_id: ChannelId) {
-    let content = concat!(
-        "prefix: Gets the server's current prefix \n\n",
-        "prefix (characters): Sets the server's prefix (Can be one or multiple characters)"
-    );
-
-    let _ = channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("Custom Prefix Help");
-                e.description("Description: Commands for custom bot prefixes");
-                e.field("Commands", content, false);
-                e
-            })
-        })
-        .await;
+pub fn language_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "language")
 }
 
 /// Custom commands for your server that output a message
 /// Usage to set: `command set <name> <content to be said>`
 /// Usage to remove: `command remove <name>`
 #[command]
-#[sub_commands(set, remove, list)]
+#[sub_commands(
+    set, embed, image, reply, alias, unalias, require, unrequire, remove, list
+)]
 async fn command(ctx: &Context, msg: &Message) -> CommandResult {
     msg.channel_id
         .say(
             ctx,
-            "Please use one of the subcommands! (set, remove, list)",
+            "Please use one of the subcommands! (set, embed, image, reply, alias, unalias, require, unrequire, remove, list)",
         )
         .await?;
 
@@ -188,16 +355,148 @@ async fn command(ctx: &Context, msg: &Message) -> CommandResult {
 #[min_args(2)]
 async fn set(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let command_name = args.single::<String>().unwrap();
-    let (pool, command_names) = {
+
+    set_internal(ctx, &CommandSource::Prefix(msg), command_name, args.rest()).await
+}
+
+async fn set_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    command_name: String,
+    content: &str,
+) -> CommandResult {
+    if let Err(reason) = Template::parse(content) {
+        source
+            .say(
+                ctx,
+                format!("There's a problem with the command: {}", reason),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    save_internal(
+        ctx,
+        source,
+        &command_name,
+        content,
+        CustomKind::Text,
+        None,
+        None,
+    )
+    .await
+}
+
+// Subcommand to set a custom command that responds with an embed
+#[command]
+#[required_permissions("MANAGE_MESSAGES")]
+#[min_args(2)]
+async fn embed(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let command_name = args.single::<String>().unwrap();
+
+    embed_internal(ctx, &CommandSource::Prefix(msg), command_name, args.rest()).await
+}
+
+async fn embed_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    command_name: String,
+    json: &str,
+) -> CommandResult {
+    // Discord users usually paste JSON inside a code block
+    let json = json
+        .trim()
+        .trim_start_matches("```json")
+        .trim_matches('`')
+        .trim();
+
+    if let Err(reason) = EmbedTemplate::parse(json) {
+        source
+            .say(ctx, format!("There's a problem with the embed: {}", reason))
+            .await?;
+        return Ok(());
+    }
+
+    save_internal(
+        ctx,
+        source,
+        &command_name,
+        "",
+        CustomKind::Embed,
+        Some(json),
+        None,
+    )
+    .await
+}
+
+// Subcommand to set a custom command that responds with an image
+#[command]
+#[required_permissions("MANAGE_MESSAGES")]
+#[min_args(2)]
+async fn image(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let command_name = args.single::<String>().unwrap();
+    let image_url = args.single::<String>().unwrap();
+
+    image_internal(
+        ctx,
+        &CommandSource::Prefix(msg),
+        command_name,
+        image_url,
+        args.rest(),
+    )
+    .await
+}
+
+async fn image_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    command_name: String,
+    image_url: String,
+    caption: &str,
+) -> CommandResult {
+    if let Err(reason) =
+        custom_commands::validate_url(&image_url).and_then(|_| Template::parse(caption))
+    {
+        source
+            .say(
+                ctx,
+                format!("There's a problem with the command: {}", reason),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    save_internal(
+        ctx,
+        source,
+        &command_name,
+        caption,
+        CustomKind::Image,
+        None,
+        Some(&image_url),
+    )
+    .await
+}
+
+async fn save_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    command_name: &str,
+    content: &str,
+    kind: CustomKind,
+    embed: Option<&str>,
+    image_url: Option<&str>,
+) -> CommandResult {
+    let (repository, command_names) = {
         let data = ctx.data.read().await;
-        let pool = data.get::<ConnectionPool>().cloned().unwrap();
+        let repository = data.get::<RepositoryContainer>().cloned().unwrap();
         let command_names = data.get::<CommandNameMap>().cloned().unwrap();
 
-        (pool, command_names)
+        (repository, command_names)
     };
 
-    if command_names.contains(&command_name) {
-        msg.channel_id
+    if command_names.contains(&command_name.to_owned()) {
+        source
             .say(
                 ctx,
                 "This command is already hardcoded! Please choose a different name!",
@@ -206,47 +505,466 @@ async fn set(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
         return Ok(());
     }
 
-    let guild_id = msg.guild_id.unwrap().0 as i64;
+    let guild_id = source.guild_id().unwrap();
 
-    sqlx::query!(
-        "INSERT INTO commands(guild_id, name, content)
-            VALUES($1, $2, $3)
-            ON CONFLICT (guild_id, name)
-            DO UPDATE
-            SET content = EXCLUDED.content",
-        guild_id,
-        command_name,
-        args.rest()
-    )
-    .execute(&pool)
-    .await?;
+    if repository
+        .resolve_name(guild_id, command_name)
+        .await?
+        .is_some()
+    {
+        source
+            .say(
+                ctx,
+                "This name is already an alias! Please remove the alias or choose a different name!",
+            )
+            .await?;
+        return Ok(());
+    }
 
-    msg.channel_id
+    repository
+        .save_command(guild_id, command_name, content, kind, embed, image_url)
+        .await?;
+
+    source
         .say(ctx, format!("Command `{}` sucessfully set!", command_name))
         .await?;
 
     Ok(())
 }
 
+// Subcommand to toggle if a custom command replies to the user
+#[command]
+#[required_permissions("MANAGE_MESSAGES")]
+#[min_args(2)]
+async fn reply(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let command_name = args.single::<String>().unwrap();
+    let toggle = args.single::<String>().unwrap();
+
+    let enabled = match toggle.to_lowercase().as_str() {
+        "on" | "true" | "yes" | "enable" => true,
+        "off" | "false" | "no" | "disable" => false,
+        _ => {
+            msg.channel_id
+                .say(ctx, "Please use `on` or `off` for replies!")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    reply_internal(ctx, &CommandSource::Prefix(msg), command_name, enabled).await
+}
+
+async fn reply_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    command_name: String,
+    enabled: bool,
+) -> CommandResult {
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    if !repository
+        .set_reply(guild_id, &command_name, enabled)
+        .await?
+    {
+        source
+            .say(
+                ctx,
+                format!(
+                    "Command `{}` doesn't exist! Aliases can't be used here",
+                    command_name
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let state = if enabled {
+        "now replies"
+    } else {
+        "no longer replies"
+    };
+
+    source
+        .say(
+            ctx,
+            format!("Command `{}` {} to the user!", command_name, state),
+        )
+        .await?;
+
+    Ok(())
+}
+
+// Subcommand to add another name for a custom command
+#[command]
+#[required_permissions("MANAGE_MESSAGES")]
+#[min_args(2)]
+async fn alias(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let command_name = args.single::<String>().unwrap();
+    let alias = args.single::<String>().unwrap();
+
+    alias_internal(ctx, &CommandSource::Prefix(msg), command_name, alias).await
+}
+
+async fn alias_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    command_name: String,
+    alias: String,
+) -> CommandResult {
+    let (repository, command_names) = {
+        let data = ctx.data.read().await;
+        let repository = data.get::<RepositoryContainer>().cloned().unwrap();
+        let command_names = data.get::<CommandNameMap>().cloned().unwrap();
+
+        (repository, command_names)
+    };
+    let guild_id = source.guild_id().unwrap();
+
+    if command_names.contains(&alias) {
+        source
+            .say(
+                ctx,
+                "This alias is already a hardcoded command! Please choose a different alias!",
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let taken = repository.fetch_command(guild_id, &alias).await?;
+
+    if taken.is_some() {
+        source
+            .say(
+                ctx,
+                "This alias is already a custom command! Please choose a different alias!",
+            )
+            .await?;
+        return Ok(());
+    }
+
+    // Aliases of aliases point to the original command
+    let command_name = repository
+        .resolve_name(guild_id, &command_name)
+        .await?
+        .unwrap_or(command_name);
+
+    let exists = repository.fetch_command(guild_id, &command_name).await?;
+
+    if exists.is_none() {
+        source
+            .say(ctx, format!("Command `{}` doesn't exist!", command_name))
+            .await?;
+        return Ok(());
+    }
+
+    repository
+        .save_alias(guild_id, &alias, &command_name)
+        .await?;
+
+    source
+        .say(
+            ctx,
+            format!("`{}` is now an alias for `{}`!", alias, command_name),
+        )
+        .await?;
+
+    Ok(())
+}
+
+// Subcommand to remove an alias from a custom command
+#[command]
+#[required_permissions("MANAGE_MESSAGES")]
+#[min_args(1)]
+async fn unalias(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let alias = args.single::<String>().unwrap();
+
+    unalias_internal(ctx, &CommandSource::Prefix(msg), alias).await
+}
+
+async fn unalias_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    alias: String,
+) -> CommandResult {
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    if !repository.remove_alias(guild_id, &alias).await? {
+        source
+            .say(ctx, format!("Alias `{}` doesn't exist!", alias))
+            .await?;
+    } else {
+        source
+            .say(ctx, format!("Alias `{}` sucessfully removed!", alias))
+            .await?;
+    }
+
+    Ok(())
+}
+
+// Subcommand to limit who can use a custom command and where
+#[command]
+#[required_permissions("MANAGE_MESSAGES")]
+#[min_args(1)]
+async fn require(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let command_name = args.single::<String>().unwrap();
+    let source = CommandSource::Prefix(msg);
+
+    let requirement = match get_requirement_args(ctx, &source, &mut args).await? {
+        Ok(requirement) => requirement,
+        Err(reason) => {
+            msg.channel_id.say(ctx, reason).await?;
+            return Ok(());
+        }
+    };
+
+    require_internal(ctx, &source, command_name, requirement).await
+}
+
+async fn require_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    command_name: String,
+    requirement: Option<Requirement>,
+) -> CommandResult {
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    let command_name =
+        match custom_commands::fetch_custom_command(&*repository, guild_id, &command_name).await? {
+            Some(custom_command) => custom_command.name,
+            None => {
+                source
+                    .say(ctx, format!("Command `{}` doesn't exist!", command_name))
+                    .await?;
+                return Ok(());
+            }
+        };
+
+    // Without a requirement, show the current ones
+    let requirement = match requirement {
+        Some(requirement) => requirement,
+        None => {
+            let requirements = repository
+                .fetch_requirements(guild_id, &command_name)
+                .await?;
+
+            let description = if requirements.is_empty() {
+                "Anyone can use this command anywhere".to_owned()
+            } else {
+                requirements
+                    .iter()
+                    .map(|x| {
+                        format!(
+                            "{}: {}",
+                            x.kind(),
+                            custom_commands::format_requirement(ctx, x)
+                        )
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            };
+
+            let mut eb = CreateEmbed::default();
+
+            eb.title(format!("Requirements for {}", command_name));
+            eb.description(description);
+
+            source.send_embed(ctx, eb).await?;
+
+            return Ok(());
+        }
+    };
+
+    repository
+        .add_requirement(guild_id, &command_name, requirement)
+        .await?;
+
+    source
+        .say(
+            ctx,
+            format!(
+                "Command `{}` now requires the {} {}",
+                command_name,
+                requirement.kind(),
+                custom_commands::format_requirement(ctx, &requirement)
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+// Subcommand to remove one or all requirements from a custom command
+#[command]
+#[required_permissions("MANAGE_MESSAGES")]
+#[min_args(1)]
+async fn unrequire(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let command_name = args.single::<String>().unwrap();
+    let source = CommandSource::Prefix(msg);
+
+    let requirement = match get_requirement_args(ctx, &source, &mut args).await? {
+        Ok(requirement) => requirement,
+        Err(reason) => {
+            msg.channel_id.say(ctx, reason).await?;
+            return Ok(());
+        }
+    };
+
+    unrequire_internal(ctx, &source, command_name, requirement).await
+}
+
+async fn unrequire_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    command_name: String,
+    requirement: Option<Requirement>,
+) -> CommandResult {
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    let command_name = repository
+        .resolve_name(guild_id, &command_name)
+        .await?
+        .unwrap_or(command_name);
+
+    repository
+        .remove_requirement(guild_id, &command_name, requirement)
+        .await?;
+
+    match requirement {
+        Some(requirement) => {
+            source
+                .say(
+                    ctx,
+                    format!(
+                        "Command `{}` no longer requires the {} {}",
+                        command_name,
+                        requirement.kind(),
+                        custom_commands::format_requirement(ctx, &requirement)
+                    ),
+                )
+                .await?;
+        }
+        None => {
+            source
+                .say(
+                    ctx,
+                    format!("Anyone can now use command `{}` anywhere!", command_name),
+                )
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/*
+ * Parses requirement arguments in the form of <role/permission/channel> <value>
+ * Roles can be mentions, IDs, or names and permissions are their names
+ */
+async fn get_requirement_args(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    args: &mut Args,
+) -> CommandResult<Result<Option<Requirement>, String>> {
+    let kind = match args.single::<String>() {
+        Ok(kind) => kind.to_lowercase(),
+        Err(_) => return Ok(Ok(None)),
+    };
+    let value = args.rest().trim();
+
+    if value.is_empty() {
+        return Ok(Err(format!(
+            "Please provide a {} for the requirement!",
+            kind
+        )));
+    }
+
+    let requirement = match kind.as_str() {
+        "role" => {
+            let role_id = parse_role(value).or_else(|| value.parse::<u64>().ok());
+
+            let role_id = match role_id {
+                Some(role_id) => Some(RoleId(role_id)),
+                None => source
+                    .guild_id()
+                    .and_then(|guild_id| guild_id.to_guild_cached(ctx))
+                    .and_then(|guild| guild.role_by_name(value).map(|role| role.id)),
+            };
+
+            role_id.map(Requirement::Role)
+        }
+        "permission" => custom_commands::parse_permission(value).map(Requirement::Permission),
+        "channel" => parse_channel(value)
+            .or_else(|| value.parse::<u64>().ok())
+            .map(|channel_id| Requirement::Channel(ChannelId(channel_id))),
+        _ => {
+            return Ok(Err(
+                "Requirements can only be a role, permission, or channel!".to_owned(),
+            ))
+        }
+    };
+
+    match requirement {
+        Some(requirement) => Ok(Ok(Some(requirement))),
+        None => Ok(Err(format!("`{}` isn't a valid {}!", value, kind))),
+    }
+}
+
 // Subcommand used to remove a custom command
 #[command]
 #[required_permissions("MANAGE_MESSAGES")]
 #[min_args(1)]
 async fn remove(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let command_name = args.single::<String>().unwrap();
-    let data = ctx.data.read().await;
-    let pool = data.get::<ConnectionPool>().unwrap();
-    let guild_id = msg.guild_id.unwrap().0 as i64;
-
-    sqlx::query!(
-        "DELETE FROM commands WHERE guild_id = $1 AND name = $2",
-        guild_id,
-        command_name
-    )
-    .execute(pool)
-    .await?;
 
-    msg.channel_id
+    remove_internal(ctx, &CommandSource::Prefix(msg), command_name).await
+}
+
+async fn remove_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    command_name: String,
+) -> CommandResult {
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    // Aliases are removed with the command
+    repository.remove_command(guild_id, &command_name).await?;
+
+    source
         .say(
             ctx,
             format!("Command {} sucessfully deleted!", command_name),
@@ -258,58 +976,408 @@ async fn remove(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
 
 #[command]
 async fn list(ctx: &Context, msg: &Message) -> CommandResult {
-    let pool = ctx
+    list_internal(ctx, &CommandSource::Prefix(msg)).await
+}
+
+async fn list_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    let repository = ctx
         .data
         .read()
         .await
-        .get::<ConnectionPool>()
+        .get::<RepositoryContainer>()
         .cloned()
         .unwrap();
-    let guild_id = msg.guild_id.unwrap().0 as i64;
+    let guild_id = source.guild_id().unwrap();
     let mut command_map: Vec<String> = Vec::new();
 
-    let command_data = sqlx::query!(
-        "SELECT name, content FROM commands WHERE guild_id = $1",
-        guild_id
-    )
-    .fetch_all(&pool)
-    .await?;
+    let command_data = repository.fetch_commands(guild_id).await?;
+    let alias_data = repository.fetch_aliases(guild_id).await?;
+    let requirement_data = repository.fetch_restricted_commands(guild_id).await?;
 
     for i in command_data {
-        command_map.push(i.name);
+        let mut entry = format!("{} ({}", i.name, i.kind);
+
+        if i.reply {
+            entry.push_str(", reply");
+        }
+
+        if requirement_data.contains(&i.name) {
+            entry.push_str(", restricted");
+        }
+
+        entry.push(')');
+
+        let aliases = alias_data
+            .iter()
+            .filter(|(_, name)| *name == i.name)
+            .map(|(alias, _)| alias.as_str())
+            .collect::<Vec<&str>>();
+
+        if !aliases.is_empty() {
+            entry.push_str(&format!(" - aliases: {}", aliases.join(", ")));
+        }
+
+        command_map.push(entry);
     }
 
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("Custom commands");
-                e.description(format!("```{} \n```", command_map.join(" \n")))
-            });
-
-            m
-        })
-        .await?;
+    let mut eb = CreateEmbed::default();
+
+    eb.title("Custom commands");
+    eb.description(format!("```{} \n```", command_map.join(" \n")));
+
+    source.send_embed(ctx, eb).await?;
 
     Ok(())
 }
 
-pub async fn command_help(ctx: &Context, channel_id: ChannelId) {
-    let content = concat!(
-        "set <name> <content>: Sets a new custom command, {user} is replaced with a mention \n\n",
-        "remove <name>: Removes an existing custom command \n\n",
-        "list: Lists all custom commands in the server"
+pub fn command_help(locale: &str) -> CreateEmbed {
+    let mut eb = locale::get_help_embed(locale, "command");
+
+    eb.field(
+        locale::get_text(locale, "help.command.placeholders_title"),
+        locale::get_text(locale, "help.command.placeholders"),
+        false,
     );
+    eb.field(
+        locale::get_text(locale, "help.command.embed_title"),
+        locale::get_text(locale, "help.command.embed"),
+        false,
+    );
+
+    eb
+}
+
+pub fn create_config_commands(commands: &mut CreateApplicationCommands) {
+    commands.create_application_command(|c| {
+        c.name("prefix")
+            .description("Gets or sets the server's prefixes")
+            .dm_permission(false)
+            .create_option(|o| {
+                o.name("new_prefixes")
+                    .description("The new prefixes, separated by spaces (Moderator only)")
+                    .kind(CommandOptionType::String)
+                    .required(false)
+            })
+    });
+
+    commands.create_application_command(|c| {
+        c.name("resetprefix")
+            .description("Resets the server's prefix back to the default one")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+    });
 
-    let _ = channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("Custom Command Help");
-                e.description(
-                    "Description: Custom command configuration (For administrators only!)",
-                );
-                e.field("Commands", content, false);
-                e
+    commands.create_application_command(|c| {
+        c.name("language")
+            .description("Gets or sets the server's language")
+            .dm_permission(false)
+            .create_option(|o| {
+                o.name("code")
+                    .description("The new language code (Administrator only)")
+                    .kind(CommandOptionType::String)
+                    .required(false)
+            })
+    });
+
+    commands.create_application_command(|c| {
+        c.name("command")
+            .description("Custom commands for your server")
+            .dm_permission(false)
+            .create_option(|o| {
+                o.name("set")
+                    .description("Sets a new custom command, see the help command for placeholders")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("name")
+                            .description("Name of the custom command")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("content")
+                            .description("What the command says")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+            })
+            .create_option(|o| {
+                o.name("embed")
+                    .description("Sets a custom command that sends an embed")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("name")
+                            .description("Name of the custom command")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("json")
+                            .description("The embed as JSON, see the help command for keys")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+            })
+            .create_option(|o| {
+                o.name("image")
+                    .description("Sets a custom command that sends an image")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("name")
+                            .description("Name of the custom command")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("url")
+                            .description("Link to the image")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("caption")
+                            .description("Text sent with the image")
+                            .kind(CommandOptionType::String)
+                            .required(false)
+                    })
+            })
+            .create_option(|o| {
+                o.name("reply")
+                    .description("Toggles if the command replies to the user")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("name")
+                            .description("Name of the custom command")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("enabled")
+                            .description("Should the command reply?")
+                            .kind(CommandOptionType::Boolean)
+                            .required(true)
+                    })
+            })
+            .create_option(|o| {
+                o.name("alias")
+                    .description("Adds another name for a custom command")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("name")
+                            .description("Name of the custom command")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("alias")
+                            .description("The new alias")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+            })
+            .create_option(|o| {
+                o.name("unalias")
+                    .description("Removes an alias")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("alias")
+                            .description("The alias to remove")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+            })
+            .create_option(|o| {
+                o.name("require")
+                    .description("Limits who can use a custom command and where")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("name")
+                            .description("Name of the custom command")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("role")
+                            .description("Users need one of the required roles")
+                            .kind(CommandOptionType::Role)
+                            .required(false)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("permission")
+                            .description(
+                                "Users need every required permission (ex. manage messages)",
+                            )
+                            .kind(CommandOptionType::String)
+                            .required(false)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("channel")
+                            .description("The command only works in the required channels")
+                            .kind(CommandOptionType::Channel)
+                            .required(false)
+                    })
+            })
+            .create_option(|o| {
+                o.name("unrequire")
+                    .description("Removes a limit from a custom command, or every limit")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("name")
+                            .description("Name of the custom command")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("role")
+                            .description("The role to remove")
+                            .kind(CommandOptionType::Role)
+                            .required(false)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("permission")
+                            .description("The permission to remove")
+                            .kind(CommandOptionType::String)
+                            .required(false)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("channel")
+                            .description("The channel to remove")
+                            .kind(CommandOptionType::Channel)
+                            .required(false)
+                    })
+            })
+            .create_option(|o| {
+                o.name("remove")
+                    .description("Removes an existing custom command")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("name")
+                            .description("Name of the custom command")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+            })
+            .create_option(|o| {
+                o.name("list")
+                    .description("Lists all custom commands in the server")
+                    .kind(CommandOptionType::SubCommand)
             })
-        })
-        .await;
+    });
+}
+
+pub async fn config_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+    let options = &command.data.options;
+
+    match command.data.name.as_str() {
+        "prefix" => {
+            let new_prefixes = interaction_utils::get_string_option(options, "new_prefixes")
+                .map(|x| x.split_whitespace().map(|x| x.to_owned()).collect())
+                .unwrap_or_default();
+
+            prefix_internal(ctx, &source, new_prefixes).await
+        }
+        "resetprefix" => resetprefix_internal(ctx, &source).await,
+        "language" => {
+            let new_locale = interaction_utils::get_string_option(options, "code");
+
+            language_internal(ctx, &source, new_locale).await
+        }
+        "command" => {
+            let subcommand = match interaction_utils::get_subcommand(options) {
+                Some(subcommand) => subcommand,
+                None => return Ok(()),
+            };
+            let sub_options = &subcommand.options;
+            let name =
+                interaction_utils::get_string_option(sub_options, "name").unwrap_or_default();
+
+            if subcommand.name == "list" {
+                return list_internal(ctx, &source).await;
+            }
+
+            // Changing custom commands is moderator only
+            if !permissions_helper::check_permission(ctx, &source, None, false).await? {
+                return Ok(());
+            }
+
+            match subcommand.name.as_str() {
+                "set" => {
+                    let content = interaction_utils::get_string_option(sub_options, "content")
+                        .unwrap_or_default();
+
+                    set_internal(ctx, &source, name, &content).await
+                }
+                "embed" => {
+                    let json = interaction_utils::get_string_option(sub_options, "json")
+                        .unwrap_or_default();
+
+                    embed_internal(ctx, &source, name, &json).await
+                }
+                "image" => {
+                    let url = interaction_utils::get_string_option(sub_options, "url")
+                        .unwrap_or_default();
+                    let caption = interaction_utils::get_string_option(sub_options, "caption")
+                        .unwrap_or_default();
+
+                    image_internal(ctx, &source, name, url, &caption).await
+                }
+                "reply" => {
+                    let enabled =
+                        interaction_utils::get_bool_option(sub_options, "enabled").unwrap_or(false);
+
+                    reply_internal(ctx, &source, name, enabled).await
+                }
+                "alias" => {
+                    let alias = interaction_utils::get_string_option(sub_options, "alias")
+                        .unwrap_or_default();
+
+                    alias_internal(ctx, &source, name, alias).await
+                }
+                "unalias" => {
+                    let alias = interaction_utils::get_string_option(sub_options, "alias")
+                        .unwrap_or_default();
+
+                    unalias_internal(ctx, &source, alias).await
+                }
+                "require" | "unrequire" => {
+                    let requirement = match get_slash_requirement(sub_options) {
+                        Ok(requirement) => requirement,
+                        Err(reason) => {
+                            source.say(ctx, reason).await?;
+                            return Ok(());
+                        }
+                    };
+
+                    if subcommand.name == "require" {
+                        require_internal(ctx, &source, name, requirement).await
+                    } else {
+                        unrequire_internal(ctx, &source, name, requirement).await
+                    }
+                }
+                "remove" => remove_internal(ctx, &source, name).await,
+                _ => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+fn get_slash_requirement(options: &[CommandDataOption]) -> Result<Option<Requirement>, String> {
+    if let Some(role_id) = interaction_utils::get_role_option(options, "role") {
+        return Ok(Some(Requirement::Role(role_id)));
+    }
+
+    if let Some(channel_id) = interaction_utils::get_channel_option(options, "channel") {
+        return Ok(Some(Requirement::Channel(channel_id)));
+    }
+
+    match interaction_utils::get_string_option(options, "permission") {
+        Some(permission) => custom_commands::parse_permission(&permission)
+            .map(|x| Some(Requirement::Permission(x)))
+            .ok_or_else(|| format!("`{}` isn't a valid permission!", permission)),
+        None => Ok(None),
+    }
 }
@@ -1,292 +1,264 @@
-use crate::helpers::reaction_gifs::{add_to_cache, check_image_cache, fetch_gifs};
+use crate::{
+    helpers::{
+        interaction_utils, locale,
+        reaction_gifs::{add_to_cache, check_image_cache, fetch_gifs},
+    },
+    structures::command_source::CommandSource,
+};
 use rand::{prelude::StdRng, Rng, SeedableRng};
 use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
     framework::standard::{macros::command, Args, CommandResult},
-    model::prelude::*,
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        prelude::*,
+    },
     prelude::*,
 };
 
-#[command]
-async fn hug(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+// Checks if the first argument is `everyone` and grabs the first mention
+fn get_reaction_target(msg: &Message, args: &mut Args) -> (Option<User>, bool) {
     let is_everyone = match args.single::<String>() {
         Ok(test) => &test == "everyone" || &test == "Everyone",
         Err(_) => false,
     };
 
-    if msg.mentions.is_empty() && !is_everyone {
-        msg.channel_id
-            .say(
-                ctx,
-                "You want to give a hug? Please mention who you want to hug or provide `everyone`!",
-            )
-            .await?;
-        return Ok(());
-    }
-
-    let raw_gifs = fetch_gifs(ctx, "anime hug", 10, "medium").await?;
-    let mut rng = StdRng::from_entropy();
+    (msg.mentions.first().cloned(), is_everyone)
+}
 
-    let guild_id = msg.guild_id.unwrap();
-    let gifs = check_image_cache(ctx, guild_id, "hug".to_owned(), raw_gifs).await;
+#[command]
+async fn hug(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let (target, is_everyone) = get_reaction_target(msg, &mut args);
 
-    let val = rng.gen_range(0..=gifs.len() - 1);
+    hug_internal(ctx, &CommandSource::Prefix(msg), target, is_everyone).await
+}
 
-    let message = if is_everyone {
-        "Group hug!".to_owned()
-    } else if msg.mentions[0].id == msg.author.id {
-        "You hugged yourself. Cute 🙂".to_owned()
-    } else {
-        format!("{} hugs {}", msg.author.name, msg.mentions[0].name)
+async fn hug_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    target: Option<User>,
+    is_everyone: bool,
+) -> CommandResult {
+    let author = source.author();
+
+    let message = match target {
+        _ if is_everyone => "Group hug!".to_owned(),
+        Some(target) if target.id == author.id => "You hugged yourself. Cute 🙂".to_owned(),
+        Some(target) => format!("{} hugs {}", author.name, target.name),
+        None => {
+            source
+                .say(
+                    ctx,
+                    "You want to give a hug? Please mention who you want to hug or provide `everyone`!",
+                )
+                .await?;
+            return Ok(());
+        }
     };
 
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.color(0xed9e2f);
-                e.description(message);
-                e.image(&gifs[val].media[0].get("gif").unwrap().url);
-                e
-            })
-        })
-        .await?;
-
-    add_to_cache(ctx, guild_id, "hug".to_owned(), gifs[val].url.to_owned()).await;
-
-    Ok(())
+    send_reaction_gif(ctx, source, "anime hug", "hug", "medium", 0xed9e2f, message).await
 }
 
 #[command]
 async fn pat(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let is_everyone = match args.single::<String>() {
-        Ok(test) => &test == "everyone" || &test == "Everyone",
-        Err(_) => false,
-    };
-
-    if msg.mentions.is_empty() && !is_everyone {
-        msg.channel_id
-            .say(
-                ctx,
-                "I wanna pat someone! Please mention who to pat or provide `everyone`!",
-            )
-            .await?;
+    let (target, is_everyone) = get_reaction_target(msg, &mut args);
 
-        return Ok(());
-    }
-
-    let raw_gifs = fetch_gifs(ctx, "anime pat", 10, "medium").await?;
-    let mut rng = StdRng::from_entropy();
-
-    let guild_id = msg.guild_id.unwrap();
-    let gifs = check_image_cache(ctx, guild_id, "pat".to_owned(), raw_gifs).await;
-
-    let val = rng.gen_range(0..=gifs.len() - 1);
+    pat_internal(ctx, &CommandSource::Prefix(msg), target, is_everyone).await
+}
 
-    let message = if is_everyone {
-        "Pats for everyone!".to_owned()
-    } else if msg.mentions[0].id == msg.author.id {
-        "You gave yourself a pat on the back!".to_owned()
-    } else {
-        format!("{} pats {}", msg.author.name, msg.mentions[0].name)
+async fn pat_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    target: Option<User>,
+    is_everyone: bool,
+) -> CommandResult {
+    let author = source.author();
+
+    let message = match target {
+        _ if is_everyone => "Pats for everyone!".to_owned(),
+        Some(target) if target.id == author.id => "You gave yourself a pat on the back!".to_owned(),
+        Some(target) => format!("{} pats {}", author.name, target.name),
+        None => {
+            source
+                .say(
+                    ctx,
+                    "I wanna pat someone! Please mention who to pat or provide `everyone`!",
+                )
+                .await?;
+            return Ok(());
+        }
     };
 
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.color(0x27e6d9);
-                e.description(message);
-                e.image(&gifs[val].media[0].get("gif").unwrap().url);
-                e
-            })
-        })
-        .await?;
-
-    add_to_cache(ctx, guild_id, "pat".to_owned(), gifs[val].url.to_owned()).await;
-
-    Ok(())
+    send_reaction_gif(ctx, source, "anime pat", "pat", "medium", 0x27e6d9, message).await
 }
 
 #[command]
 async fn slap(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let is_everyone = match args.single::<String>() {
-        Ok(test) => &test == "everyone" || &test == "Everyone",
-        Err(_) => false,
-    };
-
-    if msg.mentions.is_empty() && !is_everyone {
-        msg.channel_id
-            .say(
-                ctx,
-                "Wait... who do I slap again? Please mention the person or provide `everyone`!",
-            )
-            .await?;
-
-        return Ok(());
-    }
-
-    let raw_gifs = fetch_gifs(ctx, "anime slap", 10, "medium").await?;
-    let mut rng = StdRng::from_entropy();
+    let (target, is_everyone) = get_reaction_target(msg, &mut args);
 
-    let guild_id = msg.guild_id.unwrap();
-    let gifs = check_image_cache(ctx, guild_id, "slap".to_owned(), raw_gifs).await;
-
-    let val = rng.gen_range(0..=gifs.len() - 1);
+    slap_internal(ctx, &CommandSource::Prefix(msg), target, is_everyone).await
+}
 
-    let message = if is_everyone {
-        "You slapped everyone! Ouch... that's gotta hurt.".to_owned()
-    } else if msg.mentions[0].id == msg.author.id {
-        "You slapped yourself? Not sure if that's a good or bad thing...".to_owned()
-    } else {
-        format!("{} slaps {}", msg.author.name, msg.mentions[0].name)
+async fn slap_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    target: Option<User>,
+    is_everyone: bool,
+) -> CommandResult {
+    let author = source.author();
+
+    let message = match target {
+        _ if is_everyone => "You slapped everyone! Ouch... that's gotta hurt.".to_owned(),
+        Some(target) if target.id == author.id => {
+            "You slapped yourself? Not sure if that's a good or bad thing...".to_owned()
+        }
+        Some(target) => format!("{} slaps {}", author.name, target.name),
+        None => {
+            source
+                .say(
+                    ctx,
+                    "Wait... who do I slap again? Please mention the person or provide `everyone`!",
+                )
+                .await?;
+            return Ok(());
+        }
     };
 
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.color(0xd62929);
-                e.description(message);
-                e.image(&gifs[val].media[0].get("gif").unwrap().url);
-                e
-            })
-        })
-        .await?;
-
-    add_to_cache(ctx, guild_id, "slap".to_owned(), gifs[val].url.to_owned()).await;
-
-    Ok(())
+    send_reaction_gif(
+        ctx,
+        source,
+        "anime slap",
+        "slap",
+        "medium",
+        0xd62929,
+        message,
+    )
+    .await
 }
 
 #[command]
 async fn kiss(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let is_everyone = match args.single::<String>() {
-        Ok(test) => &test == "everyone" || &test == "Everyone",
-        Err(_) => false,
-    };
+    let (target, is_everyone) = get_reaction_target(msg, &mut args);
 
-    if msg.mentions.is_empty() && !is_everyone {
-        msg.channel_id
-            .say(
-                ctx,
-                "You want to express your feelings? Please mention who you want to kiss or provide `everyone`!",
-            )
-            .await?;
-        return Ok(());
-    }
-
-    let raw_gifs = fetch_gifs(ctx, "anime kiss", 10, "medium").await?;
-    let mut rng = StdRng::from_entropy();
-
-    let guild_id = msg.guild_id.unwrap();
-    let gifs = check_image_cache(ctx, guild_id, "kiss".to_owned(), raw_gifs).await;
-
-    let val = rng.gen_range(0..=gifs.len() - 1);
+    kiss_internal(ctx, &CommandSource::Prefix(msg), target, is_everyone).await
+}
 
-    let message = if is_everyone {
-        "A friendly kiss to everyone!".to_owned()
-    } else if msg.mentions[0].id == msg.author.id {
-        "Well... You just kissed yourself".to_owned()
-    } else {
-        format!("{} kisses {}", msg.author.name, msg.mentions[0].name)
+async fn kiss_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    target: Option<User>,
+    is_everyone: bool,
+) -> CommandResult {
+    let author = source.author();
+
+    let message = match target {
+        _ if is_everyone => "A friendly kiss to everyone!".to_owned(),
+        Some(target) if target.id == author.id => "Well... You just kissed yourself".to_owned(),
+        Some(target) => format!("{} kisses {}", author.name, target.name),
+        None => {
+            source
+                .say(
+                    ctx,
+                    "You want to express your feelings? Please mention who you want to kiss or provide `everyone`!",
+                )
+                .await?;
+            return Ok(());
+        }
     };
 
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.color(0xffb6c1);
-                e.description(message);
-                e.image(&gifs[val].media[0].get("gif").unwrap().url);
-                e
-            })
-        })
-        .await?;
-
-    add_to_cache(ctx, guild_id, "kiss".to_owned(), gifs[val].url.to_owned()).await;
-
-    Ok(())
+    send_reaction_gif(
+        ctx,
+        source,
+        "anime kiss",
+        "kiss",
+        "medium",
+        0xffb6c1,
+        message,
+    )
+    .await
 }
 
 #[command]
 async fn disgust(ctx: &Context, msg: &Message) -> CommandResult {
-    let raw_gifs = fetch_gifs(ctx, "anime disgust", 10, "medium").await?;
-    let mut rng = StdRng::from_entropy();
-
-    let guild_id = msg.guild_id.unwrap();
-    let gifs = check_image_cache(ctx, guild_id, "disgust".to_owned(), raw_gifs).await;
-
-    let val = rng.gen_range(0..=gifs.len() - 1);
+    disgust_internal(ctx, &CommandSource::Prefix(msg)).await
+}
 
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.color(0x50c878);
-                e.description(format!("{} is disgusted 😕", msg.author.name));
-                e.image(&gifs[val].media[0].get("gif").unwrap().url);
-                e
-            })
-        })
-        .await?;
+async fn disgust_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    let message = format!("{} is disgusted 😕", source.author().name);
 
-    add_to_cache(
+    send_reaction_gif(
         ctx,
-        guild_id,
-        "disgust".to_owned(),
-        gifs[val].url.to_owned(),
+        source,
+        "anime disgust",
+        "disgust",
+        "medium",
+        0x50c878,
+        message,
     )
-    .await;
-
-    Ok(())
+    .await
 }
 
 #[command]
 async fn cry(ctx: &Context, msg: &Message) -> CommandResult {
-    let raw_gifs = fetch_gifs(ctx, "anime cry", 10, "medium").await?;
-    let mut rng = StdRng::from_entropy();
+    cry_internal(ctx, &CommandSource::Prefix(msg)).await
+}
 
-    let guild_id = msg.guild_id.unwrap();
-    let gifs = check_image_cache(ctx, guild_id, "cry".to_owned(), raw_gifs).await;
+async fn cry_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    let message = format!("{} is crying! 😭", source.author().name);
 
-    let val = rng.gen_range(0..=gifs.len() - 1);
+    send_reaction_gif(ctx, source, "anime cry", "cry", "medium", 0x3252e3, message).await
+}
 
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.color(0x3252e3);
-                e.description(format!("{} is crying! 😭", msg.author.name));
-                e.image(&gifs[val].media[0].get("gif").unwrap().url);
-                e
-            })
-        })
-        .await?;
+#[command]
+async fn cringe(ctx: &Context, msg: &Message) -> CommandResult {
+    cringe_internal(ctx, &CommandSource::Prefix(msg)).await
+}
 
-    add_to_cache(ctx, guild_id, "cry".to_owned(), gifs[val].url.to_owned()).await;
+async fn cringe_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    let message = format!("{} thinks that's really cringey 😬", source.author().name);
 
-    Ok(())
+    send_reaction_gif(ctx, source, "cringe", "cringe", "low", 0x634644, message).await
 }
 
-#[command]
-async fn cringe(ctx: &Context, msg: &Message) -> CommandResult {
-    let raw_gifs = fetch_gifs(ctx, "cringe", 10, "low").await?;
+/*
+ * Shared by every reaction command
+ * Fetches gifs from tenor, skips the last one sent in the guild, and sends it in an embed
+ */
+async fn send_reaction_gif(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    search: &str,
+    cache_key: &str,
+    filter: &str,
+    color: u32,
+    message: String,
+) -> CommandResult {
+    let raw_gifs = fetch_gifs(ctx, search, 10, filter).await?;
     let mut rng = StdRng::from_entropy();
 
-    let guild_id = msg.guild_id.unwrap();
-    let gifs = check_image_cache(ctx, guild_id, "cringe".to_owned(), raw_gifs).await;
+    let guild_id = source.guild_id().unwrap();
+    let gifs = check_image_cache(ctx, guild_id, cache_key.to_owned(), raw_gifs).await;
 
     let val = rng.gen_range(0..=gifs.len() - 1);
 
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.color(0x634644);
-                e.description(format!(
-                    "{} thinks that's really cringey 😬",
-                    msg.author.name
-                ));
-                e.image(&gifs[val].media[0].get("gif").unwrap().url);
-                e
-            })
-        })
-        .await?;
+    let mut eb = CreateEmbed::default();
 
-    add_to_cache(ctx, guild_id, "cringe".to_owned(), gifs[val].url.to_owned()).await;
+    eb.color(color);
+    eb.description(message);
+    eb.image(&gifs[val].media[0].get("gif").unwrap().url);
+
+    source.send_embed(ctx, eb).await?;
+
+    add_to_cache(
+        ctx,
+        guild_id,
+        cache_key.to_owned(),
+        gifs[val].url.to_owned(),
+    )
+    .await;
 
     Ok(())
 }
@@ -294,16 +266,22 @@ async fn cringe(ctx: &Context, msg: &Message) -> CommandResult {
 #[command]
 #[aliases("gif")]
 async fn gifsearch(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
-    if args.is_empty() {
-        msg.channel_id
+    gifsearch_internal(ctx, &CommandSource::Prefix(msg), args.message()).await
+}
+
+async fn gifsearch_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    search_string: &str,
+) -> CommandResult {
+    if search_string.is_empty() {
+        source
             .say(ctx, "Please provide a search string after the command!")
             .await?;
         return Ok(());
     }
 
-    let search_string = args.message();
-
-    let filter = if msg.channel(ctx).await.unwrap().is_nsfw() {
+    let filter = if source.channel_id().to_channel(ctx).await?.is_nsfw() {
         "off"
     } else {
         "medium"
@@ -313,37 +291,96 @@ async fn gifsearch(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     let mut rng = StdRng::from_entropy();
     let val = rng.gen_range(0..=gifs.len() - 1);
 
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.color(0x5ed13b);
-                e.image(&gifs[val].media[0].get("gif").unwrap().url);
-                e
-            })
-        })
-        .await?;
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0x5ed13b);
+    eb.image(&gifs[val].media[0].get("gif").unwrap().url);
+
+    source.send_embed(ctx, eb).await?;
 
     Ok(())
 }
 
-pub async fn image_help(ctx: &Context, channel_id: ChannelId) {
-    let content = concat!(
-        "gif: Fetches a random gif from tenor \nNote: The content filter is turned off in an NSFW channel \n\n",
-        "hug <mention>: Gives wholesome hugs to someone \n\n",
-        "pat <mention>: Pats someone on the head \n\n",
-        "slap <mention>: Give someone a slap \n\n",
-        "kiss <mention>: You already know what this is and I am shaking my head... \n\n",
-        "cry: Emphasizes that you're crying  \n\n",
-        "cringe: Emphasizes that something is cringey \n\n");
-
-    let _ = channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("Images/Reaction Help");
-                e.description("Description: Various commands that work with images");
-                e.field("Commands", content, false);
-                e
+pub fn create_image_commands(commands: &mut CreateApplicationCommands) {
+    for (name, description) in [
+        ("hug", "Gives wholesome hugs to someone"),
+        ("pat", "Pats someone on the head"),
+        ("slap", "Give someone a slap"),
+        (
+            "kiss",
+            "You already know what this is and I am shaking my head...",
+        ),
+    ] {
+        commands.create_application_command(|c| {
+            c.name(name)
+                .description(description)
+                .dm_permission(false)
+                .create_option(|o| {
+                    o.name("user")
+                        .description("Who the reaction is for")
+                        .kind(CommandOptionType::User)
+                        .required(false)
+                })
+                .create_option(|o| {
+                    o.name("everyone")
+                        .description("Include everyone instead")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+        });
+    }
+
+    for (name, description) in [
+        ("disgust", "Emphasizes that you're disgusted"),
+        ("cry", "Emphasizes that you're crying"),
+        ("cringe", "Emphasizes that something is cringey"),
+    ] {
+        commands.create_application_command(|c| {
+            c.name(name).description(description).dm_permission(false)
+        });
+    }
+
+    commands.create_application_command(|c| {
+        c.name("gifsearch")
+            .description("Fetches a random gif from tenor")
+            .dm_permission(false)
+            .create_option(|o| {
+                o.name("search")
+                    .description("What to search for")
+                    .kind(CommandOptionType::String)
+                    .required(true)
             })
-        })
-        .await;
+    });
+}
+
+pub async fn image_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+    let options = &command.data.options;
+
+    let target = interaction_utils::get_user_option(options, "user");
+    let is_everyone = interaction_utils::get_bool_option(options, "everyone").unwrap_or(false);
+
+    match command.data.name.as_str() {
+        "hug" => hug_internal(ctx, &source, target, is_everyone).await,
+        "pat" => pat_internal(ctx, &source, target, is_everyone).await,
+        "slap" => slap_internal(ctx, &source, target, is_everyone).await,
+        "kiss" => kiss_internal(ctx, &source, target, is_everyone).await,
+        "disgust" => disgust_internal(ctx, &source).await,
+        "cry" => cry_internal(ctx, &source).await,
+        "cringe" => cringe_internal(ctx, &source).await,
+        "gifsearch" => {
+            let search =
+                interaction_utils::get_string_option(options, "search").unwrap_or_default();
+
+            gifsearch_internal(ctx, &source, &search).await
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn image_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "images")
 }
@@ -1,16 +1,23 @@
 use reqwest::{Error as ReqwestError, Url};
 use serde::{Deserialize, Serialize};
 use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
     framework::standard::{macros::command, Args, CommandResult},
-    model::prelude::*,
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        prelude::*,
+    },
     prelude::*,
 };
 use std::fmt::Write;
 use std::time::Duration;
 
 use crate::{
-    helpers::{command_utils::get_command_name, embed_store},
-    structures::{errors::JesterError, JapanResult},
+    helpers::{command_utils::get_command_name, embed_store, interaction_utils, locale},
+    structures::{command_source::CommandSource, errors::JesterError, JapanResult},
     PubCreds, ReqwestClient,
 };
 
@@ -29,23 +36,34 @@ pub struct Datum {
 async fn anime(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     let command_name = get_command_name(ctx, msg).await;
 
-    if args.is_empty() {
-        msg.channel_id
+    anime_internal(ctx, &CommandSource::Prefix(msg), command_name, args.rest()).await
+}
+
+async fn anime_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    command_name: &str,
+    search: &str,
+) -> CommandResult {
+    if search.is_empty() {
+        let locale = locale::get_guild_locale(ctx, source.guild_id()).await;
+
+        source
             .say(
                 ctx,
-                JesterError::MissingError(&format!("{} title for me to work with!", command_name)),
+                JesterError::MissingError(&format!("{}_title", command_name)).localized(&locale),
             )
             .await?;
 
         return Ok(());
     }
 
-    let results = match fetch_info(ctx, command_name, args.rest()).await {
+    let results = match fetch_info(ctx, command_name, search).await {
         Ok(info) => info.data,
         Err(e) => {
             println!("MAL fetch error!: {}", e);
 
-            msg.channel_id
+            source
                 .say(
                     ctx,
                     "Couldn't find your request on MAL! \nThe error is down below.",
@@ -57,7 +75,7 @@ async fn anime(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     };
 
     if results.is_empty() {
-        msg.channel_id
+        source
             .say(
                 ctx,
                 "There are no results! Try some different search terms!",
@@ -77,23 +95,16 @@ async fn anime(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 
     let result_embed = embed_store::get_result_embed(&result_string);
 
-    let sent_message = msg
-        .channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.0 = result_embed.0;
-                e
-            })
-        })
-        .await?;
+    let sent_message = source.send_embed(ctx, result_embed).await?;
 
-    while let Ok(value) = ask_for_results(ctx, msg).await {
+    while let Ok(value) = ask_for_results(ctx, source).await {
         let index = value as usize;
 
         if let Some(result) = results.get(index - 1) {
             let japan_embed = embed_store::get_anime_embed(&result.node);
 
-            msg.channel_id
+            source
+                .channel_id()
                 .send_message(ctx, |m| {
                     m.embed(|e| {
                         e.0 = japan_embed.0;
@@ -111,11 +122,11 @@ async fn anime(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     Ok(())
 }
 
-async fn ask_for_results(ctx: &Context, msg: &Message) -> CommandResult<isize> {
-    let channel_id = msg.channel_id;
+async fn ask_for_results(ctx: &Context, source: &CommandSource<'_>) -> CommandResult<isize> {
+    let channel_id = source.channel_id();
 
-    let result = msg
-        .author
+    let result = source
+        .author()
         .await_reply(ctx)
         .filter(move |given_msg| given_msg.channel_id == channel_id)
         .timeout(Duration::from_secs(30))
@@ -204,20 +215,36 @@ async fn fetch_info(ctx: &Context, search_type: &str, search: &str) -> CommandRe
     Ok(json)
 }
 
-pub async fn japan_help(ctx: &Context, channel_id: ChannelId) {
-    let content = concat!(
-        "anime <title>: Searches for an anime's information from the title \n\n",
-        "manga <title>: Searches for a manga's information from the title"
-    );
-
-    let _ = channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("Japan Help");
-                e.description("Description: Commands that deal with japanese media");
-                e.field("Commands", content, false);
-                e
+pub fn create_japan_commands(commands: &mut CreateApplicationCommands) {
+    for (name, description) in [
+        (
+            "anime",
+            "Searches for an anime's information from the title",
+        ),
+        ("manga", "Searches for a manga's information from the title"),
+    ] {
+        commands.create_application_command(|c| {
+            c.name(name).description(description).create_option(|o| {
+                o.name("title")
+                    .description("The title to search for")
+                    .kind(CommandOptionType::String)
+                    .required(true)
             })
-        })
-        .await;
+        });
+    }
+}
+
+pub async fn japan_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+    let title =
+        interaction_utils::get_string_option(&command.data.options, "title").unwrap_or_default();
+
+    anime_internal(ctx, &source, &command.data.name, &title).await
+}
+
+pub fn japan_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "japan")
 }
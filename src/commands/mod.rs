@@ -1,10 +1,18 @@
+pub mod automod;
+pub mod backup;
 pub mod ciphers;
 pub mod config;
 pub mod images;
 pub mod japan;
+pub mod moderation;
 pub mod music;
 pub mod other;
+pub mod owner;
+pub mod polls;
+pub mod reminders;
+pub mod rules;
 pub mod starboard;
+pub mod stats;
 pub mod support;
 pub mod textchannel_send;
 pub mod textmod;
@@ -0,0 +1,964 @@
+use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        prelude::*,
+        Timestamp,
+    },
+    prelude::*,
+    utils::parse_channel,
+};
+
+use crate::{
+    helpers::{
+        command_utils, guild_settings, interaction_utils, locale, mod_log,
+        moderation::{self, HierarchyError, ModAction},
+    },
+    repositories::{ModCase, SenderKind},
+    structures::{
+        command_source::CommandSource,
+        errors::{JesterError, PermissionType},
+    },
+    RepositoryContainer,
+};
+
+// Discord's limits for audit log reasons and timeouts
+const MAX_REASON_LENGTH: usize = 512;
+const MAX_TIMEOUT_SECS: i64 = 2419200;
+
+// Only the newest cases fit in the history embed
+const HISTORY_CASES: usize = 10;
+
+/// Warns a user. The warning is saved as a case
+/// Usage: `warn <user mention/ID> (reason)`
+#[command]
+#[required_permissions("MODERATE_MEMBERS")]
+async fn warn(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    moderate_from_args(ctx, msg, args, ModAction::Warn).await
+}
+
+/// Usage: `kick <user mention/ID> (reason)`
+#[command]
+#[required_permissions("KICK_MEMBERS")]
+async fn kick(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    moderate_from_args(ctx, msg, args, ModAction::Kick).await
+}
+
+/// Users can be banned by ID even if they aren't in the server
+/// Usage: `ban <user mention/ID> (reason)`
+#[command]
+#[required_permissions("BAN_MEMBERS")]
+async fn ban(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    moderate_from_args(ctx, msg, args, ModAction::Ban).await
+}
+
+/// Usage: `unban <user ID> (reason)`
+#[command]
+#[required_permissions("BAN_MEMBERS")]
+async fn unban(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    moderate_from_args(ctx, msg, args, ModAction::Unban).await
+}
+
+/// Usage: `timeout <user mention/ID> <duration> (reason)`, like `timeout @user 1h spam`
+#[command]
+#[required_permissions("MODERATE_MEMBERS")]
+async fn timeout(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    moderate_from_args(ctx, msg, args, ModAction::Timeout).await
+}
+
+async fn moderate_from_args(
+    ctx: &Context,
+    msg: &Message,
+    mut args: Args,
+    action: ModAction,
+) -> CommandResult {
+    let user = match args.single::<UserId>() {
+        Ok(user_id) => match user_id.to_user(ctx).await {
+            Ok(user) => user,
+            Err(_) => {
+                msg.channel_id.say(ctx, "That user doesn't exist!").await?;
+
+                return Ok(());
+            }
+        },
+        Err(_) => {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
+            msg.channel_id
+                .say(ctx, JesterError::MissingError("user").localized(&locale))
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let duration = if action == ModAction::Timeout {
+        match args
+            .single::<String>()
+            .ok()
+            .and_then(|x| command_utils::parse_duration(&x))
+        {
+            Some(duration) => Some(duration),
+            None => {
+                msg.channel_id
+                    .say(ctx, "Please provide a duration like `10m` or `1d`!")
+                    .await?;
+
+                return Ok(());
+            }
+        }
+    } else {
+        None
+    };
+
+    let reason = match args.rest().trim() {
+        "" => None,
+        reason => Some(reason.to_owned()),
+    };
+
+    moderate_internal(
+        ctx,
+        &CommandSource::Prefix(msg),
+        action,
+        &user,
+        reason,
+        duration,
+    )
+    .await
+}
+
+/*
+ * Every moderation action goes through here
+ * The action only happens if the moderator and the bot are both above the user,
+ * and a case is only saved once the action worked
+ */
+async fn moderate_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    action: ModAction,
+    user: &User,
+    reason: Option<String>,
+    duration: Option<i64>,
+) -> CommandResult {
+    let guild_id = source.guild_id().unwrap();
+    let locale = locale::get_guild_locale(ctx, Some(guild_id)).await;
+
+    if reason
+        .as_ref()
+        .is_some_and(|x| x.chars().count() > MAX_REASON_LENGTH)
+    {
+        source
+            .say(
+                ctx,
+                format!(
+                    "Reasons can only be up to {} characters long!",
+                    MAX_REASON_LENGTH
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    if let Some(duration) = duration {
+        if duration <= 0 || duration > MAX_TIMEOUT_SECS {
+            source.say(ctx, "Timeouts can last up to 4 weeks!").await?;
+
+            return Ok(());
+        }
+    }
+
+    if user.id == source.author().id {
+        source
+            .say(ctx, format!("You can't {} yourself!", action))
+            .await?;
+
+        return Ok(());
+    }
+
+    let bot_id = ctx.cache.current_user_id();
+
+    if user.id == bot_id {
+        source
+            .say(ctx, format!("I can't {} myself!", action))
+            .await?;
+
+        return Ok(());
+    }
+
+    let (permission, permission_name) = action.permission();
+
+    /*
+     * Slash command permissions can be changed by server admins,
+     * so the moderator's permissions are checked here for both kinds of commands
+     */
+    let moderator = guild_id.member(ctx, source.author().id).await?;
+
+    if !moderator.permissions(ctx)?.contains(permission) {
+        source
+            .say(
+                ctx,
+                JesterError::PermissionError(PermissionType::UserPerm(permission_name))
+                    .localized(&locale),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let bot_member = guild_id.member(ctx, bot_id).await?;
+
+    if !bot_member.permissions(ctx)?.contains(permission) {
+        source
+            .say(
+                ctx,
+                JesterError::PermissionError(PermissionType::External(permission_name))
+                    .localized(&locale),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    // Banned users and users that left can't be checked, so only members are compared
+    match guild_id.member(ctx, user.id).await {
+        Ok(member) => {
+            if !check_hierarchy(
+                ctx,
+                source,
+                action,
+                &member,
+                &moderator,
+                &bot_member,
+                &locale,
+            )
+            .await?
+            {
+                return Ok(());
+            }
+        }
+        Err(_) if matches!(action, ModAction::Ban | ModAction::Unban) => {}
+        Err(_) => {
+            source
+                .say(
+                    ctx,
+                    JesterError::UnsuccessfulError(action.title()).localized(&locale),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    }
+
+    let guild_name = guild_id.name(ctx).unwrap_or_else(|| "a server".to_owned());
+    let notice = format!(
+        "You were {} in {}! Reason: {}",
+        action.past_tense(),
+        guild_name,
+        reason.as_deref().unwrap_or("No reason provided")
+    );
+
+    // Kicked and banned users might not share a server with the bot afterwards, so they're told first
+    let notify_first = matches!(action, ModAction::Kick | ModAction::Ban);
+
+    if notify_first {
+        let _ = user.direct_message(ctx, |m| m.content(&notice)).await;
+    }
+
+    let audit_reason = reason.as_deref().unwrap_or("No reason provided");
+    let current_time = command_utils::get_unix_time();
+
+    let result = match action {
+        ModAction::Warn => Ok(()),
+        ModAction::Kick => guild_id.kick_with_reason(ctx, user.id, audit_reason).await,
+        ModAction::Ban => {
+            guild_id
+                .ban_with_reason(ctx, user.id, 0, audit_reason)
+                .await
+        }
+        ModAction::Unban => guild_id.unban(ctx, user.id).await,
+        ModAction::Timeout => {
+            let until = Timestamp::from_unix_timestamp(current_time + duration.unwrap_or(0))?;
+
+            guild_id
+                .edit_member(ctx, user.id, |m| {
+                    m.disable_communication_until_datetime(until)
+                })
+                .await
+                .map(|_| ())
+        }
+    };
+
+    if result.is_err() {
+        if notify_first {
+            let _ = user
+                .direct_message(ctx, |m| {
+                    m.content(format!(
+                        "Never mind, the {} in {} didn't go through!",
+                        action, guild_name
+                    ))
+                })
+                .await;
+        }
+
+        source
+            .say(
+                ctx,
+                JesterError::UnsuccessfulError(action.title()).localized(&locale),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    if matches!(action, ModAction::Warn | ModAction::Timeout) {
+        let _ = user.direct_message(ctx, |m| m.content(&notice)).await;
+    }
+
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+
+    let mut case = ModCase {
+        guild_id,
+        case_number: 0,
+        action,
+        user_id: user.id,
+        moderator_id: source.author().id,
+        reason,
+        duration_secs: duration,
+        created_at: current_time,
+    };
+
+    case.case_number = repository.add_case(case.clone()).await?;
+    let case_number = case.case_number;
+
+    // The action already happened, so a failed log shouldn't fail the command
+    if let Err(e) = mod_log::log_case(ctx, &case, false).await {
+        eprintln!("Error when logging case #{}! {}", case_number, e);
+    }
+
+    source
+        .say(
+            ctx,
+            format!(
+                "{} was {} (case #{})",
+                user.tag(),
+                action.past_tense(),
+                case_number
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+// Explains why the member can't be moderated, if they can't be
+async fn check_hierarchy(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    action: ModAction,
+    member: &Member,
+    moderator: &Member,
+    bot_member: &Member,
+    locale: &str,
+) -> CommandResult<bool> {
+    let response = match moderation::get_hierarchy_error(ctx, member, moderator, bot_member)? {
+        Some(HierarchyError::Administrator) => JesterError::PermissionError(
+            PermissionType::Mention(&action.to_string(), "administrator"),
+        )
+        .localized(locale),
+        Some(HierarchyError::Moderator) => format!(
+            "You can't {} someone with the same or a higher role than you!",
+            action
+        ),
+        Some(HierarchyError::Bot) => {
+            JesterError::UnsuccessfulError(action.title()).localized(locale)
+        }
+        None => return Ok(true),
+    };
+
+    source.say(ctx, response).await?;
+
+    Ok(false)
+}
+
+/// Shows a user's moderation cases
+/// Usage: `history <user mention/ID>`
+#[command]
+#[aliases("cases")]
+#[required_permissions("MODERATE_MEMBERS")]
+async fn history(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let user_id = match args.single::<UserId>() {
+        Ok(user_id) => user_id,
+        Err(_) => {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
+            msg.channel_id
+                .say(ctx, JesterError::MissingError("user").localized(&locale))
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    history_internal(ctx, &CommandSource::Prefix(msg), user_id).await
+}
+
+async fn history_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    user_id: UserId,
+) -> CommandResult {
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+
+    let cases = repository
+        .fetch_user_cases(source.guild_id().unwrap(), user_id)
+        .await?;
+
+    if cases.is_empty() {
+        source.say(ctx, "This user doesn't have any cases!").await?;
+
+        return Ok(());
+    }
+
+    let case_list = cases
+        .iter()
+        .take(HISTORY_CASES)
+        .map(|x| {
+            format!(
+                "**Case #{}** | {} | <t:{}:R> \nModerator: {} \nReason: {}",
+                x.case_number,
+                x.action.title(),
+                x.created_at,
+                x.moderator_id.mention(),
+                command_utils::truncate(x.reason.as_deref().unwrap_or("No reason provided"), 20)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n");
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0xe74c3c);
+    eb.title("Moderation history");
+    eb.description(format!("{} \n\n{}", user_id.mention(), case_list));
+    eb.footer(|f| {
+        f.text(format!(
+            "Showing {} of {} cases. Use case <number> for details",
+            cases.len().min(HISTORY_CASES),
+            cases.len()
+        ));
+        f
+    });
+
+    source.send_embed(ctx, eb).await?;
+
+    Ok(())
+}
+
+/// Shows a moderation case
+/// Usage: `case <number>`
+#[command]
+#[required_permissions("MODERATE_MEMBERS")]
+#[sub_commands(case_reason)]
+async fn case(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let case_number = match args.single::<i64>() {
+        Ok(case_number) => case_number,
+        Err(_) => {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
+            msg.channel_id
+                .say(
+                    ctx,
+                    JesterError::MissingError("case_number").localized(&locale),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    case_internal(ctx, &CommandSource::Prefix(msg), case_number).await
+}
+
+async fn case_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    case_number: i64,
+) -> CommandResult {
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+
+    let case = match repository
+        .fetch_case(source.guild_id().unwrap(), case_number)
+        .await?
+    {
+        Some(case) => case,
+        None => {
+            source
+                .say(ctx, format!("Case #{} doesn't exist!", case_number))
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    source.send_embed(ctx, get_case_embed(&case)).await?;
+
+    Ok(())
+}
+
+pub fn get_case_embed(case: &ModCase) -> CreateEmbed {
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0xe74c3c);
+    eb.title(format!(
+        "Case #{} | {}",
+        case.case_number,
+        case.action.title()
+    ));
+    eb.field(
+        "User",
+        format!("{} ({})", case.user_id.mention(), case.user_id.0),
+        true,
+    );
+    eb.field("Moderator", case.moderator_id.mention(), true);
+
+    if let Some(duration) = case.duration_secs {
+        eb.field(
+            "Until",
+            format!("<t:{}:f>", case.created_at + duration),
+            true,
+        );
+    }
+
+    eb.field(
+        "Reason",
+        case.reason.as_deref().unwrap_or("No reason provided"),
+        false,
+    );
+    eb.field("Date", format!("<t:{}:f>", case.created_at), false);
+
+    eb
+}
+
+/// Changes the reason of a moderation case
+/// Usage: `case reason <number> <reason>`
+#[command("reason")]
+#[required_permissions("MODERATE_MEMBERS")]
+async fn case_reason(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let case_number = match args.single::<i64>() {
+        Ok(case_number) => case_number,
+        Err(_) => {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
+            msg.channel_id
+                .say(
+                    ctx,
+                    JesterError::MissingError("case_number").localized(&locale),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let reason = args.rest().trim();
+
+    if reason.is_empty() {
+        let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
+        msg.channel_id
+            .say(ctx, JesterError::MissingError("reason").localized(&locale))
+            .await?;
+
+        return Ok(());
+    }
+
+    case_reason_internal(ctx, &CommandSource::Prefix(msg), case_number, reason).await
+}
+
+async fn case_reason_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    case_number: i64,
+    reason: &str,
+) -> CommandResult {
+    if reason.chars().count() > MAX_REASON_LENGTH {
+        source
+            .say(
+                ctx,
+                format!(
+                    "Reasons can only be up to {} characters long!",
+                    MAX_REASON_LENGTH
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+
+    let guild_id = source.guild_id().unwrap();
+
+    if repository
+        .set_case_reason(guild_id, case_number, reason)
+        .await?
+    {
+        if let Some(case) = repository.fetch_case(guild_id, case_number).await? {
+            if let Err(e) = mod_log::log_case(ctx, &case, true).await {
+                eprintln!("Error when logging case #{}! {}", case_number, e);
+            }
+        }
+
+        source
+            .say(
+                ctx,
+                format!("Updated the reason for case #{}!", case_number),
+            )
+            .await?;
+    } else {
+        source
+            .say(ctx, format!("Case #{} doesn't exist!", case_number))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Shows or sets the channel where message, member, and moderation events are logged
+/// Usage: `modlog (channel)`
+#[command]
+#[required_permissions("ADMINISTRATOR")]
+#[sub_commands(modlog_disable)]
+async fn modlog(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let new_channel = match args.single::<String>() {
+        Ok(test_id) => match parse_channel(&test_id) {
+            Some(channel_id) => Some(ChannelId(channel_id)),
+            None => {
+                msg.channel_id.say(ctx, "Please mention a channel!").await?;
+
+                return Ok(());
+            }
+        },
+        Err(_) => None,
+    };
+
+    modlog_internal(ctx, &CommandSource::Prefix(msg), new_channel).await
+}
+
+async fn modlog_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    new_channel: Option<ChannelId>,
+) -> CommandResult {
+    let guild_id = source.guild_id().unwrap();
+
+    let new_channel = match new_channel {
+        Some(new_channel) => new_channel,
+        None => {
+            match mod_log::get_mod_log_channel(ctx, guild_id).await {
+                Some(channel_id) => {
+                    source
+                        .say(
+                            ctx,
+                            format!("The current mod log channel is {}", channel_id.mention()),
+                        )
+                        .await?;
+                }
+                None => {
+                    source
+                        .say(
+                            ctx,
+                            "There is no mod log channel set. Consider setting one up?",
+                        )
+                        .await?;
+                }
+            }
+
+            return Ok(());
+        }
+    };
+
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+
+    repository
+        .set_sender_channel(guild_id, SenderKind::ModLog, Some(new_channel))
+        .await?;
+    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+    source
+        .say(
+            ctx,
+            format!(
+                "Message, member, and moderation events will now be logged in {}!",
+                new_channel.mention()
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Stops logging events in the mod log channel
+#[command("disable")]
+async fn modlog_disable(ctx: &Context, msg: &Message) -> CommandResult {
+    modlog_disable_internal(ctx, &CommandSource::Prefix(msg)).await
+}
+
+async fn modlog_disable_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    repository
+        .set_sender_channel(guild_id, SenderKind::ModLog, None)
+        .await?;
+    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+    source.say(ctx, "The mod log has been disabled").await?;
+
+    Ok(())
+}
+
+pub fn moderation_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "moderation")
+}
+
+pub fn create_moderation_commands(commands: &mut CreateApplicationCommands) {
+    let actions = [
+        (ModAction::Warn, "Warns a user"),
+        (ModAction::Kick, "Kicks a user from the server"),
+        (ModAction::Ban, "Bans a user from the server"),
+        (ModAction::Unban, "Unbans a user"),
+        (ModAction::Timeout, "Stops a user from talking for a while"),
+    ];
+
+    for (action, description) in actions {
+        commands.create_application_command(|c| {
+            c.name(action)
+                .description(description)
+                .dm_permission(false)
+                .default_member_permissions(action.permission().0)
+                .create_option(|o| {
+                    o.name("user")
+                        .description("The user to moderate")
+                        .kind(CommandOptionType::User)
+                        .required(true)
+                });
+
+            if action == ModAction::Timeout {
+                c.create_option(|o| {
+                    o.name("duration")
+                        .description("How long the timeout lasts, like 10m or 1d")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                });
+            }
+
+            c.create_option(|o| {
+                o.name("reason")
+                    .description("Why this is happening")
+                    .kind(CommandOptionType::String)
+            })
+        });
+    }
+
+    commands.create_application_command(|c| {
+        c.name("history")
+            .description("Shows a user's moderation cases")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MODERATE_MEMBERS)
+            .create_option(|o| {
+                o.name("user")
+                    .description("The user to look up")
+                    .kind(CommandOptionType::User)
+                    .required(true)
+            })
+    });
+
+    commands.create_application_command(|c| {
+        c.name("case")
+            .description("Views or edits moderation cases")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MODERATE_MEMBERS)
+            .create_option(|o| {
+                o.name("view")
+                    .description("Shows a moderation case")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("number")
+                            .description("The case number")
+                            .kind(CommandOptionType::Integer)
+                            .required(true)
+                    })
+            })
+            .create_option(|o| {
+                o.name("reason")
+                    .description("Changes the reason of a moderation case")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("number")
+                            .description("The case number")
+                            .kind(CommandOptionType::Integer)
+                            .required(true)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("reason")
+                            .description("The new reason")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+            })
+    });
+
+    commands.create_application_command(|c| {
+        c.name("modlog")
+            .description("Configures the mod log channel")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .create_option(|o| {
+                o.name("channel")
+                    .description("Gets or sets the channel where events are logged")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("channel")
+                            .description("The new mod log channel")
+                            .kind(CommandOptionType::Channel)
+                            .channel_types(&[ChannelType::Text])
+                            .required(false)
+                    })
+            })
+            .create_option(|o| {
+                o.name("disable")
+                    .description("Stops logging events")
+                    .kind(CommandOptionType::SubCommand)
+            })
+    });
+}
+
+pub async fn moderation_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+    let options = &command.data.options;
+
+    match command.data.name.as_str() {
+        "history" => match interaction_utils::get_user_option(options, "user") {
+            Some(user) => history_internal(ctx, &source, user.id).await,
+            None => Ok(()),
+        },
+        "case" => {
+            let subcommand = match interaction_utils::get_subcommand(options) {
+                Some(subcommand) => subcommand,
+                None => return Ok(()),
+            };
+
+            let case_number =
+                match interaction_utils::get_integer_option(&subcommand.options, "number") {
+                    Some(case_number) => case_number,
+                    None => return Ok(()),
+                };
+
+            match subcommand.name.as_str() {
+                "view" => case_internal(ctx, &source, case_number).await,
+                "reason" => {
+                    let reason =
+                        interaction_utils::get_string_option(&subcommand.options, "reason")
+                            .unwrap_or_default();
+
+                    case_reason_internal(ctx, &source, case_number, reason.trim()).await
+                }
+                _ => Ok(()),
+            }
+        }
+        "modlog" => match interaction_utils::get_subcommand(options) {
+            Some(subcommand) if subcommand.name == "disable" => {
+                modlog_disable_internal(ctx, &source).await
+            }
+            Some(subcommand) => {
+                let new_channel =
+                    interaction_utils::get_channel_option(&subcommand.options, "channel");
+
+                modlog_internal(ctx, &source, new_channel).await
+            }
+            None => Ok(()),
+        },
+        name => {
+            let action = match name.parse::<ModAction>() {
+                Ok(action) => action,
+                Err(_) => return Ok(()),
+            };
+
+            let user = match interaction_utils::get_user_option(options, "user") {
+                Some(user) => user,
+                None => return Ok(()),
+            };
+
+            let duration = match interaction_utils::get_string_option(options, "duration") {
+                Some(duration) => match command_utils::parse_duration(duration.trim()) {
+                    Some(duration) => Some(duration),
+                    None => {
+                        source
+                            .say(
+                                ctx,
+                                format!(
+                                    "I don't understand `{}`! Use a duration like `10m` or `1d`",
+                                    duration
+                                ),
+                            )
+                            .await?;
+
+                        return Ok(());
+                    }
+                },
+                None => None,
+            };
+
+            let reason = interaction_utils::get_string_option(options, "reason")
+                .map(|x| x.trim().to_owned())
+                .filter(|x| !x.is_empty());
+
+            moderate_internal(ctx, &source, action, &user, reason, duration).await
+        }
+    }
+}
@@ -1,12 +1,36 @@
 use serenity::{
+    builder::CreateApplicationCommands,
     framework::standard::{macros::command, CommandResult},
-    model::prelude::*,
+    model::{
+        application::interaction::application_command::ApplicationCommandInteraction, prelude::*,
+    },
     prelude::*,
 };
 
+use crate::structures::command_source::CommandSource;
+
 #[command]
 async fn ping(ctx: &Context, msg: &Message) -> CommandResult {
-    msg.channel_id.say(ctx, "Pong!").await?;
+    ping_internal(ctx, &CommandSource::Prefix(msg)).await
+}
+
+async fn ping_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    source.say(ctx, "Pong!").await?;
 
     Ok(())
 }
+
+pub fn create_general_commands(commands: &mut CreateApplicationCommands) {
+    commands.create_application_command(|c| {
+        c.name("ping")
+            .description("Checks if the bot is online")
+            .dm_permission(false)
+    });
+}
+
+pub async fn general_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    ping_internal(ctx, &CommandSource::Slash(command)).await
+}
@@ -0,0 +1,173 @@
+use serenity::{
+    builder::CreateEmbed,
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+    utils::shard_id,
+};
+use std::collections::HashMap;
+
+use crate::{helpers::incidents, ConnectionPool, ShardManagerContainer};
+
+// Discord embed fields can only hold 1024 characters
+const FIELD_LIMIT: usize = 1000;
+
+// Embed descriptions can only hold 4096 characters
+const DESCRIPTION_LIMIT: usize = 4000;
+
+/// Looks up a stored error incident by its ID
+/// Usage: `incident <ID>`
+#[command]
+#[min_args(1)]
+async fn incident(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let incident_id = args.single::<String>()?;
+
+    let pool = ctx
+        .data
+        .read()
+        .await
+        .get::<ConnectionPool>()
+        .cloned()
+        .unwrap();
+
+    let incident = match incidents::fetch_incident(&pool, &incident_id).await? {
+        Some(incident) => incident,
+        None => {
+            msg.channel_id
+                .say(ctx, format!("Incident `{}` doesn't exist!", incident_id))
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let location = match incident.guild_id {
+        Some(guild_id) => format!(
+            "{} ({}) in {}",
+            guild_id
+                .name(ctx)
+                .unwrap_or_else(|| "Unknown guild".to_owned()),
+            guild_id.0,
+            incident.channel_id.mention()
+        ),
+        None => "Direct messages".to_owned(),
+    };
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0xff69b4);
+    eb.title(format!("Incident {}", incident.id));
+    eb.field("Command", &incident.command, true);
+    eb.field("User", incident.user_id.mention(), true);
+    eb.field("Time", format!("<t:{}:F>", incident.created_at), true);
+    eb.field("Location", location, false);
+    eb.field("Arguments", code_block(&incident.args), false);
+    eb.field("Error", code_block(&incident.error), false);
+    eb.field("Source chain", code_block(&incident.source_chain), false);
+
+    msg.channel_id
+        .send_message(ctx, |m| {
+            m.embed(|e| {
+                e.0 = eb.0;
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Shows the status, latency, and guild count of every shard in this process
+/// Usage: `shards`
+#[command]
+async fn shards(ctx: &Context, msg: &Message) -> CommandResult {
+    let shard_manager = ctx
+        .data
+        .read()
+        .await
+        .get::<ShardManagerContainer>()
+        .cloned()
+        .unwrap();
+
+    let shard_count = ctx.cache.shard_count();
+
+    // Guilds are counted from the cache, so only this process's shards have any
+    let mut guild_counts: HashMap<u64, usize> = HashMap::new();
+
+    for guild_id in ctx.cache.guilds() {
+        *guild_counts
+            .entry(shard_id(guild_id, shard_count))
+            .or_default() += 1;
+    }
+
+    let mut runners = {
+        let manager = shard_manager.lock().await;
+        let runners = manager.runners.lock().await;
+
+        runners
+            .iter()
+            .map(|(id, runner)| (id.0, runner.stage, runner.latency))
+            .collect::<Vec<_>>()
+    };
+    runners.sort_by_key(|(id, _, _)| *id);
+
+    let mut description = String::new();
+
+    for (id, stage, latency) in &runners {
+        let latency = match latency {
+            Some(latency) => format!("{}ms", latency.as_millis()),
+            None => "?ms".to_owned(),
+        };
+
+        let line = format!(
+            "Shard {}{}: {}, {}, {} guilds\n",
+            id,
+            if *id == ctx.shard_id {
+                " (this shard)"
+            } else {
+                ""
+            },
+            stage,
+            latency,
+            guild_counts.get(id).copied().unwrap_or_default()
+        );
+
+        if description.len() + line.len() > DESCRIPTION_LIMIT {
+            description.push_str("...");
+            break;
+        }
+
+        description.push_str(&line);
+    }
+
+    msg.channel_id
+        .send_message(ctx, |m| {
+            m.embed(|e| {
+                e.color(0xff69b4);
+                e.title(format!(
+                    "{} of {} shards in this process",
+                    runners.len(),
+                    shard_count
+                ));
+                e.description(format!("```{}```", description))
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+fn code_block(content: &str) -> String {
+    let mut content = content.replace("```", "'''");
+
+    if content.is_empty() {
+        content = "None".to_owned();
+    } else if content.chars().count() > FIELD_LIMIT {
+        content = format!(
+            "{}...",
+            content.chars().take(FIELD_LIMIT).collect::<String>()
+        );
+    }
+
+    format!("```{} \n```", content)
+}
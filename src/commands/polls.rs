@@ -0,0 +1,466 @@
+use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        prelude::*,
+    },
+    prelude::*,
+};
+
+use crate::{
+    helpers::{
+        command_utils, interaction_utils, locale, permissions_helper,
+        polls::{self, NewPoll, Poll, POLL_EMOJIS},
+    },
+    structures::command_source::CommandSource,
+    ConnectionPool,
+};
+
+// Limits for a poll's content
+const MAX_QUESTION_LENGTH: usize = 256;
+const MAX_OPTION_LENGTH: usize = 100;
+const MIN_POLL_SECS: i64 = 60;
+const MAX_POLL_SECS: i64 = 2419200;
+
+/// Starts a poll where everyone votes for one option
+/// Usage: `poll (duration) <question> | <option> | <option>`, like `poll 1d Pizza or tacos? | Pizza | Tacos`
+#[command]
+#[only_in(guilds)]
+#[sub_commands(poll_multi, poll_close, poll_results)]
+async fn poll(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    poll_from_args(ctx, msg, args, false).await
+}
+
+/// Starts a poll where everyone can vote for multiple options
+#[command("multi")]
+#[only_in(guilds)]
+async fn poll_multi(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    poll_from_args(ctx, msg, args, true).await
+}
+
+async fn poll_from_args(
+    ctx: &Context,
+    msg: &Message,
+    mut args: Args,
+    multiple_choice: bool,
+) -> CommandResult {
+    // The duration is optional, so the first word is only used if it's one
+    let duration = match args
+        .parse::<String>()
+        .ok()
+        .and_then(|x| command_utils::parse_duration(&x))
+    {
+        Some(duration) => {
+            args.advance();
+            Some(duration)
+        }
+        None => None,
+    };
+
+    let mut parts = args
+        .rest()
+        .split('|')
+        .map(|x| x.trim().to_owned())
+        .collect::<Vec<String>>();
+
+    if parts.len() < 3 {
+        msg.channel_id
+            .say(
+                ctx,
+                "Please give a question and at least two options separated by `|`! Example: `poll Pizza or tacos? | Pizza | Tacos`",
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let question = parts.remove(0);
+
+    poll_internal(
+        ctx,
+        &CommandSource::Prefix(msg),
+        question,
+        parts,
+        multiple_choice,
+        duration,
+    )
+    .await
+}
+
+async fn poll_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    question: String,
+    options: Vec<String>,
+    multiple_choice: bool,
+    duration: Option<i64>,
+) -> CommandResult {
+    if question.is_empty() || question.chars().count() > MAX_QUESTION_LENGTH {
+        source
+            .say(
+                ctx,
+                format!(
+                    "The question has to be between 1 and {} characters long!",
+                    MAX_QUESTION_LENGTH
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    if options.len() < 2 || options.len() > POLL_EMOJIS.len() {
+        source
+            .say(
+                ctx,
+                format!("Polls need between 2 and {} options!", POLL_EMOJIS.len()),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    if options
+        .iter()
+        .any(|x| x.is_empty() || x.chars().count() > MAX_OPTION_LENGTH)
+    {
+        source
+            .say(
+                ctx,
+                format!(
+                    "Each option has to be between 1 and {} characters long!",
+                    MAX_OPTION_LENGTH
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    if let Some(duration) = duration {
+        if !(MIN_POLL_SECS..=MAX_POLL_SECS).contains(&duration) {
+            source
+                .say(
+                    ctx,
+                    "Polls can last between a minute and 4 weeks! Leave out the duration to close the poll yourself",
+                )
+                .await?;
+
+            return Ok(());
+        }
+    }
+
+    let pool = ctx
+        .data
+        .read()
+        .await
+        .get::<ConnectionPool>()
+        .cloned()
+        .unwrap();
+
+    let guild_id = source.guild_id().unwrap();
+
+    let new_poll = NewPoll {
+        guild_id,
+        channel_id: source.channel_id(),
+        author_id: source.author().id,
+        question,
+        options,
+        multiple_choice,
+        end_time: duration.map(|x| command_utils::get_unix_time() + x),
+    };
+
+    let poll_id = polls::add_poll(&pool, &new_poll).await?;
+
+    let poll = Poll {
+        id: poll_id,
+        guild_id,
+        channel_id: new_poll.channel_id,
+        message_id: None,
+        author_id: new_poll.author_id,
+        question: new_poll.question,
+        options: new_poll.options,
+        multiple_choice,
+        end_time: new_poll.end_time,
+        job_id: None,
+        closed: false,
+    };
+
+    let poll_message = match source.send_embed(ctx, polls::get_poll_embed(&poll)).await {
+        Ok(poll_message) => poll_message,
+        Err(e) => {
+            polls::remove_poll(&pool, poll_id).await?;
+
+            return Err(e);
+        }
+    };
+
+    polls::set_poll_message(&pool, poll_id, guild_id, poll_message.id, poll.end_time).await?;
+
+    for emoji in POLL_EMOJIS.iter().take(poll.options.len()) {
+        poll_message
+            .react(ctx, ReactionType::Unicode(emoji.to_string()))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Closes a poll early and posts its results
+/// Only the poll's creator or moderators can close it
+#[command("close")]
+#[only_in(guilds)]
+async fn poll_close(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let poll_id = match args.single::<i64>() {
+        Ok(poll_id) => poll_id,
+        Err(_) => {
+            msg.channel_id
+                .say(ctx, "Please provide the ID of the poll to close!")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    poll_close_internal(ctx, &CommandSource::Prefix(msg), poll_id).await
+}
+
+async fn poll_close_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    poll_id: i64,
+) -> CommandResult {
+    let pool = ctx
+        .data
+        .read()
+        .await
+        .get::<ConnectionPool>()
+        .cloned()
+        .unwrap();
+
+    let poll = match polls::fetch_poll(&pool, source.guild_id().unwrap(), poll_id).await? {
+        Some(poll) => poll,
+        None => {
+            source
+                .say(
+                    ctx,
+                    format!("There's no poll with ID {} in this server!", poll_id),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    if poll.author_id != source.author().id
+        && !permissions_helper::check_permission(ctx, source, None, false).await?
+    {
+        return Ok(());
+    }
+
+    if polls::close_poll(ctx, &pool, poll).await? {
+        source
+            .acknowledge(ctx, format!("Closed poll {}!", poll_id))
+            .await?;
+    } else {
+        source.say(ctx, "That poll is already closed!").await?;
+    }
+
+    Ok(())
+}
+
+/// Shows the current results of a poll
+#[command("results")]
+#[only_in(guilds)]
+async fn poll_results(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let poll_id = match args.single::<i64>() {
+        Ok(poll_id) => poll_id,
+        Err(_) => {
+            msg.channel_id
+                .say(ctx, "Please provide the ID of the poll!")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    poll_results_internal(ctx, &CommandSource::Prefix(msg), poll_id).await
+}
+
+async fn poll_results_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    poll_id: i64,
+) -> CommandResult {
+    let pool = ctx
+        .data
+        .read()
+        .await
+        .get::<ConnectionPool>()
+        .cloned()
+        .unwrap();
+
+    let poll = match polls::fetch_poll(&pool, source.guild_id().unwrap(), poll_id).await? {
+        Some(poll) => poll,
+        None => {
+            source
+                .say(
+                    ctx,
+                    format!("There's no poll with ID {} in this server!", poll_id),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let (counts, voters) = polls::fetch_results(&pool, &poll).await?;
+
+    source
+        .send_embed(ctx, polls::get_results_embed(&poll, &counts, voters))
+        .await?;
+
+    Ok(())
+}
+
+pub fn poll_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "polls")
+}
+
+pub fn create_poll_commands(commands: &mut CreateApplicationCommands) {
+    commands.create_application_command(|c| {
+        c.name("poll")
+            .description("Starts and manages polls")
+            .dm_permission(false)
+            .create_option(|o| {
+                o.name("create")
+                    .description("Starts a poll")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("question")
+                            .description("What the poll is about")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    });
+
+                for position in 1..=POLL_EMOJIS.len() {
+                    o.create_sub_option(|so| {
+                        so.name(format!("option{}", position))
+                            .description(format!("Option {}", position))
+                            .kind(CommandOptionType::String)
+                            .required(position <= 2)
+                    });
+                }
+
+                o.create_sub_option(|so| {
+                    so.name("multiple")
+                        .description("Lets everyone vote for more than one option")
+                        .kind(CommandOptionType::Boolean)
+                })
+                .create_sub_option(|so| {
+                    so.name("duration")
+                        .description("How long the poll stays open, like 1d or 2h30m")
+                        .kind(CommandOptionType::String)
+                })
+            })
+            .create_option(|o| {
+                o.name("close")
+                    .description("Closes a poll early and posts its results")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("id")
+                            .description("The poll's ID from its footer")
+                            .kind(CommandOptionType::Integer)
+                            .required(true)
+                    })
+            })
+            .create_option(|o| {
+                o.name("results")
+                    .description("Shows the current results of a poll")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("id")
+                            .description("The poll's ID from its footer")
+                            .kind(CommandOptionType::Integer)
+                            .required(true)
+                    })
+            })
+    });
+}
+
+pub async fn poll_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+
+    let subcommand = match interaction_utils::get_subcommand(&command.data.options) {
+        Some(subcommand) => subcommand,
+        None => return Ok(()),
+    };
+
+    match subcommand.name.as_str() {
+        "create" => {
+            let question = interaction_utils::get_string_option(&subcommand.options, "question")
+                .unwrap_or_default();
+
+            let options = (1..=POLL_EMOJIS.len())
+                .filter_map(|position| {
+                    interaction_utils::get_string_option(
+                        &subcommand.options,
+                        &format!("option{}", position),
+                    )
+                })
+                .map(|x| x.trim().to_owned())
+                .collect::<Vec<String>>();
+
+            let multiple_choice =
+                interaction_utils::get_bool_option(&subcommand.options, "multiple")
+                    .unwrap_or(false);
+
+            let duration =
+                match interaction_utils::get_string_option(&subcommand.options, "duration") {
+                    Some(duration) => match command_utils::parse_duration(duration.trim()) {
+                        Some(duration) => Some(duration),
+                        None => {
+                            source
+                                .say(
+                                    ctx,
+                                    format!(
+                                    "I don't understand `{}`! Use a duration like `1d` or `2h30m`",
+                                    duration
+                                ),
+                                )
+                                .await?;
+
+                            return Ok(());
+                        }
+                    },
+                    None => None,
+                };
+
+            poll_internal(
+                ctx,
+                &source,
+                question.trim().to_owned(),
+                options,
+                multiple_choice,
+                duration,
+            )
+            .await
+        }
+        "close" => match interaction_utils::get_integer_option(&subcommand.options, "id") {
+            Some(poll_id) => poll_close_internal(ctx, &source, poll_id).await,
+            None => Ok(()),
+        },
+        "results" => match interaction_utils::get_integer_option(&subcommand.options, "id") {
+            Some(poll_id) => poll_results_internal(ctx, &source, poll_id).await,
+            None => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
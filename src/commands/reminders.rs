@@ -0,0 +1,383 @@
+use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        prelude::*,
+    },
+    prelude::*,
+};
+
+use crate::{
+    helpers::{
+        command_utils, interaction_utils, locale,
+        reminders::{self, NewReminder},
+    },
+    structures::command_source::CommandSource,
+    ConnectionPool,
+};
+
+// Limits for a user's reminders
+const MAX_REMINDERS: usize = 25;
+const MAX_CONTENT_LENGTH: usize = 1000;
+const MAX_REMINDER_SECS: i64 = 31536000;
+const MAX_PREVIEW_LENGTH: usize = 100;
+
+/// Reminds you about something after a duration or at a time
+/// Reply to a message to link it in the reminder
+/// Usage: `remind <when> <text>`, like `remind 2h30m take a break` or `remind 2026-12-25 09:00 presents`
+#[command]
+#[sub_commands(remind_list, remind_cancel)]
+async fn remind(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mut when = match args.single::<String>() {
+        Ok(when) => when,
+        Err(_) => {
+            msg.channel_id
+                .say(
+                    ctx,
+                    "Please tell me when to remind you! Example: `remind 2h30m take a break`",
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    // A date can be followed by a time of day
+    if let Ok(time) = args.parse::<String>() {
+        let full_time = format!("{} {}", when, time);
+
+        if when.contains('-') && command_utils::parse_time(&full_time, 0).is_some() {
+            args.advance();
+            when = full_time;
+        }
+    }
+
+    let message_url = match (msg.guild_id, msg.referenced_message.as_ref()) {
+        (Some(guild_id), Some(replied)) => Some(command_utils::get_message_url(
+            guild_id,
+            replied.channel_id,
+            replied.id,
+        )),
+        (Some(guild_id), None) => Some(command_utils::get_message_url(
+            guild_id,
+            msg.channel_id,
+            msg.id,
+        )),
+        (None, _) => None,
+    };
+
+    let content = args.rest().trim().to_owned();
+
+    if content.is_empty() && msg.referenced_message.is_none() {
+        msg.channel_id
+            .say(
+                ctx,
+                "Please provide what you want to be reminded about, or reply to a message!",
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    remind_internal(
+        ctx,
+        &CommandSource::Prefix(msg),
+        &when,
+        content,
+        message_url,
+    )
+    .await
+}
+
+async fn remind_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    when: &str,
+    content: String,
+    message_url: Option<String>,
+) -> CommandResult {
+    let pool = ctx
+        .data
+        .read()
+        .await
+        .get::<ConnectionPool>()
+        .cloned()
+        .unwrap();
+
+    let current_time = command_utils::get_unix_time();
+
+    // Durations too long to add to the current time are treated like any other bad input
+    let remind_at = match command_utils::parse_duration(when) {
+        Some(duration) => current_time.checked_add(duration),
+        None => command_utils::parse_time(when, current_time),
+    };
+
+    let remind_at = match remind_at {
+        Some(time) => time,
+        None => {
+            source
+                .say(
+                    ctx,
+                    format!(
+                        "I don't understand `{}`! Use a duration like `2h30m` or a UTC time like `2026-12-25 09:00`",
+                        when
+                    ),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    if remind_at <= current_time {
+        source.say(ctx, "That time has already passed!").await?;
+
+        return Ok(());
+    }
+
+    if remind_at - current_time > MAX_REMINDER_SECS {
+        source
+            .say(ctx, "Reminders can only be up to a year away!")
+            .await?;
+
+        return Ok(());
+    }
+
+    if content.chars().count() > MAX_CONTENT_LENGTH {
+        source
+            .say(
+                ctx,
+                format!(
+                    "Reminders can only be up to {} characters long!",
+                    MAX_CONTENT_LENGTH
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let user_id = source.author().id;
+
+    if reminders::fetch_reminders(&pool, user_id).await?.len() >= MAX_REMINDERS {
+        source
+            .say(
+                ctx,
+                format!(
+                    "You can only have up to {} reminders! Cancel one with `remind cancel <ID>`",
+                    MAX_REMINDERS
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let reminder = NewReminder {
+        user_id,
+        guild_id: source.guild_id(),
+        channel_id: source.channel_id(),
+        content,
+        message_url,
+        remind_at,
+    };
+
+    let reminder_id = reminders::add_reminder(&pool, reminder).await?;
+
+    source
+        .say(
+            ctx,
+            format!(
+                "Okay! I'll remind you <t:{}:R> (ID {})",
+                remind_at, reminder_id
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command("list")]
+async fn remind_list(ctx: &Context, msg: &Message) -> CommandResult {
+    remind_list_internal(ctx, &CommandSource::Prefix(msg)).await
+}
+
+async fn remind_list_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    let pool = ctx
+        .data
+        .read()
+        .await
+        .get::<ConnectionPool>()
+        .cloned()
+        .unwrap();
+
+    let reminder_data = reminders::fetch_reminders(&pool, source.author().id).await?;
+
+    if reminder_data.is_empty() {
+        source.say(ctx, "You don't have any reminders!").await?;
+
+        return Ok(());
+    }
+
+    let reminder_list = reminder_data
+        .iter()
+        .map(|x| {
+            // A single long word could still fill the embed, so the preview is capped by length too
+            let content = match command_utils::truncate(&x.content, 8) {
+                content if content.is_empty() => "A replied message".to_owned(),
+                content if content.chars().count() > MAX_PREVIEW_LENGTH => format!(
+                    "{}...",
+                    content.chars().take(MAX_PREVIEW_LENGTH).collect::<String>()
+                ),
+                content => content,
+            };
+
+            format!("`{}` <t:{}:R>: {}", x.id, x.remind_at, content)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0x3498db);
+    eb.title(format!("Reminders for {}", source.author().name));
+    eb.description(reminder_list);
+    eb.footer(|f| {
+        f.text("Cancel a reminder with remind cancel <ID>");
+        f
+    });
+
+    source.send_embed(ctx, eb).await?;
+
+    Ok(())
+}
+
+#[command("cancel")]
+async fn remind_cancel(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let reminder_id = match args.single::<i64>() {
+        Ok(reminder_id) => reminder_id,
+        Err(_) => {
+            msg.channel_id
+                .say(ctx, "Please provide the ID of the reminder to cancel!")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    remind_cancel_internal(ctx, &CommandSource::Prefix(msg), reminder_id).await
+}
+
+async fn remind_cancel_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    reminder_id: i64,
+) -> CommandResult {
+    let pool = ctx
+        .data
+        .read()
+        .await
+        .get::<ConnectionPool>()
+        .cloned()
+        .unwrap();
+
+    if reminders::cancel_reminder(&pool, source.author().id, reminder_id).await? {
+        source
+            .say(ctx, format!("Cancelled reminder {}!", reminder_id))
+            .await?;
+    } else {
+        source
+            .say(
+                ctx,
+                format!("You don't have a reminder with ID {}!", reminder_id),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub fn reminder_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "reminders")
+}
+
+pub fn create_reminder_commands(commands: &mut CreateApplicationCommands) {
+    commands.create_application_command(|c| {
+        c.name("remind")
+            .description("Reminds you about something later")
+            .create_option(|o| {
+                o.name("set")
+                    .description("Sets a new reminder")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("when")
+                            .description(
+                                "A duration like 2h30m, or a UTC time like 2026-12-25 09:00",
+                            )
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+                    .create_sub_option(|so| {
+                        so.name("text")
+                            .description("What to remind you about")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+            })
+            .create_option(|o| {
+                o.name("list")
+                    .description("Lists your reminders")
+                    .kind(CommandOptionType::SubCommand)
+            })
+            .create_option(|o| {
+                o.name("cancel")
+                    .description("Cancels one of your reminders")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("id")
+                            .description("The reminder's ID from the list")
+                            .kind(CommandOptionType::Integer)
+                            .required(true)
+                    })
+            })
+    });
+}
+
+pub async fn reminder_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+
+    let subcommand = match interaction_utils::get_subcommand(&command.data.options) {
+        Some(subcommand) => subcommand,
+        None => return Ok(()),
+    };
+
+    match subcommand.name.as_str() {
+        "set" => {
+            let when = interaction_utils::get_string_option(&subcommand.options, "when")
+                .unwrap_or_default();
+            let content = interaction_utils::get_string_option(&subcommand.options, "text")
+                .unwrap_or_default();
+
+            // Slash commands link to the bot's response since there's no invoking message
+            let message_url = match source.guild_id() {
+                Some(_) => Some(source.message_url(ctx).await?),
+                None => None,
+            };
+
+            remind_internal(ctx, &source, when.trim(), content, message_url).await
+        }
+        "list" => remind_list_internal(ctx, &source).await,
+        "cancel" => match interaction_utils::get_integer_option(&subcommand.options, "id") {
+            Some(reminder_id) => remind_cancel_internal(ctx, &source, reminder_id).await,
+            None => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
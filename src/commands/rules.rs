@@ -0,0 +1,558 @@
+use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        prelude::*,
+    },
+    prelude::*,
+    utils::parse_channel,
+};
+
+use crate::{
+    helpers::{
+        command_rules,
+        cooldowns::{self, Cooldown, CooldownScope},
+        guild_settings, interaction_utils, locale,
+    },
+    structures::command_source::CommandSource,
+    CommandScopeMap, RepositoryContainer,
+};
+
+/// Disables a command or a whole group in the server
+/// Usage: `disable <command/group>`
+#[command]
+#[required_permissions("ADMINISTRATOR")]
+#[min_args(1)]
+async fn disable(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single::<String>()?;
+
+    disable_internal(ctx, &CommandSource::Prefix(msg), &name).await
+}
+
+/// Re-enables a disabled command or group
+/// Usage: `enable <command/group>`
+#[command]
+#[required_permissions("ADMINISTRATOR")]
+#[min_args(1)]
+async fn enable(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single::<String>()?;
+
+    enable_internal(ctx, &CommandSource::Prefix(msg), &name).await
+}
+
+/// Limits a command or group to the given channels
+/// Usage: `restrict <command/group> <channel mentions>`
+#[command]
+#[required_permissions("ADMINISTRATOR")]
+#[min_args(2)]
+async fn restrict(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single::<String>()?;
+
+    let channels = args
+        .iter::<String>()
+        .filter_map(|x| x.ok())
+        .filter_map(|x| parse_channel(&x).map(ChannelId))
+        .collect::<Vec<ChannelId>>();
+
+    restrict_internal(ctx, &CommandSource::Prefix(msg), &name, channels).await
+}
+
+/// Removes all channel limits from a command or group
+/// Usage: `unrestrict <command/group>`
+#[command]
+#[required_permissions("ADMINISTRATOR")]
+#[min_args(1)]
+async fn unrestrict(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single::<String>()?;
+
+    unrestrict_internal(ctx, &CommandSource::Prefix(msg), &name).await
+}
+
+/// Sets a cooldown for a command in the server. Use reset to go back to the default
+/// Usage: `cooldown <command> <seconds/reset> (user/channel/guild)`
+#[command]
+#[required_permissions("ADMINISTRATOR")]
+#[min_args(2)]
+async fn cooldown(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single::<String>()?;
+    let seconds_string = args.single::<String>()?;
+
+    let seconds = if seconds_string == "reset" {
+        None
+    } else {
+        match seconds_string.parse::<i64>() {
+            Ok(seconds) if seconds >= 0 => Some(seconds),
+            _ => {
+                msg.channel_id
+                    .say(ctx, "Please provide a valid amount of seconds or `reset`!")
+                    .await?;
+
+                return Ok(());
+            }
+        }
+    };
+
+    let scope = match args.single::<String>() {
+        Ok(scope_string) => match scope_string.parse::<CooldownScope>() {
+            Ok(scope) => Some(scope),
+            Err(e) => {
+                msg.channel_id.say(ctx, e).await?;
+
+                return Ok(());
+            }
+        },
+        Err(_) => None,
+    };
+
+    cooldown_internal(ctx, &CommandSource::Prefix(msg), &name, seconds, scope).await
+}
+
+/// Lists all disabled and restricted commands in the server
+#[command]
+#[required_permissions("ADMINISTRATOR")]
+async fn rules(ctx: &Context, msg: &Message) -> CommandResult {
+    rules_internal(ctx, &CommandSource::Prefix(msg)).await
+}
+
+/*
+ * Gets the command or group key that a rule applies to
+ * Sends a message and returns None if the name is unknown or protected
+ */
+async fn get_rule_key(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    name: &str,
+) -> CommandResult<Option<String>> {
+    let scope_map = ctx
+        .data
+        .read()
+        .await
+        .get::<CommandScopeMap>()
+        .cloned()
+        .unwrap();
+
+    match command_rules::resolve_rule_name(&scope_map, name) {
+        Some((_, group)) if group == command_rules::PROTECTED_GROUP => {
+            source
+                .say(ctx, "Bot configuration commands can't be changed!")
+                .await?;
+
+            Ok(None)
+        }
+        Some((key, _)) => Ok(Some(key)),
+        None => {
+            source
+                .say(
+                    ctx,
+                    format!(
+                        "`{}` isn't a command or a group! Check the help command for names",
+                        name
+                    ),
+                )
+                .await?;
+
+            Ok(None)
+        }
+    }
+}
+
+async fn disable_internal(ctx: &Context, source: &CommandSource<'_>, name: &str) -> CommandResult {
+    let key = match get_rule_key(ctx, source, name).await? {
+        Some(key) => key,
+        None => return Ok(()),
+    };
+
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    repository.set_disabled(guild_id, &key, true).await?;
+    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+    source.say(ctx, format!("Disabled `{}`!", key)).await?;
+
+    Ok(())
+}
+
+async fn enable_internal(ctx: &Context, source: &CommandSource<'_>, name: &str) -> CommandResult {
+    let key = match get_rule_key(ctx, source, name).await? {
+        Some(key) => key,
+        None => return Ok(()),
+    };
+
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    repository.set_disabled(guild_id, &key, false).await?;
+    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+    source.say(ctx, format!("Enabled `{}`!", key)).await?;
+
+    Ok(())
+}
+
+async fn restrict_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    name: &str,
+    channels: Vec<ChannelId>,
+) -> CommandResult {
+    if channels.is_empty() {
+        source
+            .say(ctx, "Please provide at least one channel mention!")
+            .await?;
+
+        return Ok(());
+    }
+
+    let key = match get_rule_key(ctx, source, name).await? {
+        Some(key) => key,
+        None => return Ok(()),
+    };
+
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    repository
+        .add_command_channels(guild_id, &key, &channels)
+        .await?;
+    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+    let channel_string = channels
+        .iter()
+        .map(|x| x.mention().to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    source
+        .say(
+            ctx,
+            format!("`{}` can now be used in {}", key, channel_string),
+        )
+        .await?;
+
+    Ok(())
+}
+
+async fn unrestrict_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    name: &str,
+) -> CommandResult {
+    let key = match get_rule_key(ctx, source, name).await? {
+        Some(key) => key,
+        None => return Ok(()),
+    };
+
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    repository.clear_command_channels(guild_id, &key).await?;
+    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+    source
+        .say(ctx, format!("`{}` can now be used in every channel!", key))
+        .await?;
+
+    Ok(())
+}
+
+/*
+ * Sets or resets a guild's cooldown override for a command
+ * Cooldowns are per command, so groups aren't accepted here
+ * If a scope isn't given, use the scope of the default cooldown
+ */
+async fn cooldown_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    name: &str,
+    seconds: Option<i64>,
+    scope: Option<CooldownScope>,
+) -> CommandResult {
+    let (repository, scope_map) = {
+        let data = ctx.data.read().await;
+        let repository = data.get::<RepositoryContainer>().cloned().unwrap();
+        let scope_map = data.get::<CommandScopeMap>().cloned().unwrap();
+
+        (repository, scope_map)
+    };
+
+    let command_name = match scope_map
+        .get(&name.to_lowercase())
+        .and_then(|scopes| scopes.iter().find(|x| !x.subcommand))
+    {
+        Some(scope) if scope.group != command_rules::PROTECTED_GROUP => scope.command.to_owned(),
+        Some(_) => {
+            source
+                .say(ctx, "Bot configuration commands can't be changed!")
+                .await?;
+
+            return Ok(());
+        }
+        None => {
+            source
+                .say(ctx, format!("`{}` isn't a command!", name))
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let guild_id = source.guild_id().unwrap();
+
+    let seconds = match seconds {
+        Some(seconds) => seconds,
+        None => {
+            repository
+                .set_cooldown(guild_id, &command_name, None)
+                .await?;
+            guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+            source
+                .say(
+                    ctx,
+                    format!("Reset the cooldown for `{}` back to default", command_name),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let scope = scope
+        .or_else(|| cooldowns::get_default_cooldown(&command_name).map(|x| x.scope))
+        .unwrap_or(CooldownScope::User);
+
+    repository
+        .set_cooldown(
+            guild_id,
+            &command_name,
+            Some(Cooldown {
+                scope,
+                seconds: seconds as u64,
+            }),
+        )
+        .await?;
+    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
+
+    let message = if seconds == 0 {
+        format!("Turned off the cooldown for `{}`", command_name)
+    } else {
+        format!(
+            "`{}` now has a {} second cooldown per {}",
+            command_name, seconds, scope
+        )
+    };
+
+    source.say(ctx, message).await?;
+
+    Ok(())
+}
+
+async fn rules_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+    let guild_id = source.guild_id().unwrap();
+
+    let rules = repository.fetch_command_rules(guild_id).await?;
+    let disabled = rules.disabled;
+
+    let mut restricted: Vec<(String, Vec<String>)> = Vec::new();
+
+    for (command_name, channel_id) in rules.channels {
+        let mention = channel_id.mention().to_string();
+
+        match restricted.last_mut() {
+            Some((name, channels)) if *name == command_name => channels.push(mention),
+            _ => restricted.push((command_name, vec![mention])),
+        }
+    }
+
+    let cooldown_data = rules.cooldowns;
+
+    let disabled_string = if disabled.is_empty() {
+        "None".to_owned()
+    } else {
+        format!("```{} \n```", disabled.join(" \n"))
+    };
+
+    let restricted_string = if restricted.is_empty() {
+        "None".to_owned()
+    } else {
+        restricted
+            .iter()
+            .map(|(name, channels)| format!("`{}`: {}", name, channels.join(", ")))
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+
+    let cooldown_string = if cooldown_data.is_empty() {
+        "None".to_owned()
+    } else {
+        cooldown_data
+            .iter()
+            .map(|(name, cooldown)| {
+                format!("`{}`: {}s per {}", name, cooldown.seconds, cooldown.scope)
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+
+    let mut eb = CreateEmbed::default();
+
+    eb.title("Command rules");
+    eb.field("Disabled", disabled_string, false);
+    eb.field("Channel restrictions", restricted_string, false);
+    eb.field("Cooldown overrides", cooldown_string, false);
+
+    source.send_embed(ctx, eb).await?;
+
+    Ok(())
+}
+
+pub fn rules_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "rules")
+}
+
+pub fn create_rules_commands(commands: &mut CreateApplicationCommands) {
+    for (name, description) in [
+        (
+            "disable",
+            "Disables a command or a whole group in the server",
+        ),
+        ("enable", "Re-enables a disabled command or group"),
+        ("unrestrict", "Allows a command or group in every channel"),
+    ] {
+        commands.create_application_command(|c| {
+            c.name(name)
+                .description(description)
+                .dm_permission(false)
+                .default_member_permissions(Permissions::ADMINISTRATOR)
+                .create_option(|o| {
+                    o.name("name")
+                        .description("Name of the command or group")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                })
+        });
+    }
+
+    commands.create_application_command(|c| {
+        c.name("restrict")
+            .description("Only allows a command or group in the given channel")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .create_option(|o| {
+                o.name("name")
+                    .description("Name of the command or group")
+                    .kind(CommandOptionType::String)
+                    .required(true)
+            })
+            .create_option(|o| {
+                o.name("channel")
+                    .description("Channel to allow the command in")
+                    .kind(CommandOptionType::Channel)
+                    .channel_types(&[ChannelType::Text])
+                    .required(true)
+            })
+    });
+
+    commands.create_application_command(|c| {
+        c.name("cooldown")
+            .description("Sets a command's cooldown. Leave out seconds to reset it")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .create_option(|o| {
+                o.name("name")
+                    .description("Name of the command")
+                    .kind(CommandOptionType::String)
+                    .required(true)
+            })
+            .create_option(|o| {
+                o.name("seconds")
+                    .description("Length of the cooldown. 0 turns it off")
+                    .kind(CommandOptionType::Integer)
+                    .min_int_value(0)
+                    .required(false)
+            })
+            .create_option(|o| {
+                o.name("scope")
+                    .description("Who the cooldown applies to")
+                    .kind(CommandOptionType::String)
+                    .add_string_choice("user", "user")
+                    .add_string_choice("channel", "channel")
+                    .add_string_choice("guild", "guild")
+                    .required(false)
+            })
+    });
+
+    commands.create_application_command(|c| {
+        c.name("rules")
+            .description("Lists all disabled commands, restricted commands, and cooldowns")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+    });
+}
+
+pub async fn rules_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+    let options = &command.data.options;
+    let name = interaction_utils::get_string_option(options, "name").unwrap_or_default();
+
+    match command.data.name.as_str() {
+        "disable" => disable_internal(ctx, &source, &name).await,
+        "enable" => enable_internal(ctx, &source, &name).await,
+        "restrict" => {
+            let channels = interaction_utils::get_channel_option(options, "channel")
+                .into_iter()
+                .collect();
+
+            restrict_internal(ctx, &source, &name, channels).await
+        }
+        "unrestrict" => unrestrict_internal(ctx, &source, &name).await,
+        "cooldown" => {
+            let seconds = interaction_utils::get_integer_option(options, "seconds");
+            let scope = interaction_utils::get_string_option(options, "scope")
+                .and_then(|x| x.parse::<CooldownScope>().ok());
+
+            cooldown_internal(ctx, &source, &name, seconds, scope).await
+        }
+        "rules" => rules_internal(ctx, &source).await,
+        _ => Ok(()),
+    }
+}
@@ -1,66 +1,80 @@
 use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult, Delimiter},
     model::{
-        channel::{Message, ReactionType},
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        channel::{ChannelType, Message, ReactionType},
         id::ChannelId,
+        Permissions,
     },
-    utils::parse_channel, prelude::Mentionable,
+    prelude::Mentionable,
+    utils::parse_channel,
 };
-use sqlx::PgPool;
 use std::time::Duration;
 
-use crate::ConnectionPool;
+use crate::{
+    helpers::{guild_settings, interaction_utils, locale},
+    repositories::{Repository, SenderKind},
+    structures::command_source::CommandSource,
+    RepositoryContainer,
+};
 
 #[command]
 #[required_permissions("MANAGE_MESSAGES")]
 #[sub_commands("deactivate", "wizard", "threshold", "channel")]
 async fn starboard(ctx: &Context, msg: &Message) -> CommandResult {
-    starboard_help(ctx, msg.channel_id).await;
+    let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
+    CommandSource::Prefix(msg)
+        .send_embed(ctx, starboard_help(&locale))
+        .await?;
 
     Ok(())
 }
 
 #[command]
 async fn threshold(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let pool = ctx
+    let new_threshold = args.single::<u32>().ok().map(|threshold| threshold as i64);
+
+    threshold_internal(ctx, &CommandSource::Prefix(msg), new_threshold).await
+}
+
+async fn threshold_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    new_threshold: Option<i64>,
+) -> CommandResult {
+    let repository = ctx
         .data
         .read()
         .await
-        .get::<ConnectionPool>()
+        .get::<RepositoryContainer>()
         .cloned()
         .unwrap();
+    let guild_id = source.guild_id().unwrap();
 
-    if let Ok(new_threshold) = args.single::<u32>() {
+    if let Some(new_threshold) = new_threshold {
         if new_threshold <= 0 {
-            msg.channel_id
+            source
                 .say(ctx, "Please enter a number greater than 0!")
                 .await?;
 
             return Ok(());
         }
 
-        sqlx::query!(
-            "UPDATE guild_info SET starboard_threshold = $1 WHERE guild_id = $2",
-            new_threshold as i32,
-            msg.guild_id.unwrap().0 as i64
-        )
-        .execute(&pool)
-        .await?;
-
-        msg.channel_id
-            .say(ctx, "New threshold sucessfully set!")
+        repository
+            .set_starboard_threshold(guild_id, Some(new_threshold as i32))
             .await?;
-    } else {
-        let data = sqlx::query!(
-            "SELECT starboard_threshold FROM guild_info WHERE guild_id = $1",
-            msg.guild_id.unwrap().0 as i64
-        )
-        .fetch_one(&pool)
-        .await?;
+        guild_settings::invalidate_guild_settings(ctx, guild_id).await;
 
-        if let Some(starboard_threshold) = data.starboard_threshold {
-            msg.channel_id
+        source.say(ctx, "New threshold sucessfully set!").await?;
+    } else {
+        if let Some(starboard_threshold) = repository.fetch_starboard_threshold(guild_id).await? {
+            source
                 .say(
                     ctx,
                     format!(
@@ -70,7 +84,7 @@ async fn threshold(ctx: &Context, msg: &Message, mut args: Args) -> CommandResul
                 )
                 .await?;
         } else {
-            msg.channel_id
+            source
                 .say(ctx, "Please enter a number greater than 0!")
                 .await?;
         }
@@ -81,53 +95,61 @@ async fn threshold(ctx: &Context, msg: &Message, mut args: Args) -> CommandResul
 
 #[command]
 async fn channel(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let pool = ctx
+    let new_channel = match args.single::<String>() {
+        Ok(test_id) => match parse_channel(&test_id) {
+            Some(channel_id) => Some(ChannelId(channel_id)),
+            None => {
+                msg.channel_id.say(ctx, "Please mention a channel!").await?;
+                return Ok(());
+            }
+        },
+        Err(_) => None,
+    };
+
+    channel_internal(ctx, &CommandSource::Prefix(msg), new_channel).await
+}
+
+async fn channel_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    new_channel: Option<ChannelId>,
+) -> CommandResult {
+    let repository = ctx
         .data
         .read()
         .await
-        .get::<ConnectionPool>()
+        .get::<RepositoryContainer>()
         .cloned()
         .unwrap();
+    let guild_id = source.guild_id().unwrap();
 
-    if let Ok(test_id) = args.single::<String>() {
-        let new_channel = match parse_channel(&test_id) {
-            Some(channel_id) => channel_id,
-            None => {
-                msg.channel_id.say(ctx, "Please mention a channel!").await?;
-                return Ok(());
-            }
-        };
-
-        sqlx::query!(
-            "INSERT INTO text_channels VALUES($1, null, null, $2)
-                    ON CONFLICT (guild_id)
-                    DO UPDATE SET quote_id = $2",
-            msg.guild_id.unwrap().0 as i64,
-            new_channel as i64
-        )
-        .execute(&pool)
-        .await?;
+    if let Some(new_channel) = new_channel {
+        repository
+            .set_sender_channel(guild_id, SenderKind::Quote, Some(new_channel))
+            .await?;
+        guild_settings::invalidate_guild_settings(ctx, guild_id).await;
 
-        msg.channel_id
+        source
             .say(ctx, "New starboard channel sucessfully set!")
             .await?;
     } else {
-        let starboard_table = sqlx::query!(
-            "SELECT quote_id FROM text_channels WHERE guild_id = $1",
-            msg.guild_id.unwrap().0 as i64,
-        )
-        .fetch_one(&pool)
-        .await?;
+        let quote_channel = repository
+            .fetch_sender_channels(guild_id)
+            .await?
+            .and_then(|x| x.quote);
 
-        if let Some(quote_id) = starboard_table.quote_id {
-            msg.channel_id
+        if let Some(quote_id) = quote_channel {
+            source
                 .say(
                     ctx,
-                    format!("The current quote/starboard channel is {}", ChannelId(quote_id as u64).mention()),
+                    format!(
+                        "The current quote/starboard channel is {}",
+                        quote_id.mention()
+                    ),
                 )
                 .await?;
         } else {
-            msg.channel_id
+            source
                 .say(
                     ctx,
                     "There is no quote/starboard channel set. Consider setting one up?",
@@ -141,19 +163,23 @@ async fn channel(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult
 
 #[command]
 async fn deactivate(ctx: &Context, msg: &Message) -> CommandResult {
-    let pool = ctx
+    deactivate_internal(ctx, &CommandSource::Prefix(msg)).await
+}
+
+async fn deactivate_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    let repository = ctx
         .data
         .read()
         .await
-        .get::<ConnectionPool>()
+        .get::<RepositoryContainer>()
         .cloned()
         .unwrap();
 
-    let author_id = msg.author.id;
-    let channel_id = msg.channel_id;
+    let author_id = source.author().id;
+    let channel_id = source.channel_id();
+    let guild_id = source.guild_id().unwrap();
 
-    let sent_message = msg
-        .channel_id
+    let sent_message = source
         .say(
             ctx,
             "Removing the starboard re-enables quoting! You want to do this?",
@@ -180,38 +206,30 @@ async fn deactivate(ctx: &Context, msg: &Message) -> CommandResult {
 
             if let ReactionType::Unicode(emoji) = &reaction.emoji {
                 if emoji == "✅" {
-                    sqlx::query!(
-                        "UPDATE guild_info SET starboard_threshold = null WHERE guild_id = $1",
-                        msg.guild_id.unwrap().0 as i64
-                    )
-                    .execute(&pool)
-                    .await?;
-
-                    sqlx::query!(
-                        "UPDATE text_channels SET quote_id = null WHERE guild_id = $1",
-                        msg.guild_id.unwrap().0 as i64
-                    )
-                    .execute(&pool)
-                    .await?;
+                    repository.set_starboard_threshold(guild_id, None).await?;
+                    repository
+                        .set_sender_channel(guild_id, SenderKind::Quote, None)
+                        .await?;
+                    guild_settings::invalidate_guild_settings(ctx, guild_id).await;
 
-                    msg.channel_id
+                    channel_id
                         .say(ctx, "The starboard has been deactivated")
                         .await?;
                 } else if emoji == "❌" {
-                    msg.channel_id.say(ctx, "Aborting...").await?;
+                    channel_id.say(ctx, "Aborting...").await?;
                 } else {
-                    msg.channel_id
+                    channel_id
                         .say(ctx, "That's not a valid emoji! Aborting...")
                         .await?;
                 }
             } else {
-                msg.channel_id
+                channel_id
                     .say(ctx, "That's not a valid emoji! Aborting...")
                     .await?;
             }
         }
         None => {
-            msg.channel_id.say(ctx, "Timed out").await?;
+            channel_id.say(ctx, "Timed out").await?;
         }
     }
 
@@ -220,15 +238,19 @@ async fn deactivate(ctx: &Context, msg: &Message) -> CommandResult {
 
 #[command]
 async fn wizard(ctx: &Context, msg: &Message) -> CommandResult {
+    wizard_internal(ctx, &CommandSource::Prefix(msg)).await
+}
+
+async fn wizard_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
     let intro_string = concat!(
         "Welcome to starboard configuration \n",
         "Reacting with ✅ will disable quoting on your guild!"
     );
 
-    let author_id = msg.author.id;
-    let channel_id = msg.channel_id;
+    let author_id = source.author().id;
+    let channel_id = source.channel_id();
 
-    let sent_message = msg.channel_id.say(ctx, intro_string).await?;
+    let sent_message = source.say(ctx, intro_string).await?;
     sent_message
         .react(ctx, ReactionType::Unicode(String::from("✅")))
         .await?;
@@ -250,132 +272,124 @@ async fn wizard(ctx: &Context, msg: &Message) -> CommandResult {
 
             if let ReactionType::Unicode(emoji) = &reaction.emoji {
                 if emoji == "✅" {
-                    let pool = ctx
+                    let repository = ctx
                         .data
                         .read()
                         .await
-                        .get::<ConnectionPool>()
+                        .get::<RepositoryContainer>()
                         .cloned()
                         .unwrap();
 
-                    starboard_wizard_threshold(ctx, msg, &pool).await?
+                    starboard_wizard_threshold(ctx, source, &*repository).await?
                 } else if emoji == "❌" {
-                    msg.channel_id.say(ctx, "Aborting...").await?;
+                    channel_id.say(ctx, "Aborting...").await?;
                 } else {
-                    msg.channel_id
+                    channel_id
                         .say(ctx, "That's not a valid emoji! Aborting...")
                         .await?;
                 }
             } else {
-                msg.channel_id
+                channel_id
                     .say(ctx, "That's not a valid emoji! Aborting...")
                     .await?;
             }
         }
         None => {
-            msg.channel_id.say(ctx, "Timed out").await?;
+            channel_id.say(ctx, "Timed out").await?;
         }
     }
 
     Ok(())
 }
 
-async fn starboard_wizard_threshold(ctx: &Context, msg: &Message, pool: &PgPool) -> CommandResult {
-    msg.channel_id
+async fn starboard_wizard_threshold(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    repository: &dyn Repository,
+) -> CommandResult {
+    let channel_id = source.channel_id();
+    let guild_id = source.guild_id().unwrap();
+
+    channel_id
         .say(
             ctx,
             "Sounds good! Please enter a number greater than 0 for the starboard threshold!",
         )
         .await?;
 
-    let channel_id = msg.channel_id;
-
     loop {
-        let threshold_message = msg
-            .author
+        let threshold_message = source
+            .author()
             .await_reply(ctx)
             .timeout(Duration::from_secs(120))
             .filter(move |given_msg| given_msg.channel_id == channel_id)
             .await;
 
         match threshold_message {
-            Some(message) => {
-                match message.content.parse::<u32>() {
-                    Ok(threshold) => {
-                        if threshold > 0 {
-                            sqlx::query!("UPDATE guild_info SET starboard_threshold = $1 WHERE guild_id = $2", 
-                                    threshold as i32, msg.guild_id.unwrap().0 as i64)
-                                .execute(pool).await?;
+            Some(message) => match message.content.parse::<u32>() {
+                Ok(threshold) => {
+                    if threshold > 0 {
+                        repository
+                            .set_starboard_threshold(guild_id, Some(threshold as i32))
+                            .await?;
+                        guild_settings::invalidate_guild_settings(ctx, guild_id).await;
 
-                            break;
-                        } else {
-                            msg.channel_id
-                                .say(ctx, "Please enter an integer greater than 0!")
-                                .await?;
-                        }
-                    }
-                    Err(_) => {
-                        msg.channel_id
+                        break;
+                    } else {
+                        channel_id
                             .say(ctx, "Please enter an integer greater than 0!")
                             .await?;
                     }
                 }
-            }
+                Err(_) => {
+                    channel_id
+                        .say(ctx, "Please enter an integer greater than 0!")
+                        .await?;
+                }
+            },
             None => {
-                msg.channel_id.say(ctx, "Timed out").await?;
+                channel_id.say(ctx, "Timed out").await?;
 
                 return Ok(());
             }
         }
     }
 
-    starboard_wizard_channel(ctx, msg, pool).await?;
+    starboard_wizard_channel(ctx, source, repository).await?;
 
     Ok(())
 }
 
-async fn starboard_wizard_channel(ctx: &Context, msg: &Message, pool: &PgPool) -> CommandResult {
-    let mut channel_check = false;
-
-    let row_check = sqlx::query!(
-        "SELECT EXISTS(SELECT 1 FROM text_channels WHERE guild_id = $1)",
-        msg.guild_id.unwrap().0 as i64
-    )
-    .fetch_one(pool)
-    .await?;
-
-    if row_check.exists.unwrap() {
-        let query = sqlx::query!(
-            "SELECT quote_id FROM text_channels WHERE guild_id = $1",
-            msg.guild_id.unwrap().0 as i64
-        )
-        .fetch_one(pool)
-        .await?;
+async fn starboard_wizard_channel(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    repository: &dyn Repository,
+) -> CommandResult {
+    let channel_id = source.channel_id();
+    let guild_id = source.guild_id().unwrap();
 
-        if query.quote_id.is_some() {
-            channel_check = true;
-        } else {
-            channel_check = false;
-        }
-    };
+    let channel_check = repository
+        .fetch_sender_channels(guild_id)
+        .await?
+        .and_then(|x| x.quote)
+        .is_some();
 
     if channel_check {
         let send_string = concat!(
             "You already have a channel set up for quotes! \nIf you want to change it, run `starboard channel <mention>` \n",
             "Enjoy your new starboard!");
-        msg.channel_id.say(ctx, send_string).await?;
+        channel_id.say(ctx, send_string).await?;
     } else {
-        msg.channel_id
+        channel_id
             .say(
                 ctx,
                 "Now please mention the channel you want messages sent to!",
             )
             .await?;
-        let channel_id = msg.channel_id;
 
         loop {
-            let channel_message = msg
-                .author
+            let channel_message = source
+                .author()
                 .await_reply(ctx)
                 .timeout(Duration::from_secs(120))
                 .filter(move |given_msg| given_msg.channel_id == channel_id)
@@ -387,29 +401,28 @@ async fn starboard_wizard_channel(ctx: &Context, msg: &Message, pool: &PgPool) -
                     let given_id = args.parse::<String>().unwrap();
 
                     match parse_channel(given_id) {
-                        Some(channel_id) => {
-                            sqlx::query!(
-                                "INSERT INTO text_channels VALUES($1, null, null, $2)
-                                        ON CONFLICT (guild_id)
-                                        DO UPDATE SET quote_id = $2",
-                                msg.guild_id.unwrap().0 as i64,
-                                channel_id as i64
-                            )
-                            .execute(pool)
-                            .await?;
+                        Some(new_channel_id) => {
+                            repository
+                                .set_sender_channel(
+                                    guild_id,
+                                    SenderKind::Quote,
+                                    Some(ChannelId(new_channel_id)),
+                                )
+                                .await?;
+                            guild_settings::invalidate_guild_settings(ctx, guild_id).await;
 
-                            msg.channel_id.say(ctx, "Enjoy your new starboard!").await?;
+                            channel_id.say(ctx, "Enjoy your new starboard!").await?;
                             break;
                         }
                         None => {
-                            msg.channel_id
+                            channel_id
                                 .say(ctx, "Please mention a channel in this guild!")
                                 .await?;
                         }
                     }
                 }
                 None => {
-                    msg.channel_id.say(ctx, "Timed out").await?;
+                    channel_id.say(ctx, "Timed out").await?;
 
                     return Ok(());
                 }
@@ -420,26 +433,77 @@ async fn starboard_wizard_channel(ctx: &Context, msg: &Message, pool: &PgPool) -
     Ok(())
 }
 
-pub async fn starboard_help(ctx: &Context, channel_id: ChannelId) {
-    let content = concat!(
-        "wizard: Easy way to setup the starboard \n\n",
-        "threshold: Sets the threshold for a message to appear \n\n",
-        "channel: Sets the channel where starboard embeds are sent \n\n",
-        "deactivate: Deactivates the starboard and re-enables quoting"
-    );
-
-    let _ = channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("Starboard Help");
-                e.description("Description: admin commands for starboarding in a discord server");
-                e.field("Commands", content, false);
-                e.footer(|f| {
-                    f.text("Enabling the starboard will disable the quote command!");
-                    f
-                });
-                e
+pub fn create_starboard_commands(commands: &mut CreateApplicationCommands) {
+    commands.create_application_command(|c| {
+        c.name("starboard")
+            .description("Starboard admin commands")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MANAGE_MESSAGES)
+            .create_option(|o| {
+                o.name("wizard")
+                    .description("Easy way to setup the starboard")
+                    .kind(CommandOptionType::SubCommand)
             })
-        })
-        .await;
+            .create_option(|o| {
+                o.name("threshold")
+                    .description("Gets or sets the threshold for a message to appear")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("stars")
+                            .description("The new threshold")
+                            .kind(CommandOptionType::Integer)
+                            .min_int_value(1)
+                            .required(false)
+                    })
+            })
+            .create_option(|o| {
+                o.name("channel")
+                    .description("Gets or sets the channel where starboard embeds are sent")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|so| {
+                        so.name("channel")
+                            .description("The new starboard channel")
+                            .kind(CommandOptionType::Channel)
+                            .channel_types(&[ChannelType::Text])
+                            .required(false)
+                    })
+            })
+            .create_option(|o| {
+                o.name("deactivate")
+                    .description("Deactivates the starboard and re-enables quoting")
+                    .kind(CommandOptionType::SubCommand)
+            })
+    });
+}
+
+pub async fn starboard_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+
+    let subcommand = match interaction_utils::get_subcommand(&command.data.options) {
+        Some(subcommand) => subcommand,
+        None => return Ok(()),
+    };
+
+    match subcommand.name.as_str() {
+        "wizard" => wizard_internal(ctx, &source).await,
+        "threshold" => {
+            let new_threshold = interaction_utils::get_integer_option(&subcommand.options, "stars");
+
+            threshold_internal(ctx, &source, new_threshold).await
+        }
+        "channel" => {
+            let new_channel = interaction_utils::get_channel_option(&subcommand.options, "channel");
+
+            channel_internal(ctx, &source, new_channel).await
+        }
+        "deactivate" => deactivate_internal(ctx, &source).await,
+        _ => Ok(()),
+    }
+}
+
+pub fn starboard_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "starboard")
 }
@@ -0,0 +1,189 @@
+use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
+    framework::standard::{macros::command, CommandResult},
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        prelude::*,
+    },
+    prelude::*,
+};
+
+use crate::{
+    helpers::{analytics, command_utils, interaction_utils, locale},
+    structures::command_source::CommandSource,
+    BotOwners, ConnectionPool,
+};
+
+// Stats only cover the last 30 days
+const STATS_PERIOD: i64 = 2592000;
+
+/// Shows the most used commands, top users, and error rates in the server
+/// Usage: `stats` or `stats global` for every server (Bot dev only)
+#[command]
+#[only_in(guilds)]
+#[required_permissions("MANAGE_MESSAGES")]
+#[sub_commands(global)]
+async fn stats(ctx: &Context, msg: &Message) -> CommandResult {
+    stats_internal(ctx, &CommandSource::Prefix(msg), false).await
+}
+
+#[command]
+#[owners_only(true)]
+async fn global(ctx: &Context, msg: &Message) -> CommandResult {
+    stats_internal(ctx, &CommandSource::Prefix(msg), true).await
+}
+
+async fn stats_internal(ctx: &Context, source: &CommandSource<'_>, global: bool) -> CommandResult {
+    let pool = ctx
+        .data
+        .read()
+        .await
+        .get::<ConnectionPool>()
+        .cloned()
+        .unwrap();
+
+    let guild_id = if global { None } else { source.guild_id() };
+    let since = command_utils::get_unix_time() - STATS_PERIOD;
+
+    let (total_uses, total_errors) = analytics::fetch_totals(&pool, guild_id, since).await?;
+
+    if total_uses == 0 {
+        source
+            .say(ctx, "No commands have been used in the last 30 days!")
+            .await?;
+
+        return Ok(());
+    }
+
+    let command_stats = analytics::fetch_command_stats(&pool, guild_id, since, 100).await?;
+    let user_stats = analytics::fetch_user_stats(&pool, guild_id, since, 5).await?;
+
+    let top_commands = command_stats
+        .iter()
+        .take(10)
+        .map(|x| {
+            format!(
+                "`{}`: {} uses, {} errors, {}ms average",
+                x.command,
+                x.uses,
+                get_percentage(x.errors, x.uses),
+                x.average_ms
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let mut failing_stats = command_stats
+        .iter()
+        .filter(|x| x.errors > 0)
+        .collect::<Vec<_>>();
+    failing_stats.sort_by(|a, b| (b.errors * a.uses).cmp(&(a.errors * b.uses)));
+
+    let failing_commands = if failing_stats.is_empty() {
+        "None!".to_owned()
+    } else {
+        failing_stats
+            .iter()
+            .take(5)
+            .map(|x| {
+                format!(
+                    "`{}`: {} of {} uses failed ({})",
+                    x.command,
+                    x.errors,
+                    x.uses,
+                    get_percentage(x.errors, x.uses)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+
+    let top_users = user_stats
+        .iter()
+        .map(|x| format!("{}: {} uses", x.user_id.mention(), x.uses))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let title = match guild_id {
+        Some(guild_id) => format!(
+            "Command stats for {}",
+            guild_id
+                .name(ctx)
+                .unwrap_or_else(|| "this server".to_owned())
+        ),
+        None => "Global command stats".to_owned(),
+    };
+
+    let mut eb = CreateEmbed::default();
+
+    eb.title(title);
+    eb.color(0xfda50f);
+    eb.description(format!(
+        "{} commands used with {} errors ({})",
+        total_uses,
+        total_errors,
+        get_percentage(total_errors, total_uses)
+    ));
+    eb.field("Top commands", top_commands, false);
+    eb.field("Top users", top_users, false);
+    eb.field("Highest error rates", failing_commands, false);
+    eb.footer(|f| {
+        f.text("Stats are from the last 30 days");
+        f
+    });
+
+    source.send_embed(ctx, eb).await?;
+
+    Ok(())
+}
+
+fn get_percentage(part: i64, total: i64) -> String {
+    if total == 0 {
+        return "0%".to_owned();
+    }
+
+    format!("{:.1}%", part as f64 / total as f64 * 100.0)
+}
+
+pub fn stats_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "stats")
+}
+
+pub fn create_stats_commands(commands: &mut CreateApplicationCommands) {
+    commands.create_application_command(|c| {
+        c.name("stats")
+            .description("Shows the most used commands, top users, and error rates")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MANAGE_MESSAGES)
+            .create_option(|o| {
+                o.name("global")
+                    .description("Show stats for every server (Bot dev only)")
+                    .kind(CommandOptionType::Boolean)
+                    .required(false)
+            })
+    });
+}
+
+pub async fn stats_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+    let global =
+        interaction_utils::get_bool_option(&command.data.options, "global").unwrap_or(false);
+
+    if global {
+        let owners = ctx.data.read().await.get::<BotOwners>().cloned().unwrap();
+
+        if !owners.contains(&command.user.id) {
+            source.say(ctx, "This is a bot dev only command!").await?;
+
+            return Ok(());
+        }
+    }
+
+    stats_internal(ctx, &source, global).await
+}
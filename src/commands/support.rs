@@ -1,131 +1,303 @@
+use serde_json::Value;
 use serenity::{
-    builder::CreateEmbed,
-    framework::standard::{macros::command, Args, CommandResult},
-    model::prelude::*,
+    builder::{CreateApplicationCommands, CreateEmbed},
+    framework::standard::{macros::command, Args, CommandGroup, CommandResult, OnlyIn},
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        prelude::*,
+    },
     prelude::*,
 };
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     commands::{
-        ciphers::*, config::*, images::*, japan::*, /*music::*,*/ starboard::*,
+        automod::*, backup::*, ciphers::*, config::*, images::*, japan::*, moderation::*,
+        /*music::*,*/ polls::*, reminders::*, rules::*, starboard::*, stats::*,
         textchannel_send::*, textmod::*, utility::*,
     },
-    helpers::{botinfo::*, command_utils /*, voice_utils::* */},
+    helpers::{
+        botinfo::*, command_rules::CommandScope, command_utils, guild_settings, interaction_utils,
+        locale, /* voice_utils::* */
+    },
+    structures::{command_source::CommandSource, commands::MASTER_GROUP},
+    CommandScopeMap, UnavailableCommands,
 };
 
+// Each help category with the command group it belongs to
+const HELP_CATEGORIES: [(&str, &str); 17] = [
+    ("prefix", "botconfiguration"),
+    ("language", "botconfiguration"),
+    ("command", "botconfiguration"),
+    ("rules", "botconfiguration"),
+    ("backup", "botconfiguration"),
+    ("starboard", "starboard"),
+    ("utilities", "utility"),
+    ("reminders", "utility"),
+    ("polls", "utility"),
+    ("moderation", "moderation"),
+    ("automod", "moderation"),
+    ("senders", "senders"),
+    ("ciphers", "ciphers"),
+    ("text", "textmodification"),
+    // ("voice", "voice"),
+    // ("music", "music"),
+    ("images", "images"),
+    ("japan", "japan"),
+    ("stats", "support"),
+];
+
 #[command]
 async fn help(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    if args.is_empty() {
-        if command_utils::check_mention_prefix(msg) {
-            emergency_help_message(ctx, msg.channel_id).await;
-        } else {
-            default_help_message(ctx, msg.channel_id).await;
-        }
+    if args.is_empty() && command_utils::check_mention_prefix(msg) {
+        let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
+        msg.channel_id
+            .send_message(ctx, |m| {
+                m.embed(|e| {
+                    e.0 = emergency_help_message(&locale).0;
+                    e
+                })
+            })
+            .await?;
 
         return Ok(());
     }
 
-    let subcommand = args.single::<String>()?;
-
-    match subcommand.as_str() {
-        "prefix" => prefix_help(ctx, msg.channel_id).await,
-        "command" => command_help(ctx, msg.channel_id).await,
-        "starboard" => starboard_help(ctx, msg.channel_id).await,
-        "utilities" => utility_help(ctx, msg.channel_id).await,
-        "senders" => sender_help(ctx, msg.channel_id).await,
-        "ciphers" => cipher_help(ctx, msg.channel_id).await,
-        "text" => textmod_help(ctx, msg.channel_id).await,
-        // "voice" => voice_help(ctx, msg.channel_id).await,
-        // "music" => music_help(ctx, msg.channel_id).await,
-        "images" => image_help(ctx, msg.channel_id).await,
-        "japan" => japan_help(ctx, msg.channel_id).await,
-        _ => {}
+    let subcommand = args.single::<String>().ok();
+
+    help_internal(ctx, &CommandSource::Prefix(msg), subcommand).await
+}
+
+async fn help_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    subcommand: Option<String>,
+) -> CommandResult {
+    let (scope_map, unavailable_commands) = {
+        let data = ctx.data.read().await;
+        let scope_map = data.get::<CommandScopeMap>().cloned().unwrap();
+        let unavailable_commands = data.get::<UnavailableCommands>().cloned().unwrap();
+
+        (scope_map, unavailable_commands)
+    };
+
+    let mut disabled = match source.guild_id() {
+        Some(guild_id) => guild_settings::get_guild_settings(ctx, guild_id)
+            .await?
+            .command_rules
+            .disabled
+            .iter()
+            .cloned()
+            .collect(),
+        None => HashSet::new(),
+    };
+
+    // Commands without an API key are hidden like disabled ones
+    disabled.extend(unavailable_commands.iter().cloned());
+
+    let locale = locale::get_guild_locale(ctx, source.guild_id()).await;
+
+    let category = match subcommand.as_deref() {
+        Some(category) => category,
+        None => {
+            source
+                .send_embed(ctx, default_help_message(&locale, &disabled))
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    // Disabled groups don't get any help
+    match HELP_CATEGORIES.iter().find(|(name, _)| *name == category) {
+        Some((_, group)) if !disabled.contains(*group) => {}
+        _ => return Ok(()),
+    }
+
+    let mut help_embed = match category {
+        "prefix" => prefix_help(&locale),
+        "language" => language_help(&locale),
+        "command" => command_help(&locale),
+        "rules" => rules_help(&locale),
+        "backup" => backup_help(&locale),
+        "starboard" => starboard_help(&locale),
+        "utilities" => utility_help(&locale),
+        "reminders" => reminder_help(&locale),
+        "polls" => poll_help(&locale),
+        "moderation" => moderation_help(&locale),
+        "automod" => automod_help(&locale),
+        "senders" => sender_help(&locale),
+        "ciphers" => cipher_help(&locale),
+        "text" => textmod_help(&locale),
+        // "voice" => voice_help(),
+        // "music" => music_help(),
+        "images" => image_help(&locale),
+        "japan" => japan_help(&locale),
+        "stats" => stats_help(&locale),
+        _ => return Ok(()),
+    };
+
+    if !disabled.is_empty() {
+        hide_disabled_commands(&mut help_embed, &scope_map, &disabled);
     }
 
+    source.send_embed(ctx, help_embed).await?;
+
     Ok(())
 }
 
-async fn emergency_help_message(ctx: &Context, channel_id: ChannelId) {
-    let content = concat!(
-        "prefix (characters): Sets the server's bot prefix \n\n",
-        "resetprefix: Reset's the server's prefix back to the default one"
-    );
+/*
+ * Help entries are separated by blank lines and start with the command name
+ * Remove every entry where the command is disabled in the guild
+ */
+fn hide_disabled_commands(
+    help_embed: &mut CreateEmbed,
+    scope_map: &HashMap<String, Vec<CommandScope>>,
+    disabled: &HashSet<String>,
+) {
+    let fields = match help_embed
+        .0
+        .get_mut("fields")
+        .and_then(|x| x.as_array_mut())
+    {
+        Some(fields) => fields,
+        None => return,
+    };
+
+    for field in fields {
+        let content = match field.get("value").and_then(|x| x.as_str()) {
+            Some(content) => content,
+            None => continue,
+        };
+
+        let new_content = content
+            .split("\n\n")
+            .filter(|entry| {
+                let name = entry.split(&[' ', ':'][..]).next().unwrap_or_default();
 
-    let _ = channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("CourtJester Emergency Help");
-                e.description("You should only use this if you mess up your prefix!");
-                e.field("Commands", content, false);
-                e
+                match scope_map.get(name) {
+                    Some(scopes) => !scopes.iter().all(|x| disabled.contains(&x.command)),
+                    None => true,
+                }
             })
-        })
-        .await;
+            .collect::<Vec<&str>>()
+            .join("\n\n");
+
+        field["value"] = Value::String(new_content);
+    }
+}
+
+fn emergency_help_message(locale: &str) -> CreateEmbed {
+    let mut eb = CreateEmbed::default();
+
+    eb.title(locale::get_text(locale, "help.emergency.title"));
+    eb.description(locale::get_text(locale, "help.emergency.description"));
+    eb.field(
+        locale::get_text(locale, "help.commands"),
+        locale::get_text(locale, "help.emergency.commands"),
+        false,
+    );
+
+    eb
 }
 
-async fn default_help_message(ctx: &Context, channel_id: ChannelId) {
-    let categories = concat!(
-        "prefix \n",
-        "command \n",
-        "starboard \n",
-        "utilities \n",
-        "senders \n",
-        "ciphers \n",
-        "text \n",
-        // "voice \n",
-        // "music \n",
-        "images \n",
-        "japan \n"
+fn default_help_message(locale: &str, disabled: &HashSet<String>) -> CreateEmbed {
+    let categories = HELP_CATEGORIES
+        .iter()
+        .filter(|(_, group)| !disabled.contains(*group))
+        .map(|(name, _)| *name)
+        .collect::<Vec<&str>>()
+        .join(" \n");
+
+    let dm_commands = get_dm_commands(MASTER_GROUP.options.sub_groups, disabled).join(", ");
+
+    let mut eb = CreateEmbed::default();
+
+    eb.title(locale::get_text(locale, "help.default.title"));
+    eb.description(locale::get_formatted(
+        locale,
+        "help.default.description",
+        &[("commands", &dm_commands)],
+    ));
+    eb.field(
+        locale::get_text(locale, "help.default.subcategories"),
+        format!("```\n{} \n```", categories),
+        false,
     );
+    eb.footer(|f| {
+        f.text(locale::get_text(locale, "help.default.footer"));
+        f
+    });
 
-    let _ = channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("CourtJester Help");
-                e.description(concat!(
-                    "Help for the CourtJester Discord bot \n",
-                    "Command parameters: <> is required and () is optional \n",
-                    "Please use `help <subcategory>` to see that category's help \n\n",
-                    "Looking for music? It's gone for now until I get time to update it."
-                ));
-                e.field("Subcategories", format!("```\n{}```", categories), false);
-                e.footer(|f| {
-                    f.text("Use the support command for any further help!");
-                    f
-                });
-                e
-            })
+    eb
+}
+
+/*
+ * Lists the commands the framework lets through in DMs
+ * Guild only groups/commands and ones that need permissions are skipped
+ */
+fn get_dm_commands(
+    groups: &[&'static CommandGroup],
+    disabled: &HashSet<String>,
+) -> Vec<&'static str> {
+    groups
+        .iter()
+        .filter(|group| {
+            group.options.only_in != OnlyIn::Guild
+                && group.options.help_available
+                && !group.options.owners_only
+        })
+        .flat_map(|group| group.options.commands)
+        .filter(|command| {
+            command.options.only_in != OnlyIn::Guild
+                && command.options.help_available
+                && !command.options.owners_only
+                && command.options.required_permissions.is_empty()
         })
-        .await;
+        .map(|command| command.options.names[0])
+        .filter(|name| !disabled.contains(*name))
+        .collect()
 }
 
 #[command]
+#[only_in(guilds)]
 async fn support(ctx: &Context, msg: &Message) -> CommandResult {
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("CourtJester Support");
-                e.description("Need more help?");
-                e.field("Support Server", "https://discord.gg/pswt7by", false);
-                e.field(
-                    "Github repository",
-                    "https://github.com/bdashore3/courtjester",
-                    false,
-                );
-                e.field("kingbri's twitter", "https://twitter.com/kingbri1st", false);
-                e.footer(|f| {
-                    f.text("Created with ❤️ by kingbri#6666");
-                    f
-                })
-            })
-        })
-        .await?;
+    support_internal(ctx, &CommandSource::Prefix(msg)).await
+}
+
+async fn support_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    let mut eb = CreateEmbed::default();
+
+    eb.title("CourtJester Support");
+    eb.description("Need more help?");
+    eb.field("Support Server", "https://discord.gg/pswt7by", false);
+    eb.field(
+        "Github repository",
+        "https://github.com/bdashore3/courtjester",
+        false,
+    );
+    eb.field("kingbri's twitter", "https://twitter.com/kingbri1st", false);
+    eb.footer(|f| {
+        f.text("Created with ❤️ by kingbri#6666");
+        f
+    });
+
+    source.send_embed(ctx, eb).await?;
 
     Ok(())
 }
 
 #[command]
+#[only_in(guilds)]
 async fn info(ctx: &Context, msg: &Message) -> CommandResult {
+    info_internal(ctx, &CommandSource::Prefix(msg)).await
+}
+
+async fn info_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
     let mut eb = CreateEmbed::default();
 
     let guild_count = ctx.cache.guilds().len();
@@ -156,14 +328,83 @@ async fn info(ctx: &Context, msg: &Message) -> CommandResult {
     eb.color(0xfda50f);
     eb.description(story_string);
 
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.0 = eb.0;
-                e
-            })
-        })
-        .await?;
+    source.send_embed(ctx, eb).await?;
 
     Ok(())
 }
+
+pub fn create_support_commands(commands: &mut CreateApplicationCommands) {
+    commands.create_application_command(|c| {
+        c.name("help")
+            .description("Help for the CourtJester Discord bot")
+            .create_option(|o| {
+                o.name("category")
+                    .description("The category to get help for")
+                    .kind(CommandOptionType::String)
+                    .required(false);
+
+                for (category, _) in HELP_CATEGORIES {
+                    o.add_string_choice(category, category);
+                }
+
+                o
+            })
+    });
+
+    commands.create_application_command(|c| {
+        c.name("support")
+            .description("Support links for the bot")
+            .dm_permission(false)
+    });
+
+    commands.create_application_command(|c| {
+        c.name("info")
+            .description("Information about the bot")
+            .dm_permission(false)
+    });
+}
+
+pub async fn support_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+
+    match command.data.name.as_str() {
+        "help" => {
+            let category = interaction_utils::get_string_option(&command.data.options, "category");
+
+            help_internal(ctx, &source, category).await
+        }
+        "support" => support_internal(ctx, &source).await,
+        "info" => info_internal(ctx, &source).await,
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dm_commands_follow_the_framework_checks() {
+        let dm_commands = get_dm_commands(MASTER_GROUP.options.sub_groups, &HashSet::new());
+
+        for name in ["mock", "b64encode", "help", "anime", "avatar", "remind"] {
+            assert!(dm_commands.contains(&name), "{} should work in DMs", name);
+        }
+
+        for name in ["poll", "kang", "stats", "support", "warn", "ping", "mockl"] {
+            assert!(
+                !dm_commands.contains(&name),
+                "{} shouldn't work in DMs",
+                name
+            );
+        }
+
+        let disabled = HashSet::from(["anime".to_owned()]);
+        let dm_commands = get_dm_commands(MASTER_GROUP.options.sub_groups, &disabled);
+
+        assert!(!dm_commands.contains(&"anime"));
+    }
+}
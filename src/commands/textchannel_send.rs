@@ -1,97 +1,95 @@
 use rand::prelude::*;
 use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
     framework::standard::{macros::command, Args, CommandResult},
-    model::prelude::*,
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        prelude::*,
+    },
     prelude::*,
     utils::{parse_channel, parse_username},
 };
-use sqlx::PgPool;
 use std::time::Duration;
 use tokio::time::sleep;
 
 use crate::{
-    helpers::{command_utils, permissions_helper},
-    ConnectionPool,
+    helpers::{guild_settings, interaction_utils, locale, permissions_helper},
+    repositories::SenderKind,
+    structures::command_source::CommandSource,
+    RepositoryContainer,
 };
 
-struct TextChannels {
-    nice_id: Option<i64>,
-    bruh_id: Option<i64>,
-    quote_id: Option<i64>,
-}
-
 /// Sends `nice` to a specified channel. Provide a channel as the first argument to set it
 /// Usage: `nice <message>` or `nice <channel>`
 #[command]
 async fn nice(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
-
     let test_id = args.single::<String>().unwrap_or_default();
 
-    let pool = ctx
+    let new_channel = parse_channel(&test_id).map(ChannelId);
+
+    if new_channel.is_none() && !args.is_empty() {
+        msg.channel_id
+            .say(ctx, "Please execute this command without any arguments")
+            .await?;
+        return Ok(());
+    }
+
+    nice_internal(ctx, &CommandSource::Prefix(msg), new_channel).await
+}
+
+async fn nice_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    new_channel: Option<ChannelId>,
+) -> CommandResult {
+    let guild_id = source.guild_id().unwrap();
+
+    let repository = ctx
         .data
         .read()
         .await
-        .get::<ConnectionPool>()
+        .get::<RepositoryContainer>()
         .cloned()
         .unwrap();
 
-    let check = sqlx::query!(
-        "SELECT EXISTS(SELECT nice_id FROM text_channels WHERE guild_id = $1)",
-        guild_id.0 as i64
-    )
-    .fetch_one(&pool)
-    .await?;
-
-    if let Some(channel_id) = parse_channel(&test_id) {
-        if permissions_helper::check_permission(ctx, msg, None, false).await? {
-            if check.exists.unwrap() {
-                sqlx::query!(
-                    "UPDATE text_channels SET nice_id = $1 WHERE guild_id = $2",
-                    channel_id as i64,
-                    guild_id.0 as i64
-                )
-                .execute(&pool)
+    let channels = guild_settings::get_guild_settings(ctx, guild_id)
+        .await?
+        .sender_channels;
+
+    if let Some(channel_id) = new_channel {
+        if permissions_helper::check_permission(ctx, source, None, false).await? {
+            repository
+                .set_sender_channel(guild_id, SenderKind::Nice, Some(channel_id))
                 .await?;
-            } else {
-                insert_or_update(&pool, guild_id, "nice", channel_id as i64).await?;
-            }
+            guild_settings::invalidate_guild_settings(ctx, guild_id).await;
 
-            msg.channel_id.say(ctx, "Channel sucessfully set!").await?;
+            source.say(ctx, "Channel sucessfully set!").await?;
         }
 
         return Ok(());
     }
 
-    if !args.is_empty() {
-        msg.channel_id
-            .say(ctx, "Please execute this command without any arguments")
-            .await?;
-        return Ok(());
-    }
-
-    if !check.exists.unwrap() {
-        msg.channel_id
-            .say(ctx, "The Nice channel isn't set! Please specify a channel!")
-            .await?;
-        return Ok(());
-    }
-
-    let channel_num = get_channels(&pool, guild_id).await?;
+    let nice_id = match channels.and_then(|x| x.nice) {
+        Some(nice_id) => nice_id,
+        None => {
+            source
+                .say(ctx, "The Nice channel isn't set! Please specify a channel!")
+                .await?;
+            return Ok(());
+        }
+    };
 
-    if channel_num.nice_id.is_none() {
-        msg.channel_id
-            .say(ctx, "The Nice channel isn't set! Please specify a channel!")
-            .await?;
-        return Ok(());
-    }
+    source.acknowledge(ctx, "Nice!").await?;
 
-    let message_url = command_utils::get_message_url(msg.guild_id.unwrap(), msg.channel_id, msg.id);
-    ChannelId(channel_num.nice_id.unwrap() as u64)
+    let message_url = source.message_url(ctx).await?;
+    nice_id
         .send_message(ctx, |m| {
             m.embed(|e| {
                 e.color(0x290e05);
-                e.title(format!("Nice - {}", msg.author.name));
+                e.title(format!("Nice - {}", source.author().name));
                 e.field("Source", format!("[Jump!]({})", message_url), false)
             })
         })
@@ -104,79 +102,73 @@ async fn nice(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
 /// Usage: `bruh <message>` or `bruh <channel>`
 #[command]
 async fn bruh(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
-
     let test_id = args.single::<String>().unwrap_or_default();
 
-    let pool = ctx
+    let new_channel = parse_channel(&test_id).map(ChannelId);
+
+    if new_channel.is_none() && !args.is_empty() {
+        msg.channel_id
+            .say(ctx, "Please execute this command without any arguments")
+            .await?;
+        return Ok(());
+    }
+
+    bruh_internal(ctx, &CommandSource::Prefix(msg), new_channel).await
+}
+
+async fn bruh_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    new_channel: Option<ChannelId>,
+) -> CommandResult {
+    let guild_id = source.guild_id().unwrap();
+
+    let repository = ctx
         .data
         .read()
         .await
-        .get::<ConnectionPool>()
+        .get::<RepositoryContainer>()
         .cloned()
         .unwrap();
 
-    let check = sqlx::query!(
-        "SELECT EXISTS(SELECT bruh_id FROM text_channels WHERE guild_id = $1)",
-        guild_id.0 as i64
-    )
-    .fetch_one(&pool)
-    .await?;
-
-    if let Some(channel_id) = parse_channel(&test_id) {
-        if permissions_helper::check_permission(ctx, msg, None, false).await? {
-            if check.exists.unwrap() {
-                sqlx::query!(
-                    "UPDATE text_channels SET bruh_id = $1 WHERE guild_id = $2",
-                    channel_id as i64,
-                    guild_id.0 as i64
-                )
-                .execute(&pool)
+    let channels = guild_settings::get_guild_settings(ctx, guild_id)
+        .await?
+        .sender_channels;
+
+    if let Some(channel_id) = new_channel {
+        if permissions_helper::check_permission(ctx, source, None, false).await? {
+            repository
+                .set_sender_channel(guild_id, SenderKind::Bruh, Some(channel_id))
                 .await?;
-            } else {
-                insert_or_update(&pool, guild_id, "bruh", channel_id as i64).await?;
-            }
+            guild_settings::invalidate_guild_settings(ctx, guild_id).await;
 
-            msg.channel_id.say(ctx, "Channel sucessfully set!").await?;
+            source.say(ctx, "Channel sucessfully set!").await?;
         }
 
         return Ok(());
     }
 
-    if !args.is_empty() {
-        msg.channel_id
-            .say(ctx, "Please execute this command without any arguments")
-            .await?;
-        return Ok(());
-    }
-
-    if !check.exists.unwrap() {
-        msg.channel_id
-            .say(ctx, "The Bruh channel isn't set! Please specify a channel!")
-            .await?;
-        return Ok(());
-    }
-
-    let channel_nums = get_channels(&pool, guild_id).await?;
-
-    if channel_nums.bruh_id.is_none() {
-        msg.channel_id
-            .say(ctx, "The Bruh channel isn't set! Please specify a channel!")
-            .await?;
-        return Ok(());
-    }
+    let bruh_id = match channels.and_then(|x| x.bruh) {
+        Some(bruh_id) => bruh_id,
+        None => {
+            source
+                .say(ctx, "The Bruh channel isn't set! Please specify a channel!")
+                .await?;
+            return Ok(());
+        }
+    };
 
-    let message_url = command_utils::get_message_url(msg.guild_id.unwrap(), msg.channel_id, msg.id);
+    source.say(ctx, "***BRUH MOMENT***").await?;
 
-    msg.channel_id.say(ctx, "***BRUH MOMENT***").await?;
-    ChannelId(channel_nums.bruh_id.unwrap() as u64)
+    let message_url = source.message_url(ctx).await?;
+    bruh_id
         .send_message(ctx, |m| {
             m.embed(|e| {
                 e.color(0xfc5e03);
                 e.title("Ladies and Gentlemen!");
                 e.description(format!(
                     "A bruh moment has been declared by {}",
-                    msg.author.mention()
+                    source.author().mention()
                 ));
                 e.field("Source", format!("[Jump!]({})", message_url), false)
             })
@@ -190,28 +182,48 @@ async fn bruh(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
 /// Usage: `quote <user mention> <content>` or `quote <content>`
 #[command]
 async fn quote(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
-    let mut self_quote = true;
-
     let test_id = args.parse::<String>().unwrap_or_default();
 
-    let pool = ctx
+    let new_channel = parse_channel(&test_id).map(ChannelId);
+
+    let quoted_user = if parse_username(&test_id).is_some() {
+        args.advance();
+        msg.mentions.first().cloned()
+    } else {
+        None
+    };
+
+    quote_internal(
+        ctx,
+        &CommandSource::Prefix(msg),
+        new_channel,
+        quoted_user,
+        args.rest(),
+    )
+    .await
+}
+
+async fn quote_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    new_channel: Option<ChannelId>,
+    quoted_user: Option<User>,
+    content: &str,
+) -> CommandResult {
+    let guild_id = source.guild_id().unwrap();
+
+    let repository = ctx
         .data
         .read()
         .await
-        .get::<ConnectionPool>()
+        .get::<RepositoryContainer>()
         .cloned()
         .unwrap();
 
-    let starboard_data = sqlx::query!(
-        "SELECT starboard_threshold FROM guild_info WHERE guild_id = $1",
-        guild_id.0 as i64
-    )
-    .fetch_one(&pool)
-    .await?;
+    let settings = guild_settings::get_guild_settings(ctx, guild_id).await?;
 
-    if starboard_data.starboard_threshold.is_some() {
-        msg.channel_id
+    if settings.starboard_threshold.is_some() {
+        source
             .say(
                 ctx,
                 "You can't use the quote command because starboard is enabled in this server!",
@@ -220,35 +232,23 @@ async fn quote(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
         return Ok(());
     }
 
-    let check = sqlx::query!(
-        "SELECT EXISTS(SELECT quote_id FROM text_channels WHERE guild_id = $1)",
-        guild_id.0 as i64
-    )
-    .fetch_one(&pool)
-    .await?;
-
-    if let Some(channel_id) = parse_channel(&test_id) {
-        if permissions_helper::check_permission(ctx, msg, None, false).await? {
-            if check.exists.unwrap() {
-                sqlx::query!(
-                    "UPDATE text_channels SET quote_id = $1 WHERE guild_id = $2",
-                    channel_id as i64,
-                    guild_id.0 as i64
-                )
-                .execute(&pool)
+    let channels = settings.sender_channels;
+
+    if let Some(channel_id) = new_channel {
+        if permissions_helper::check_permission(ctx, source, None, false).await? {
+            repository
+                .set_sender_channel(guild_id, SenderKind::Quote, Some(channel_id))
                 .await?;
-            } else {
-                insert_or_update(&pool, guild_id, "quote", channel_id as i64).await?;
-            }
+            guild_settings::invalidate_guild_settings(ctx, guild_id).await;
 
-            msg.channel_id.say(ctx, "Channel sucessfully set!").await?;
+            source.say(ctx, "Channel sucessfully set!").await?;
         }
 
         return Ok(());
     }
 
-    if !check.exists.unwrap() {
-        msg.channel_id
+    if channels.is_none() {
+        source
             .say(
                 ctx,
                 "The Quote channel isn't set! Please specify a channel!",
@@ -257,12 +257,8 @@ async fn quote(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
         return Ok(());
     }
 
-    if parse_username(&test_id).is_some() {
-        self_quote = false;
-    }
-
-    if args.is_empty() || test_id.is_empty() {
-        msg.channel_id
+    if content.is_empty() {
+        source
             .say(
                 ctx,
                 "Please provide the quote (with author if you are quoting someone else)",
@@ -271,56 +267,35 @@ async fn quote(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
         return Ok(());
     }
 
-    let channels = get_channels(&pool, guild_id).await?;
-
-    if channels.quote_id.is_none() {
-        msg.channel_id
-            .say(
-                ctx,
-                "The Quote channel isn't set! Please specify a channel!",
-            )
-            .await?;
-        return Ok(());
-    }
-
-    let message_url = command_utils::get_message_url(msg.guild_id.unwrap(), msg.channel_id, msg.id);
-
-    let avatar_option = if self_quote {
-        msg.author.avatar_url()
-    } else {
-        msg.mentions[0].avatar_url()
-    };
-
-    let avatar_id = match avatar_option {
-        Some(avatar_id) => avatar_id,
+    let quote_id = match channels.and_then(|x| x.quote) {
+        Some(quote_id) => quote_id,
         None => {
-            if self_quote {
-                msg.author.default_avatar_url()
-            } else {
-                msg.mentions[0].default_avatar_url()
-            }
+            source
+                .say(
+                    ctx,
+                    "The Quote channel isn't set! Please specify a channel!",
+                )
+                .await?;
+            return Ok(());
         }
     };
 
-    ChannelId(channels.quote_id.unwrap() as u64)
+    source.acknowledge(ctx, "Quote sent!").await?;
+
+    let message_url = source.message_url(ctx).await?;
+
+    let quoted_user = quoted_user.as_ref().unwrap_or_else(|| source.author());
+
+    quote_id
         .send_message(ctx, |m| {
             m.embed(|e| {
                 e.color(0xfabe21);
-                if self_quote {
-                    e.author(|a| {
-                        a.name(&msg.author.name);
-                        a.icon_url(&avatar_id);
-                        a
-                    });
-                } else {
-                    e.author(|a| {
-                        a.name(&msg.mentions[0].name);
-                        a.icon_url(&avatar_id);
-                        a
-                    });
-                    args.advance();
-                }
-                e.description(args.rest());
+                e.author(|a| {
+                    a.name(&quoted_user.name);
+                    a.icon_url(quoted_user.face());
+                    a
+                });
+                e.description(content);
                 e.field("Source", format!("[Jump!]({})", message_url), false)
             })
         })
@@ -331,128 +306,120 @@ async fn quote(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
 
 #[command]
 async fn vibecheck(ctx: &Context, msg: &Message) -> CommandResult {
-    msg.channel_id.say(ctx, "Initiating vibe check...").await?;
+    vibecheck_internal(ctx, &CommandSource::Prefix(msg)).await
+}
+
+async fn vibecheck_internal(ctx: &Context, source: &CommandSource<'_>) -> CommandResult {
+    let locale = locale::get_guild_locale(ctx, source.guild_id()).await;
+    let user = source.author().mention().to_string();
+
+    source
+        .say(ctx, locale::get_text(&locale, "vibecheck.start"))
+        .await?;
 
     sleep(Duration::from_secs(3)).await;
 
     if random() {
-        let success_vec = vec![
-            "Continue vibing good sir/madam",
-            "Have a wonderful day",
-            "Your wish will come true",
-            "STRAIGHT vibing! I like that",
-            "Drop your favorite vibes in the chat",
-        ];
+        let success_vec = locale::get_list(&locale, "vibecheck.phrases");
 
         let mut rng = StdRng::from_entropy();
 
         let val = rng.gen_range(0..=success_vec.len() - 1);
 
-        msg.channel_id
+        source
             .say(
                 ctx,
-                format!(
-                    "{} has passed the vibe check. {}.",
-                    msg.author.mention(),
-                    success_vec[val]
+                locale::get_formatted(
+                    &locale,
+                    "vibecheck.pass",
+                    &[("user", &user), ("phrase", &success_vec[val])],
                 ),
             )
             .await?;
     } else {
-        msg.channel_id
+        source
             .say(
                 ctx,
-                format!(
-                    "{} has failed the vibe check. Show me your vibing license!",
-                    msg.author.mention()
-                ),
+                locale::get_formatted(&locale, "vibecheck.fail", &[("user", &user)]),
             )
             .await?;
     }
     Ok(())
 }
 
-async fn get_channels(
-    pool: &PgPool,
-    guild_id: GuildId,
-) -> Result<TextChannels, Box<dyn std::error::Error + Send + Sync>> {
-    let data = sqlx::query_as!(
-        TextChannels,
-        "SELECT nice_id, bruh_id, quote_id FROM text_channels WHERE guild_id = $1",
-        guild_id.0 as i64
-    )
-    .fetch_one(pool)
-    .await?;
+pub fn create_sender_commands(commands: &mut CreateApplicationCommands) {
+    for (name, description) in [
+        ("nice", "Sends nice to a defined channel"),
+        ("bruh", "Sends a bruh moment to a defined channel"),
+    ] {
+        commands.create_application_command(|c| {
+            c.name(name)
+                .description(description)
+                .dm_permission(false)
+                .create_option(|o| {
+                    o.name("channel")
+                        .description("Sets the sender channel (Moderator only)")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text])
+                        .required(false)
+                })
+        });
+    }
+
+    commands.create_application_command(|c| {
+        c.name("quote")
+            .description("Quotes yourself or a user. Deactivated when starboard is enabled")
+            .dm_permission(false)
+            .create_option(|o| {
+                o.name("text")
+                    .description("The text to quote")
+                    .kind(CommandOptionType::String)
+                    .required(false)
+            })
+            .create_option(|o| {
+                o.name("user")
+                    .description("The user who said the quote")
+                    .kind(CommandOptionType::User)
+                    .required(false)
+            })
+            .create_option(|o| {
+                o.name("channel")
+                    .description("Sets the quote channel (Moderator only)")
+                    .kind(CommandOptionType::Channel)
+                    .channel_types(&[ChannelType::Text])
+                    .required(false)
+            })
+    });
 
-    Ok(data)
+    commands.create_application_command(|c| {
+        c.name("vibecheck")
+            .description("Checks your vibe. Try it out!")
+            .dm_permission(false)
+    });
 }
 
-async fn insert_or_update(
-    pool: &PgPool,
-    guild_id: GuildId,
-    channel_type: &str,
-    channel_id: i64,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    match channel_type {
-        "nice" => {
-            sqlx::query!(
-                "INSERT INTO text_channels VALUES($1, $2, null, null)
-                        ON CONFLICT (guild_id)
-                        DO UPDATE SET nice_id = $2",
-                guild_id.0 as i64,
-                channel_id
-            )
-            .execute(pool)
-            .await?;
-        }
-        "bruh" => {
-            sqlx::query!(
-                "INSERT INTO text_channels VALUES($1, null, $2, null)
-                        ON CONFLICT (guild_id)
-                        DO UPDATE SET bruh_id = $2",
-                guild_id.0 as i64,
-                channel_id
-            )
-            .execute(pool)
-            .await?;
-        }
+pub async fn sender_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+    let options = &command.data.options;
+    let new_channel = interaction_utils::get_channel_option(options, "channel");
+
+    match command.data.name.as_str() {
+        "nice" => nice_internal(ctx, &source, new_channel).await,
+        "bruh" => bruh_internal(ctx, &source, new_channel).await,
         "quote" => {
-            sqlx::query!(
-                "INSERT INTO text_channels VALUES($1, null, null, $2)
-                        ON CONFLICT (guild_id)
-                        DO UPDATE SET quote_id = $2",
-                guild_id.0 as i64,
-                channel_id
-            )
-            .execute(pool)
-            .await?;
+            let quoted_user = interaction_utils::get_user_option(options, "user");
+            let content = interaction_utils::get_string_option(options, "text").unwrap_or_default();
+
+            quote_internal(ctx, &source, new_channel, quoted_user, &content).await
         }
-        _ => {}
+        "vibecheck" => vibecheck_internal(ctx, &source).await,
+        _ => Ok(()),
     }
-
-    Ok(())
 }
 
-pub async fn sender_help(ctx: &Context, channel_id: ChannelId) {
-    let content = concat!(
-        "nice: Sends nice to a defined channel \n\n",
-        "bruh: Sends a bruh moment to a defined channel \n\n",
-        "quote <author> <text>: Quotes a user. Deactivated when starboard is enabled \n\n",
-        "vibecheck: Checks your vibe. Try it out!"
-    );
-
-    let _ = channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("Textchannel Sender Help");
-                e.description("Description: Commands that send messages to specified channels");
-                e.field("Commands", content, false);
-                e.footer(|f| {
-                    f.text("Adding a channel mention will set the sender channel (Moderator only)");
-                    f
-                });
-                e
-            })
-        })
-        .await;
+pub fn sender_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "senders")
 }
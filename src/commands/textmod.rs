@@ -1,10 +1,17 @@
 use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
     framework::standard::{macros::command, Args, CommandResult},
-    model::prelude::*,
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        prelude::*,
+    },
     prelude::*,
 };
 
-use crate::{helpers::*, JesterError};
+use crate::{helpers::*, structures::command_source::CommandSource, JesterError};
 
 /// Outputs a spongebob mock string
 /// Usage: `mock <message>`
@@ -12,8 +19,10 @@ use crate::{helpers::*, JesterError};
 #[min_args(1)]
 pub async fn mock(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     if args.is_empty() {
+        let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
         msg.channel_id
-            .say(ctx, JesterError::MissingError("string to mock"))
+            .say(ctx, JesterError::MissingError("mock").localized(&locale))
             .await?;
     } else {
         let mock_string = textmod_helper::get_mock_string(args.rest());
@@ -26,12 +35,9 @@ pub async fn mock(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 
 #[command]
 pub async fn mockl(ctx: &Context, msg: &Message) -> CommandResult {
-    let input_message = msg
-        .channel_id
-        .messages(ctx, |retriever| retriever.before(msg.id).limit(1))
-        .await?;
+    let input_message = get_last_content(ctx, &CommandSource::Prefix(msg)).await?;
 
-    let mock_string = textmod_helper::get_mock_string(&input_message[0].content);
+    let mock_string = textmod_helper::get_mock_string(&input_message);
 
     msg.channel_id.say(ctx, mock_string).await?;
 
@@ -44,8 +50,10 @@ pub async fn mockl(ctx: &Context, msg: &Message) -> CommandResult {
 #[min_args(1)]
 async fn inv(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     if args.is_empty() {
+        let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
         msg.channel_id
-            .say(ctx, JesterError::MissingError("string to invert"))
+            .say(ctx, JesterError::MissingError("invert").localized(&locale))
             .await?;
     } else {
         let inv_string = textmod_helper::get_inverted_string(args.rest());
@@ -58,12 +66,9 @@ async fn inv(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 
 #[command]
 async fn invl(ctx: &Context, msg: &Message) -> CommandResult {
-    let input_message = msg
-        .channel_id
-        .messages(ctx, |retriever| retriever.before(msg.id).limit(1))
-        .await?;
+    let input_message = get_last_content(ctx, &CommandSource::Prefix(msg)).await?;
 
-    let inv_string = textmod_helper::get_inverted_string(&input_message[0].content);
+    let inv_string = textmod_helper::get_inverted_string(&input_message);
 
     msg.channel_id.say(ctx, inv_string).await?;
 
@@ -76,8 +81,13 @@ async fn invl(ctx: &Context, msg: &Message) -> CommandResult {
 #[min_args(1)]
 async fn upp(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     if args.is_empty() {
+        let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
         msg.channel_id
-            .say(ctx, JesterError::MissingError("string to uppercase"))
+            .say(
+                ctx,
+                JesterError::MissingError("uppercase").localized(&locale),
+            )
             .await?;
     } else {
         msg.channel_id.say(ctx, args.rest().to_uppercase()).await?;
@@ -88,13 +98,10 @@ async fn upp(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 
 #[command]
 async fn uppl(ctx: &Context, msg: &Message) -> CommandResult {
-    let input_message = msg
-        .channel_id
-        .messages(ctx, |retriever| retriever.before(msg.id).limit(1))
-        .await?;
+    let input_message = get_last_content(ctx, &CommandSource::Prefix(msg)).await?;
 
     msg.channel_id
-        .say(ctx, input_message[0].content.to_uppercase())
+        .say(ctx, input_message.to_uppercase())
         .await?;
 
     Ok(())
@@ -106,8 +113,13 @@ async fn uppl(ctx: &Context, msg: &Message) -> CommandResult {
 #[min_args(1)]
 async fn low(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     if args.is_empty() {
+        let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
         msg.channel_id
-            .say(ctx, JesterError::MissingError("string to lowercase"))
+            .say(
+                ctx,
+                JesterError::MissingError("lowercase").localized(&locale),
+            )
             .await?;
     } else {
         msg.channel_id.say(ctx, args.rest().to_lowercase()).await?;
@@ -118,13 +130,10 @@ async fn low(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 
 #[command]
 async fn lowl(ctx: &Context, msg: &Message) -> CommandResult {
-    let input_message = msg
-        .channel_id
-        .messages(ctx, |retriever| retriever.before(msg.id).limit(1))
-        .await?;
+    let input_message = get_last_content(ctx, &CommandSource::Prefix(msg)).await?;
 
     msg.channel_id
-        .say(ctx, input_message[0].content.to_lowercase())
+        .say(ctx, input_message.to_lowercase())
         .await?;
 
     Ok(())
@@ -136,8 +145,10 @@ async fn lowl(ctx: &Context, msg: &Message) -> CommandResult {
 #[min_args(1)]
 async fn space(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     if args.is_empty() {
+        let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
         msg.channel_id
-            .say(ctx, JesterError::MissingError("string to space out"))
+            .say(ctx, JesterError::MissingError("space").localized(&locale))
             .await?;
     } else {
         let spaced_string = textmod_helper::get_spaced_string(args.rest(), false);
@@ -150,12 +161,9 @@ async fn space(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 
 #[command]
 async fn spacel(ctx: &Context, msg: &Message) -> CommandResult {
-    let input_message = msg
-        .channel_id
-        .messages(ctx, |retriever| retriever.before(msg.id).limit(1))
-        .await?;
+    let input_message = get_last_content(ctx, &CommandSource::Prefix(msg)).await?;
 
-    let spaced_string = textmod_helper::get_spaced_string(&input_message[0].content, false);
+    let spaced_string = textmod_helper::get_spaced_string(&input_message, false);
 
     msg.channel_id.say(ctx, spaced_string).await?;
 
@@ -169,10 +177,12 @@ async fn spacel(ctx: &Context, msg: &Message) -> CommandResult {
 #[min_args(1)]
 async fn biggspace(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     if args.is_empty() {
+        let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
         msg.channel_id
             .say(
                 ctx,
-                JesterError::MissingError("string to B I G G S P A C E"),
+                JesterError::MissingError("biggspace").localized(&locale),
             )
             .await?;
     } else {
@@ -186,12 +196,9 @@ async fn biggspace(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 
 #[command]
 async fn biggspacel(ctx: &Context, msg: &Message) -> CommandResult {
-    let input_message = msg
-        .channel_id
-        .messages(ctx, |retriever| retriever.before(msg.id).limit(1))
-        .await?;
+    let input_message = get_last_content(ctx, &CommandSource::Prefix(msg)).await?;
 
-    let bigspace_string = textmod_helper::get_spaced_string(&input_message[0].content, true);
+    let bigspace_string = textmod_helper::get_spaced_string(&input_message, true);
 
     msg.channel_id.say(ctx, bigspace_string).await?;
 
@@ -201,8 +208,10 @@ async fn biggspacel(ctx: &Context, msg: &Message) -> CommandResult {
 #[command]
 async fn h4ck(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     if args.is_empty() {
+        let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
         msg.channel_id
-            .say(ctx, JesterError::MissingError("string to h4ck"))
+            .say(ctx, JesterError::MissingError("h4ck").localized(&locale))
             .await?;
     } else {
         let hacked_string = textmod_helper::get_hacked_string(args.rest());
@@ -216,8 +225,10 @@ async fn h4ck(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 #[command]
 async fn uwu(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     if args.is_empty() {
+        let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
         msg.channel_id
-            .say(ctx, JesterError::MissingError("string to uwu"))
+            .say(ctx, JesterError::MissingError("uwu").localized(&locale))
             .await?;
     } else {
         let uwu_string = textmod_helper::get_uwu_string(args.rest());
@@ -228,33 +239,102 @@ async fn uwu(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     Ok(())
 }
 
-pub async fn textmod_help(ctx: &Context, channel_id: ChannelId) {
-    let content = concat!(
-        "mock <message>: Spongebob mocks a string \n\n",
-        "inv <message>: Inverts capitalization of each letter in the message \n\n",
-        "upp <message>: Every letter becomes uppercase \n\n",
-        "low <message>: Every letter becomes lowercase \n\n",
-        "space <message>: Spaces out each letter in the message (whitespace omitted) \n\n",
-        "biggspace <message>: Same as space, but W I D E R \n\n",
-        "h4ck <message>: Become a hackerman by making h4ck3d w0rd5 \n\n",
-        "uwu <message>: Translate to the uwu wanguwage uwu"
-    );
-
-    let _ = channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("Text Modification Help");
-                e.description("Description: Commands that modify text");
-                e.field("Commands", content, false);
-                e.footer(|f| {
-                    f.text(concat!(
-                        "Putting an l in front of any command",
-                        "(except h4ck and uwu) will use the last message"
-                    ));
-                    f
+// Fetches the content of the message sent before the invocation
+async fn get_last_content(ctx: &Context, source: &CommandSource<'_>) -> CommandResult<String> {
+    let message_id = source.message_id(ctx).await?;
+
+    let input_message = source
+        .channel_id()
+        .messages(ctx, |retriever| retriever.before(message_id).limit(1))
+        .await?;
+
+    match input_message.into_iter().next() {
+        Some(message) => Ok(message.content),
+        None => Err("There is no previous message in this channel!".into()),
+    }
+}
+
+pub fn create_textmod_commands(commands: &mut CreateApplicationCommands) {
+    let textmod_commands = [
+        ("mock", "Spongebob mocks a string", true),
+        (
+            "inv",
+            "Inverts capitalization of each letter in the message",
+            true,
+        ),
+        ("upp", "Every letter becomes uppercase", true),
+        ("low", "Every letter becomes lowercase", true),
+        ("space", "Spaces out each letter in the message", true),
+        ("biggspace", "Same as space, but W I D E R", true),
+        ("h4ck", "Become a hackerman by making h4ck3d w0rd5", false),
+        ("uwu", "Translate to the uwu wanguwage uwu", false),
+    ];
+
+    for (name, description, has_last) in textmod_commands {
+        commands.create_application_command(|c| {
+            c.name(name).description(description);
+            c.create_option(|o| {
+                o.name("text")
+                    .description("The text to modify")
+                    .kind(CommandOptionType::String)
+                    .required(!has_last)
+            });
+
+            if has_last {
+                c.create_option(|o| {
+                    o.name("last")
+                        .description("Use the last message in the channel instead")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
                 });
-                e
-            })
-        })
-        .await;
+            }
+
+            c
+        });
+    }
+}
+
+pub async fn textmod_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+    let options = &command.data.options;
+
+    let input = if interaction_utils::get_bool_option(options, "last").unwrap_or(false) {
+        get_last_content(ctx, &source).await?
+    } else {
+        match interaction_utils::get_string_option(options, "text") {
+            Some(text) => text,
+            None => {
+                let locale = locale::get_guild_locale(ctx, source.guild_id()).await;
+
+                source
+                    .say(ctx, JesterError::MissingError("text").localized(&locale))
+                    .await?;
+
+                return Ok(());
+            }
+        }
+    };
+
+    let output = match command.data.name.as_str() {
+        "mock" => textmod_helper::get_mock_string(&input),
+        "inv" => textmod_helper::get_inverted_string(&input),
+        "upp" => input.to_uppercase(),
+        "low" => input.to_lowercase(),
+        "space" => textmod_helper::get_spaced_string(&input, false),
+        "biggspace" => textmod_helper::get_spaced_string(&input, true),
+        "h4ck" => textmod_helper::get_hacked_string(&input),
+        "uwu" => textmod_helper::get_uwu_string(&input),
+        _ => return Ok(()),
+    };
+
+    source.say(ctx, output).await?;
+
+    Ok(())
+}
+
+pub fn textmod_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "text")
 }
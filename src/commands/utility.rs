@@ -1,31 +1,55 @@
 use std::borrow::Cow;
 
 use serenity::{
+    builder::{CreateApplicationCommands, CreateEmbed},
     framework::standard::{macros::command, Args, CommandResult},
-    model::prelude::*,
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::ApplicationCommandInteraction,
+        },
+        prelude::*,
+    },
     prelude::*,
+    utils::parse_emoji,
 };
 
-use crate::{helpers::command_utils::fetch_avatar, JesterError};
+use crate::{
+    helpers::{command_utils::fetch_avatar, interaction_utils, locale},
+    structures::command_source::CommandSource,
+    JesterError,
+};
 
 #[command]
 async fn avatar(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let avatar_url = if let Ok(user_id) = args.single::<UserId>() {
-        fetch_avatar(ctx, user_id, None).await
+    let user_id = if let Ok(user_id) = args.single::<UserId>() {
+        user_id
     } else if args.is_empty() {
-        fetch_avatar(ctx, msg.author.id, None).await
+        msg.author.id
     } else {
+        let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
         msg.channel_id
-            .say(ctx, JesterError::MissingError("User ID/mention"))
+            .say(ctx, JesterError::MissingError("user").localized(&locale))
             .await?;
 
         return Ok(());
     };
 
+    avatar_internal(ctx, &CommandSource::Prefix(msg), user_id).await
+}
+
+async fn avatar_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    user_id: UserId,
+) -> CommandResult {
+    let avatar_url = fetch_avatar(ctx, user_id, None).await;
+
     match avatar_url {
-        Some(url) => msg.channel_id.say(ctx, url).await?,
+        Some(url) => source.say(ctx, url).await?,
         None => {
-            msg.channel_id
+            source
                 .say(ctx, "No avatar could be found for this user!")
                 .await?
         }
@@ -35,26 +59,38 @@ async fn avatar(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
 }
 
 #[command]
+#[only_in(guilds)]
 #[aliases("gavatar")]
 async fn guild_avatar(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let guild = msg.guild(ctx).unwrap();
-
-    let avatar_url = if let Ok(user_id) = args.single::<UserId>() {
-        fetch_avatar(ctx, user_id, Some(guild)).await
+    let user_id = if let Ok(user_id) = args.single::<UserId>() {
+        user_id
     } else if args.is_empty() {
-        fetch_avatar(ctx, msg.author.id, Some(guild)).await
+        msg.author.id
     } else {
+        let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
         msg.channel_id
-            .say(ctx, JesterError::MissingError("User ID/mention"))
+            .say(ctx, JesterError::MissingError("user").localized(&locale))
             .await?;
 
         return Ok(());
     };
 
+    guild_avatar_internal(ctx, &CommandSource::Prefix(msg), user_id).await
+}
+
+async fn guild_avatar_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    user_id: UserId,
+) -> CommandResult {
+    let guild = source.guild_id().unwrap().to_guild_cached(ctx).unwrap();
+
+    let avatar_url = fetch_avatar(ctx, user_id, Some(guild)).await;
+
     match avatar_url {
-        Some(url) => msg.channel_id.say(ctx, url).await?,
-        None => msg
-            .channel_id
+        Some(url) => source.say(ctx, url).await?,
+        None => source
             .say(
                 ctx,
                 "No guild avatar could be found for this member! Use the `avatar` command instead!",
@@ -66,23 +102,37 @@ async fn guild_avatar(ctx: &Context, msg: &Message, mut args: Args) -> CommandRe
 }
 
 #[command]
+#[only_in(guilds)]
 #[aliases("steal")]
 #[required_permissions("MANAGE_EMOJIS_AND_STICKERS")]
 async fn kang(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let emoji = match args.single::<EmojiIdentifier>() {
         Ok(id) => id,
         Err(_) => {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
             msg.channel_id
-                .say(ctx, JesterError::MissingError("custom emoji"))
+                .say(ctx, JesterError::MissingError("emoji").localized(&locale))
                 .await?;
 
             return Ok(());
         }
     };
 
-    let guild = msg.guild(ctx).unwrap();
+    let name = args.single::<String>().ok();
+
+    kang_internal(ctx, &CommandSource::Prefix(msg), emoji, name).await
+}
+
+async fn kang_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    emoji: EmojiIdentifier,
+    name: Option<String>,
+) -> CommandResult {
+    let guild = source.guild_id().unwrap().to_guild_cached(ctx).unwrap();
     if guild.emojis.contains_key(&emoji.id) {
-        msg.channel_id
+        source
             .say(ctx, "This emoji already exists in this server! Aborting...")
             .await?;
 
@@ -98,11 +148,11 @@ async fn kang(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let encoded_bytes = base64::encode(image_bytes);
     let formatted_bytes = format!("data:image/{};base64,{}", ext, encoded_bytes);
 
-    let name = args.single::<String>().unwrap_or(emoji.name);
+    let name = name.unwrap_or(emoji.name);
 
     match guild.create_emoji(ctx, &name, &formatted_bytes).await {
         Ok(new_emoji) => {
-            msg.channel_id
+            source
                 .say(
                     ctx,
                     format!("New emoji {} created! {}", new_emoji.name, new_emoji),
@@ -112,7 +162,7 @@ async fn kang(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
             Ok(())
         }
         Err(e) => {
-            msg.channel_id.say(
+            source.say(
                 ctx,
                 "Something went wrong with emoji creation. Check your emoji limit? The error message is below."
             ).await?;
@@ -123,58 +173,96 @@ async fn kang(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
 }
 
 #[command]
+#[only_in(guilds)]
 #[aliases("einfo")]
 pub async fn emoji_info(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let emoji = match args.single::<EmojiIdentifier>() {
         Ok(id) => id,
         Err(_) => {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
             msg.channel_id
-                .say(ctx, JesterError::MissingError("custom emoji"))
+                .say(ctx, JesterError::MissingError("emoji").localized(&locale))
                 .await?;
 
             return Ok(());
         }
     };
 
+    emoji_info_internal(ctx, &CommandSource::Prefix(msg), emoji).await
+}
+
+// Embed with emoji name, image as thumbnail, and original link to image
+async fn emoji_info_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    emoji: EmojiIdentifier,
+) -> CommandResult {
     let emoji_url = emoji.url();
+    let author = source.author();
 
-    msg.channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("Emoji info for...");
-                e.thumbnail(&emoji_url);
-                e.field("Name", emoji.name, false);
-                e.field("Emoji ID", emoji.id.0, false);
-                e.field("Image URL", format!("[Click here]({})", &emoji_url), false);
-                e.footer(|f| {
-                    f.text(format!(
-                        "Requested by {}#{}",
-                        msg.author.name, msg.author.discriminator
-                    ));
-                    f
-                });
-                e
-            })
-        })
-        .await?;
+    let mut eb = CreateEmbed::default();
+
+    eb.title("Emoji info for...");
+    eb.thumbnail(&emoji_url);
+    eb.field("Name", emoji.name, false);
+    eb.field("Emoji ID", emoji.id.0, false);
+    eb.field("Image URL", format!("[Click here]({})", &emoji_url), false);
+    eb.footer(|f| {
+        f.text(format!(
+            "Requested by {}#{}",
+            author.name, author.discriminator
+        ));
+        f
+    });
+
+    source.send_embed(ctx, eb).await?;
 
     Ok(())
-    // Embed with emoji name, image as thumbnail, and original link to image
 }
 
 #[command]
+#[only_in(guilds)]
 async fn spoiler(ctx: &Context, msg: &Message) -> CommandResult {
     let attachment = match msg.attachments.get(0) {
         Some(attachment) => attachment,
         None => {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
             msg.channel_id
-                .say(ctx, JesterError::MissingError("attachment"))
+                .say(
+                    ctx,
+                    JesterError::MissingError("attachment").localized(&locale),
+                )
                 .await?;
 
             return Ok(());
         }
     };
 
+    if !spoiler_internal(ctx, &CommandSource::Prefix(msg), attachment).await? {
+        return Ok(());
+    }
+
+    if msg.delete(ctx).await.is_err() {
+        msg.channel_id.say(
+            ctx,
+            concat!("The spoiled attachment was posted, but I cannot delete the old message! \n",
+            "Please give me the `MANAGE_MESSAGES` permission if you want the unspoiled image deleted!")
+        ).await?;
+
+        return Ok(());
+    };
+
+    Ok(())
+}
+
+// Returns false if the spoiled file couldn't be sent
+async fn spoiler_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    attachment: &Attachment,
+) -> CommandResult<bool> {
     let new_filename = format!("SPOILER_{}", attachment.filename);
 
     let bytes = attachment.download().await?;
@@ -184,58 +272,74 @@ async fn spoiler(ctx: &Context, msg: &Message) -> CommandResult {
         filename: new_filename.to_owned(),
     };
 
-    let msg_result = msg
-        .channel_id
-        .send_message(ctx, |m| {
-            m.content(format!("Invoked by {}", msg.author.mention()));
-            m.add_file(new_attachment);
-            m
-        })
-        .await;
+    let content = format!("Invoked by {}", source.author().mention());
+
+    let msg_result = match source {
+        CommandSource::Prefix(msg) => msg
+            .channel_id
+            .send_message(ctx, |m| {
+                m.content(content);
+                m.add_file(new_attachment);
+                m
+            })
+            .await
+            .map(|_| ()),
+        CommandSource::Slash(command) => command
+            .create_followup_message(&ctx.http, |m| {
+                m.content(content);
+                m.add_file(new_attachment);
+                m
+            })
+            .await
+            .map(|_| ()),
+    };
 
     if msg_result.is_err() {
-        msg.channel_id
+        source
             .say(
                 ctx,
                 "This file is too big! Please attach a file less than 8 MB...",
             )
             .await?;
 
-        return Ok(());
+        return Ok(false);
     }
 
-    if msg.delete(ctx).await.is_err() {
-        msg.channel_id.say(
-            ctx,
-            concat!("The spoiled attachment was posted, but I cannot delete the old message! \n",
-            "Please give me the `MANAGE_MESSAGES` permission if you want the unspoiled image deleted!")
-        ).await?;
-
-        return Ok(());
-    };
-
-    Ok(())
+    Ok(true)
 }
 
 #[command]
+#[only_in(guilds)]
 async fn banner(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let user_result = if let Ok(user_id) = args.single::<UserId>() {
-        ctx.http.get_user(user_id.0).await
+    let user_id = if let Ok(user_id) = args.single::<UserId>() {
+        user_id
     } else if args.is_empty() {
-        ctx.http.get_user(msg.author.id.0).await
+        msg.author.id
     } else {
+        let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
         msg.channel_id
-            .say(ctx, JesterError::MissingError("User ID/mention"))
+            .say(ctx, JesterError::MissingError("user").localized(&locale))
             .await?;
 
         return Ok(());
     };
 
-    let user = match user_result {
+    banner_internal(ctx, &CommandSource::Prefix(msg), user_id).await
+}
+
+async fn banner_internal(
+    ctx: &Context,
+    source: &CommandSource<'_>,
+    user_id: UserId,
+) -> CommandResult {
+    let user = match ctx.http.get_user(user_id.0).await {
         Ok(user) => user,
         Err(_) => {
-            msg.channel_id
-                .say(ctx, JesterError::MissingError("User ID/mention"))
+            let locale = locale::get_guild_locale(ctx, source.guild_id()).await;
+
+            source
+                .say(ctx, JesterError::MissingError("user").localized(&locale))
                 .await?;
 
             return Ok(());
@@ -243,34 +347,147 @@ async fn banner(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     };
 
     match user.banner_url() {
-        Some(banner_url) => msg.channel_id.say(ctx, banner_url).await?,
-        None => {
-            msg.channel_id
-                .say(ctx, "No banner found for this user!")
-                .await?
-        }
+        Some(banner_url) => source.say(ctx, banner_url).await?,
+        None => source.say(ctx, "No banner found for this user!").await?,
     };
 
     Ok(())
 }
 
-pub async fn utility_help(ctx: &Context, channel_id: ChannelId) {
-    let content = concat!(
-        "avatar (user mention/ID): Gets your own, or the mentioned person's avatar \n\n",
-        "gavatar (user mention/ID): Same as the avatar command, but gets the server avatar if it exists \n\n",
-        "spoiler <attachment>: Creates a spoiler from an attached file \n\n",
-        "kang <emoji> (new name): Steal an emoji from anywhere and load it to your server. Requires the `manage emojis` permission \n\n",
-        "einfo <emoji>: Get the information of an emoji"
-    );
-
-    let _ = channel_id
-        .send_message(ctx, |m| {
-            m.embed(|e| {
-                e.title("Miscellaneous Utility Help");
-                e.description("Description: Various utility commands");
-                e.field("Commands", content, false);
-                e
+pub fn create_utility_commands(commands: &mut CreateApplicationCommands) {
+    for (name, description, guild_only) in [
+        (
+            "avatar",
+            "Gets your own, or the mentioned person's avatar",
+            false,
+        ),
+        (
+            "gavatar",
+            "Gets the server avatar of yourself or the mentioned person",
+            true,
+        ),
+        (
+            "banner",
+            "Gets your own, or the mentioned person's banner",
+            true,
+        ),
+    ] {
+        commands.create_application_command(|c| {
+            c.name(name)
+                .description(description)
+                .dm_permission(!guild_only)
+                .create_option(|o| {
+                    o.name("user")
+                        .description("The user to look up")
+                        .kind(CommandOptionType::User)
+                        .required(false)
+                })
+        });
+    }
+
+    commands.create_application_command(|c| {
+        c.name("kang")
+            .description("Steal an emoji from anywhere and load it to your server")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MANAGE_EMOJIS_AND_STICKERS)
+            .create_option(|o| {
+                o.name("emoji")
+                    .description("The custom emoji to steal")
+                    .kind(CommandOptionType::String)
+                    .required(true)
+            })
+            .create_option(|o| {
+                o.name("name")
+                    .description("A new name for the emoji")
+                    .kind(CommandOptionType::String)
+                    .required(false)
+            })
+    });
+
+    commands.create_application_command(|c| {
+        c.name("einfo")
+            .description("Get the information of an emoji")
+            .dm_permission(false)
+            .create_option(|o| {
+                o.name("emoji")
+                    .description("The custom emoji to look up")
+                    .kind(CommandOptionType::String)
+                    .required(true)
             })
-        })
-        .await;
+    });
+
+    commands.create_application_command(|c| {
+        c.name("spoiler")
+            .description("Creates a spoiler from an attached file")
+            .dm_permission(false)
+            .create_option(|o| {
+                o.name("file")
+                    .description("The file to spoil")
+                    .kind(CommandOptionType::Attachment)
+                    .required(true)
+            })
+    });
+}
+
+pub async fn utility_interaction(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let source = CommandSource::Slash(command);
+    let options = &command.data.options;
+
+    let user_id =
+        interaction_utils::get_user_option(options, "user").map_or(command.user.id, |user| user.id);
+
+    match command.data.name.as_str() {
+        "avatar" => avatar_internal(ctx, &source, user_id).await,
+        "gavatar" => guild_avatar_internal(ctx, &source, user_id).await,
+        "banner" => banner_internal(ctx, &source, user_id).await,
+        "kang" | "einfo" => {
+            let emoji = match interaction_utils::get_string_option(options, "emoji")
+                .and_then(parse_emoji)
+            {
+                Some(emoji) => emoji,
+                None => {
+                    let locale = locale::get_guild_locale(ctx, source.guild_id()).await;
+
+                    source
+                        .say(ctx, JesterError::MissingError("emoji").localized(&locale))
+                        .await?;
+
+                    return Ok(());
+                }
+            };
+
+            if command.data.name == "kang" {
+                let name = interaction_utils::get_string_option(options, "name");
+
+                kang_internal(ctx, &source, emoji, name).await
+            } else {
+                emoji_info_internal(ctx, &source, emoji).await
+            }
+        }
+        "spoiler" => match interaction_utils::get_attachment_option(options, "file") {
+            Some(attachment) => spoiler_internal(ctx, &source, &attachment)
+                .await
+                .map(|_| ()),
+            None => {
+                let locale = locale::get_guild_locale(ctx, source.guild_id()).await;
+
+                source
+                    .say(
+                        ctx,
+                        JesterError::MissingError("attachment").localized(&locale),
+                    )
+                    .await?;
+
+                Ok(())
+            }
+        },
+        _ => Ok(()),
+    }
+}
+
+pub fn utility_help(locale: &str) -> CreateEmbed {
+    locale::get_help_embed(locale, "utilities")
 }
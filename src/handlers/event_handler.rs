@@ -1,15 +1,30 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
 
-use crate::{helpers::start_loops, reactions::reaction_handler, ConnectionPool, PrefixMap};
+use crate::{
+    handlers::interaction_handler,
+    helpers::{
+        automod, guild_settings, mod_log,
+        scheduler::{self, JobKind},
+        start_loops,
+    },
+    reactions::reaction_handler,
+    ConnectionPool, RepositoryContainer, UnavailableCommands,
+};
 // use lavalink_rs::gateway::LavalinkEventHandler;
 use serenity::{
     async_trait,
     client::{Context, EventHandler},
     model::{
-        channel::{GuildChannel, Reaction},
-        guild::{Guild, UnavailableGuild},
-        id::GuildId,
+        application::{command::Command, interaction::Interaction},
+        channel::{GuildChannel, Message, Reaction},
+        event::MessageUpdateEvent,
+        guild::{Guild, Member, UnavailableGuild},
+        id::{ChannelId, GuildId, MessageId},
         prelude::Ready,
+        user::User,
     },
 };
 
@@ -32,6 +47,32 @@ impl EventHandler for SerenityHandler {
                 panic!("Error when pruning guilds! {}", e);
             }
 
+            let unavailable_commands = ctx
+                .data
+                .read()
+                .await
+                .get::<UnavailableCommands>()
+                .cloned()
+                .unwrap();
+
+            println!("Registering slash commands!");
+            if let Err(e) = Command::set_global_application_commands(&ctx.http, |commands| {
+                interaction_handler::create_commands(commands);
+
+                // Commands without their API keys aren't registered at all
+                commands.0.retain(|command| {
+                    !command["name"]
+                        .as_str()
+                        .is_some_and(|name| unavailable_commands.contains(name))
+                });
+
+                commands
+            })
+            .await
+            {
+                eprintln!("Error when registering slash commands! {}", e);
+            }
+
             let pool = ctx
                 .data
                 .read()
@@ -40,17 +81,26 @@ impl EventHandler for SerenityHandler {
                 .cloned()
                 .unwrap();
 
-            println!("Starting starboard deletion loop!");
-            tokio::spawn(async move {
-                if let Err(e) = start_loops::starboard_removal_loop(&pool).await {
-                    panic!("Delete buffer failed to start!: {}", e);
-                };
-            });
-
+            /*
+             * Presence is set per shard, so every process rotates the activity of its own shards
+             * The job scheduler is only for work that one process does for the whole bot
+             */
             println!("Starting activity loop!");
-            tokio::spawn(async move {
-                start_loops::activity_loop(&ctx.shard).await;
-            });
+            tokio::spawn(start_loops::activity_loop(ctx.clone()));
+
+            let recurring_jobs = [
+                (JobKind::StarboardCleanup, Duration::from_secs(345600)),
+                (JobKind::RunLogCleanup, Duration::from_secs(86400)),
+            ];
+
+            for (kind, interval) in recurring_jobs {
+                if let Err(e) = scheduler::ensure_recurring_job(&pool, kind, interval).await {
+                    eprintln!("Error when adding the {} job! {}", kind, e);
+                }
+            }
+
+            println!("Starting the job scheduler!");
+            tokio::spawn(scheduler::run_scheduler(ctx));
         }
     }
 
@@ -61,47 +111,38 @@ impl EventHandler for SerenityHandler {
     }
 
     async fn guild_create(&self, ctx: Context, guild: Guild, is_new: bool) {
-        let pool = ctx
+        let repository = ctx
             .data
             .read()
             .await
-            .get::<ConnectionPool>()
+            .get::<RepositoryContainer>()
             .cloned()
             .unwrap();
 
         if is_new {
-            sqlx::query!(
-                "INSERT INTO guild_info VALUES($1, null) ON CONFLICT DO NOTHING",
-                guild.id.0 as i64
-            )
-            .execute(&pool)
-            .await
-            .unwrap();
+            repository.add_guild(guild.id).await.unwrap();
         }
     }
 
     async fn guild_delete(&self, ctx: Context, incomplete: UnavailableGuild, _full: Option<Guild>) {
-        let (pool, prefixes) = {
-            let data = ctx.data.read().await;
-            let pool = data.get::<ConnectionPool>().cloned().unwrap();
-            let prefixes = data.get::<PrefixMap>().cloned().unwrap();
-
-            (pool, prefixes)
-        };
-
-        if let Err(e) = sqlx::query!(
-            "DELETE FROM guild_info WHERE guild_id = $1",
-            incomplete.id.0 as i64
-        )
-        .execute(&pool)
-        .await
-        {
-            eprintln!("Error in guild removal! (ID {}): {}", incomplete.id.0, e)
+        // An outage also fires this event, but the bot is still in the guild
+        if incomplete.unavailable {
+            return;
         }
 
-        if prefixes.contains_key(&incomplete.id) {
-            prefixes.remove(&incomplete.id);
+        let repository = ctx
+            .data
+            .read()
+            .await
+            .get::<RepositoryContainer>()
+            .cloned()
+            .unwrap();
+
+        if let Err(e) = repository.remove_guild(incomplete.id).await {
+            eprintln!("Error in guild removal! (ID {}): {}", incomplete.id.0, e)
         }
+
+        guild_settings::invalidate_guild_settings(&ctx, incomplete.id).await;
     }
 
     async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
@@ -111,6 +152,97 @@ impl EventHandler for SerenityHandler {
     async fn reaction_remove(&self, ctx: Context, reaction: Reaction) {
         let _ = reaction_handler::dispatch_reaction(&ctx, &reaction, true).await;
     }
+
+    async fn message(&self, ctx: Context, msg: Message) {
+        mod_log::cache_message(&ctx, &msg).await;
+
+        if let Err(e) = automod::check_message(&ctx, &msg).await {
+            eprintln!("Error in automod! {}", e);
+        }
+    }
+
+    async fn message_update(
+        &self,
+        ctx: Context,
+        _old_if_available: Option<Message>,
+        _new: Option<Message>,
+        event: MessageUpdateEvent,
+    ) {
+        if let Err(e) = mod_log::log_message_edit(&ctx, &event).await {
+            eprintln!("Error when logging a message edit! {}", e);
+        }
+    }
+
+    async fn message_delete(
+        &self,
+        ctx: Context,
+        channel_id: ChannelId,
+        deleted_message_id: MessageId,
+        guild_id: Option<GuildId>,
+    ) {
+        if let Some(guild_id) = guild_id {
+            if let Err(e) =
+                mod_log::log_message_delete(&ctx, guild_id, channel_id, deleted_message_id).await
+            {
+                eprintln!("Error when logging a message delete! {}", e);
+            }
+        }
+    }
+
+    async fn message_delete_bulk(
+        &self,
+        ctx: Context,
+        channel_id: ChannelId,
+        multiple_deleted_messages_ids: Vec<MessageId>,
+        guild_id: Option<GuildId>,
+    ) {
+        if let Some(guild_id) = guild_id {
+            if let Err(e) =
+                mod_log::log_bulk_delete(&ctx, guild_id, channel_id, &multiple_deleted_messages_ids)
+                    .await
+            {
+                eprintln!("Error when logging a bulk delete! {}", e);
+            }
+        }
+    }
+
+    async fn guild_member_addition(&self, ctx: Context, new_member: Member) {
+        if let Err(e) = mod_log::log_member_join(&ctx, &new_member).await {
+            eprintln!("Error when logging a member join! {}", e);
+        }
+    }
+
+    async fn guild_member_removal(
+        &self,
+        ctx: Context,
+        guild_id: GuildId,
+        user: User,
+        member_data_if_available: Option<Member>,
+    ) {
+        if let Err(e) =
+            mod_log::log_member_leave(&ctx, guild_id, &user, member_data_if_available.as_ref())
+                .await
+        {
+            eprintln!("Error when logging a member leave! {}", e);
+        }
+    }
+
+    async fn guild_member_update(
+        &self,
+        ctx: Context,
+        old_if_available: Option<Member>,
+        new: Member,
+    ) {
+        if let Err(e) = mod_log::log_member_update(&ctx, old_if_available.as_ref(), &new).await {
+            eprintln!("Error when logging a member update! {}", e);
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        if let Err(e) = interaction_handler::dispatch_interaction(&ctx, interaction).await {
+            eprintln!("Error in interaction dispatch! {}", e);
+        }
+    }
 }
 
 /*
@@ -1,24 +1,40 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Instant};
 
 use crate::{
-    helpers::command_utils,
+    helpers::{
+        analytics::{self, CommandUsage},
+        command_rules::{self, CommandAccess},
+        command_utils, cooldowns, custom_commands, embed_store,
+        incidents::{self, NewIncident},
+        locale,
+        templates::TemplateData,
+    },
     structures::{commands::*, errors::*},
-    ConnectionPool, EmergencyCommands, PrefixMap, PubCreds,
+    CommandScopeMap, CommandTimers, EmergencyCommands, RepositoryContainer,
 };
 use serenity::{
     client::Context,
     framework::standard::{macros::hook, CommandError, DispatchError, StandardFramework},
-    model::{channel::Message, id::UserId, Permissions},
+    model::{
+        channel::Message,
+        id::{GuildId, UserId},
+        Permissions,
+    },
     prelude::Mentionable,
 };
 
-pub fn get_framework(bot_id: UserId, owners: HashSet<UserId>) -> StandardFramework {
+pub fn get_framework(
+    bot_id: UserId,
+    owners: HashSet<UserId>,
+    unavailable_commands: HashSet<String>,
+) -> StandardFramework {
     StandardFramework::new()
         .configure(|c| {
             c.dynamic_prefix(dynamic_prefix)
                 .prefix("")
                 .on_mention(Some(bot_id))
                 .owners(owners)
+                .disabled_commands(unavailable_commands)
         })
         .on_dispatch_error(dispatch_error)
         .unrecognised_command(unrecognized_command_hook)
@@ -37,40 +53,127 @@ pub fn get_framework(bot_id: UserId, owners: HashSet<UserId>) -> StandardFramewo
         .group(&IMAGES_GROUP)
         .group(&JAPAN_GROUP)
         .group(&UTILITY_GROUP)
+        .group(&MODERATION_GROUP)
+        .group(&OWNER_GROUP)
 }
 
 #[hook]
 async fn unrecognized_command_hook(ctx: &Context, msg: &Message, command_name: &str) {
-    let pool = ctx
+    let repository = ctx
         .data
         .read()
         .await
-        .get::<ConnectionPool>()
+        .get::<RepositoryContainer>()
         .cloned()
         .unwrap();
 
-    let guild_id = msg.guild_id.unwrap().0 as i64;
+    // Custom commands only exist in guilds
+    let guild_id = match msg.guild_id {
+        Some(guild_id) => guild_id,
+        None => return,
+    };
+
+    let custom_command =
+        match custom_commands::fetch_custom_command(&*repository, guild_id, command_name).await {
+            Ok(Some(custom_command)) => custom_command,
+            Ok(None) => return,
+            Err(e) => {
+                eprintln!("Error when fetching a custom command! {}", e);
+                return;
+            }
+        };
 
-    let cmd_data = sqlx::query!(
-        "SELECT content FROM commands WHERE guild_id = $1 AND name = $2",
+    match custom_commands::check_requirements(
+        ctx,
+        msg,
+        &*repository,
         guild_id,
-        command_name
+        &custom_command.name,
     )
-    .fetch_optional(&pool)
     .await
-    .unwrap();
-
-    if let Some(cmd_data) = cmd_data {
-        let content = cmd_data
-            .content
-            .unwrap()
-            .replace("{user}", &msg.author.mention().to_string());
-        let _ = msg.channel_id.say(ctx, content).await;
+    {
+        Ok(None) => {}
+        Ok(Some(reason)) => {
+            let _ = msg.channel_id.say(ctx, reason).await;
+            return;
+        }
+        Err(e) => {
+            eprintln!("Error when checking custom command requirements! {}", e);
+            return;
+        }
     }
+
+    // The use counter goes up before rendering so {uses} includes this use
+    let uses = match repository
+        .increment_uses(guild_id, &custom_command.name)
+        .await
+    {
+        Ok(uses) => uses,
+        Err(e) => {
+            eprintln!("Error when counting custom command uses! {}", e);
+            return;
+        }
+    };
+
+    let (server_name, member_count) = match msg.guild(ctx) {
+        Some(guild) => (guild.name, guild.member_count),
+        None => (String::new(), 0),
+    };
+
+    let (content, embed) = custom_commands::build_response(
+        &custom_command,
+        &TemplateData {
+            user_id: msg.author.id,
+            username: &msg.author.name,
+            args: command_utils::get_command_args(&msg.content, command_name),
+            channel_id: msg.channel_id,
+            server_name: &server_name,
+            member_count,
+            uses,
+        },
+    );
+
+    // Only the user running the command can be pinged
+    let _ = msg
+        .channel_id
+        .send_message(ctx, |m| {
+            if !content.is_empty() {
+                m.content(content);
+            }
+
+            if let Some(embed) = embed {
+                m.set_embed(embed);
+            }
+
+            if custom_command.reply {
+                m.reference_message(msg);
+            }
+
+            m.allowed_mentions(|am| am.empty_parse().users(vec![msg.author.id]))
+        })
+        .await;
 }
 
 #[hook]
 async fn before(ctx: &Context, msg: &Message, cmd_name: &str) -> bool {
+    let should_run = check_before(ctx, msg, cmd_name).await;
+
+    if should_run {
+        let command_timers = ctx
+            .data
+            .read()
+            .await
+            .get::<CommandTimers>()
+            .cloned()
+            .unwrap();
+
+        command_timers.insert(msg.id, Instant::now());
+    }
+
+    should_run
+}
+
+async fn check_before(ctx: &Context, msg: &Message, cmd_name: &str) -> bool {
     if command_utils::check_mention_prefix(msg) {
         let emergency_commands = ctx
             .data
@@ -81,13 +184,16 @@ async fn before(ctx: &Context, msg: &Message, cmd_name: &str) -> bool {
             .unwrap();
 
         if emergency_commands.contains(&cmd_name.to_owned()) {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
             let _ = msg
                 .channel_id
                 .say(
                     ctx,
-                    format!(
-                        "{}, you are running an emergency command!",
-                        msg.author.mention()
+                    locale::get_formatted(
+                        &locale,
+                        "error.emergency",
+                        &[("user", &msg.author.mention().to_string())],
                     ),
                 )
                 .await;
@@ -97,26 +203,126 @@ async fn before(ctx: &Context, msg: &Message, cmd_name: &str) -> bool {
         }
     }
 
-    true
+    if let Some(guild_id) = msg.guild_id {
+        if !check_access(ctx, msg, guild_id, cmd_name).await {
+            return false;
+        }
+    }
+
+    /*
+     * Cooldowns are only started once the command is allowed here,
+     * otherwise a disabled command would use up the next allowed run
+     */
+    match cooldowns::check_cooldown(ctx, msg.guild_id, msg.channel_id, msg.author.id, cmd_name)
+        .await
+    {
+        Ok(Some(remaining)) => {
+            let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
+            let _ = msg
+                .channel_id
+                .say(ctx, cooldowns::get_cooldown_message(&locale, remaining))
+                .await;
+
+            false
+        }
+        Ok(None) => true,
+        Err(e) => {
+            eprintln!("Error when checking cooldowns! {}", e);
+
+            true
+        }
+    }
+}
+
+async fn check_access(ctx: &Context, msg: &Message, guild_id: GuildId, cmd_name: &str) -> bool {
+    match command_rules::check_command_access(
+        ctx,
+        guild_id,
+        msg.channel_id,
+        cmd_name,
+        Some(&msg.content),
+    )
+    .await
+    {
+        Ok(CommandAccess::Allowed) => true,
+        Ok(CommandAccess::Disabled) => false,
+        Ok(CommandAccess::WrongChannel(channels)) => {
+            let locale = locale::get_guild_locale(ctx, Some(guild_id)).await;
+
+            let _ = msg
+                .channel_id
+                .say(ctx, command_rules::get_channel_message(&locale, &channels))
+                .await;
+
+            false
+        }
+        Err(e) => {
+            eprintln!("Error when checking command rules! {}", e);
+
+            true
+        }
+    }
 }
 
 // After a command is executed, goto here
 #[hook]
 async fn after(ctx: &Context, msg: &Message, cmd_name: &str, error: Result<(), CommandError>) {
+    let (command_timers, scope_map) = {
+        let data = ctx.data.read().await;
+        let command_timers = data.get::<CommandTimers>().cloned().unwrap();
+        let scope_map = data.get::<CommandScopeMap>().cloned().unwrap();
+
+        (command_timers, scope_map)
+    };
+
+    let duration = command_timers
+        .remove(&msg.id)
+        .map(|(_, start)| start.elapsed())
+        .unwrap_or_default();
+
+    let usage = CommandUsage {
+        guild_id: msg.guild_id,
+        channel_id: msg.channel_id,
+        user_id: msg.author.id,
+        command: command_rules::get_full_command_name(&scope_map, cmd_name, Some(&msg.content)),
+        duration,
+        error: error.as_ref().err().map(|why| why.to_string()),
+    };
+
+    if let Err(e) = analytics::record_usage(ctx, usage).await {
+        eprintln!("Error when recording command usage! {}", e);
+    }
+
     if let Err(why) = error {
-        let part_1 = "Looks like the bot encountered an error! \n";
-        let part_2 = "Please use the `support` command and send the output to the support server!";
-        let error_string = format!("{}{}", part_1, part_2);
+        let incident = NewIncident {
+            command: cmd_name,
+            args: &msg.content,
+            guild_id: msg.guild_id,
+            channel_id: msg.channel_id,
+            user_id: msg.author.id,
+            error: &why,
+        };
+
+        let incident_id = match incidents::record_incident(ctx, incident).await {
+            Ok(incident_id) => Some(incident_id),
+            Err(e) => {
+                eprintln!(
+                    "Error when recording incident for {}! {} ({})",
+                    cmd_name, e, why
+                );
+
+                None
+            }
+        };
+
+        let error_embed = embed_store::get_error_embed(cmd_name, incident_id.as_deref());
 
         let _ = msg
             .channel_id
             .send_message(ctx, |m| {
                 m.embed(|e| {
-                    e.color(0xff69b4);
-                    e.title("Aw Snap!");
-                    e.description(error_string);
-                    e.field("Command Name", cmd_name, false);
-                    e.field("Error", format!("```{} \n```", why), false);
+                    e.0 = error_embed.0;
                     e
                 })
             })
@@ -127,13 +333,16 @@ async fn after(ctx: &Context, msg: &Message, cmd_name: &str, error: Result<(), C
 // On a dispatch error, go to this function
 #[hook]
 async fn dispatch_error(ctx: &Context, msg: &Message, error: DispatchError, _command_name: &str) {
+    let locale = locale::get_guild_locale(ctx, msg.guild_id).await;
+
     match error {
         DispatchError::LackingPermissions(Permissions::ADMINISTRATOR) => {
             let _ = msg
                 .channel_id
                 .say(
                     ctx,
-                    JesterError::PermissionError(PermissionType::UserPerm("administrator")),
+                    JesterError::PermissionError(PermissionType::UserPerm("administrator"))
+                        .localized(&locale),
                 )
                 .await;
         }
@@ -142,7 +351,8 @@ async fn dispatch_error(ctx: &Context, msg: &Message, error: DispatchError, _com
                 .channel_id
                 .say(
                     ctx,
-                    JesterError::PermissionError(PermissionType::UserPerm("manage messages")),
+                    JesterError::PermissionError(PermissionType::UserPerm("manage messages"))
+                        .localized(&locale),
                 )
                 .await;
         }
@@ -151,7 +361,38 @@ async fn dispatch_error(ctx: &Context, msg: &Message, error: DispatchError, _com
                 .channel_id
                 .say(
                     ctx,
-                    JesterError::PermissionError(PermissionType::UserPerm("manage emojis")),
+                    JesterError::PermissionError(PermissionType::UserPerm("manage emojis"))
+                        .localized(&locale),
+                )
+                .await;
+        }
+        DispatchError::LackingPermissions(Permissions::MODERATE_MEMBERS) => {
+            let _ = msg
+                .channel_id
+                .say(
+                    ctx,
+                    JesterError::PermissionError(PermissionType::UserPerm("moderate members"))
+                        .localized(&locale),
+                )
+                .await;
+        }
+        DispatchError::LackingPermissions(Permissions::KICK_MEMBERS) => {
+            let _ = msg
+                .channel_id
+                .say(
+                    ctx,
+                    JesterError::PermissionError(PermissionType::UserPerm("kick members"))
+                        .localized(&locale),
+                )
+                .await;
+        }
+        DispatchError::LackingPermissions(Permissions::BAN_MEMBERS) => {
+            let _ = msg
+                .channel_id
+                .say(
+                    ctx,
+                    JesterError::PermissionError(PermissionType::UserPerm("ban members"))
+                        .localized(&locale),
                 )
                 .await;
         }
@@ -160,14 +401,30 @@ async fn dispatch_error(ctx: &Context, msg: &Message, error: DispatchError, _com
                 .channel_id
                 .say(
                     ctx,
-                    format!("Args required: {}. Args given: {}", min, given),
+                    locale::get_formatted(
+                        &locale,
+                        "error.arguments",
+                        &[("min", &min.to_string()), ("given", &given.to_string())],
+                    ),
                 )
                 .await;
         }
+        DispatchError::OnlyForGuilds => {
+            let _ = msg
+                .channel_id
+                .say(ctx, locale::get_text(&locale, "error.guild_only"))
+                .await;
+        }
+        DispatchError::CommandDisabled => {
+            let _ = msg
+                .channel_id
+                .say(ctx, locale::get_text(&locale, "error.unavailable"))
+                .await;
+        }
         DispatchError::OnlyForOwners => {
             let _ = msg
                 .channel_id
-                .say(ctx, "This is a bot dev only command!")
+                .say(ctx, locale::get_text(&locale, "error.owner_only"))
                 .await;
         }
         _ => println!("Unhandled dispatch error: {:?}", error),
@@ -176,31 +433,15 @@ async fn dispatch_error(ctx: &Context, msg: &Message, error: DispatchError, _com
 
 /*
  * The heart of custom prefixes
- * If the guild has a prefix in the Dashmap, use that prefix
+ * If the guild has prefixes in the Dashmap, use whichever one starts the message
  * Otherwise, use the default prefix from credentials_helper
+ *
+ * Prefixes are case insensitive, so the prefix is returned as it was typed
  */
 
 #[hook]
 async fn dynamic_prefix(ctx: &Context, msg: &Message) -> Option<String> {
-    let (prefixes, default_prefix) = {
-        let data = ctx.data.read().await;
-        let prefixes = data.get::<PrefixMap>().cloned().unwrap();
-        let default_prefix = data
-            .get::<PubCreds>()
-            .unwrap()
-            .get("default prefix")
-            .cloned()
-            .unwrap();
-
-        (prefixes, default_prefix)
-    };
+    let prefixes = command_utils::get_guild_prefixes(ctx, msg.guild_id).await;
 
-    let guild_id = msg.guild_id.unwrap();
-
-    let wrapped_prefix = prefixes.get(&guild_id);
-
-    match wrapped_prefix {
-        Some(prefix_guard) => Some(prefix_guard.value().to_owned()),
-        None => Some(default_prefix),
-    }
+    command_utils::find_prefix(&msg.content, &prefixes).map(|prefix| prefix.to_owned())
 }
@@ -0,0 +1,233 @@
+use std::time::Instant;
+
+use serenity::{
+    builder::CreateApplicationCommands,
+    client::Context,
+    framework::standard::CommandResult,
+    model::application::interaction::{
+        application_command::ApplicationCommandInteraction, Interaction,
+    },
+};
+
+use crate::{
+    commands::{
+        automod::*, backup::*, ciphers::*, config::*, images::*, japan::*, moderation::*, other::*,
+        polls::*, reminders::*, rules::*, starboard::*, stats::*, support::*, textchannel_send::*,
+        textmod::*, utility::*,
+    },
+    helpers::{
+        analytics::{self, CommandUsage},
+        command_rules::{self, CommandAccess},
+        cooldowns, embed_store,
+        incidents::{self, NewIncident},
+        interaction_utils, locale,
+    },
+    CommandScopeMap,
+};
+
+// Every slash command is registered globally using the bot's application ID
+pub fn create_commands(commands: &mut CreateApplicationCommands) -> &mut CreateApplicationCommands {
+    create_general_commands(commands);
+    create_textmod_commands(commands);
+    create_cipher_commands(commands);
+    create_sender_commands(commands);
+    create_config_commands(commands);
+    create_rules_commands(commands);
+    create_backup_commands(commands);
+    create_support_commands(commands);
+    create_stats_commands(commands);
+    create_starboard_commands(commands);
+    create_image_commands(commands);
+    create_japan_commands(commands);
+    create_utility_commands(commands);
+    create_reminder_commands(commands);
+    create_poll_commands(commands);
+    create_moderation_commands(commands);
+    create_automod_commands(commands);
+
+    commands
+}
+
+pub async fn dispatch_interaction(ctx: &Context, interaction: Interaction) -> CommandResult {
+    if let Interaction::ApplicationCommand(command) = interaction {
+        // Some commands take longer than the 3 second response window, so always defer
+        command.defer(&ctx.http).await?;
+
+        let locale = locale::get_guild_locale(ctx, command.guild_id).await;
+
+        if let Some(guild_id) = command.guild_id {
+            let access = command_rules::check_command_access(
+                ctx,
+                guild_id,
+                command.channel_id,
+                &command.data.name,
+                None,
+            )
+            .await?;
+
+            let denied_message = match access {
+                CommandAccess::Allowed => None,
+                CommandAccess::Disabled => Some(locale::get_text(&locale, "error.disabled")),
+                CommandAccess::WrongChannel(channels) => {
+                    Some(command_rules::get_channel_message(&locale, &channels))
+                }
+            };
+
+            if let Some(denied_message) = denied_message {
+                command
+                    .create_followup_message(&ctx.http, |m| m.content(denied_message))
+                    .await?;
+
+                return Ok(());
+            }
+        }
+
+        if let Some(remaining) = check_slash_cooldown(ctx, &command).await? {
+            command
+                .create_followup_message(&ctx.http, |m| {
+                    m.content(cooldowns::get_cooldown_message(&locale, remaining))
+                })
+                .await?;
+
+            return Ok(());
+        }
+
+        let start = Instant::now();
+        let result = execute_command(ctx, &command).await;
+
+        let usage = CommandUsage {
+            guild_id: command.guild_id,
+            channel_id: command.channel_id,
+            user_id: command.user.id,
+            command: get_usage_name(ctx, &command).await,
+            duration: start.elapsed(),
+            error: result.as_ref().err().map(|why| why.to_string()),
+        };
+
+        if let Err(e) = analytics::record_usage(ctx, usage).await {
+            eprintln!("Error when recording command usage! {}", e);
+        }
+
+        if let Err(why) = result {
+            let args = interaction_utils::format_options(&command.data.options);
+            let incident = NewIncident {
+                command: &command.data.name,
+                args: &args,
+                guild_id: command.guild_id,
+                channel_id: command.channel_id,
+                user_id: command.user.id,
+                error: &why,
+            };
+
+            let incident_id = match incidents::record_incident(ctx, incident).await {
+                Ok(incident_id) => Some(incident_id),
+                Err(e) => {
+                    eprintln!(
+                        "Error when recording incident for {}! {} ({})",
+                        command.data.name, e, why
+                    );
+
+                    None
+                }
+            };
+
+            let error_embed =
+                embed_store::get_error_embed(&command.data.name, incident_id.as_deref());
+
+            command
+                .create_followup_message(&ctx.http, |m| m.add_embed(error_embed))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Slash command names are resolved to the prefix command so both share a cooldown
+async fn check_slash_cooldown(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult<Option<u64>> {
+    let scope_map = ctx
+        .data
+        .read()
+        .await
+        .get::<CommandScopeMap>()
+        .cloned()
+        .unwrap();
+
+    let command_name = match scope_map
+        .get(&command.data.name)
+        .and_then(|scopes| command_rules::resolve_scope(scopes, None))
+    {
+        Some(scope) if scope.group != command_rules::PROTECTED_GROUP => &scope.command,
+        _ => return Ok(None),
+    };
+
+    cooldowns::check_cooldown(
+        ctx,
+        command.guild_id,
+        command.channel_id,
+        command.user.id,
+        command_name,
+    )
+    .await
+}
+
+// Slash commands are recorded under the same names as their prefix versions
+async fn get_usage_name(ctx: &Context, command: &ApplicationCommandInteraction) -> String {
+    let scope_map = ctx
+        .data
+        .read()
+        .await
+        .get::<CommandScopeMap>()
+        .cloned()
+        .unwrap();
+
+    let command_name = command_rules::get_full_command_name(&scope_map, &command.data.name, None);
+
+    match interaction_utils::get_subcommand(&command.data.options) {
+        Some(subcommand) => format!("{} {}", command_name, subcommand.name),
+        None => command_name,
+    }
+}
+
+async fn execute_command(ctx: &Context, command: &ApplicationCommandInteraction) -> CommandResult {
+    match command.data.name.as_str() {
+        "ping" => general_interaction(ctx, command).await,
+        "mock" | "inv" | "upp" | "low" | "space" | "biggspace" | "h4ck" | "uwu" => {
+            textmod_interaction(ctx, command).await
+        }
+        "b64encode" | "b64decode" => cipher_interaction(ctx, command).await,
+        "nice" | "bruh" | "quote" | "vibecheck" => sender_interaction(ctx, command).await,
+        "prefix" | "resetprefix" | "language" | "command" => config_interaction(ctx, command).await,
+        "disable" | "enable" | "restrict" | "unrestrict" | "cooldown" | "rules" => {
+            rules_interaction(ctx, command).await
+        }
+        "export" | "import" => backup_interaction(ctx, command).await,
+        "help" | "support" | "info" => support_interaction(ctx, command).await,
+        "stats" => stats_interaction(ctx, command).await,
+        "starboard" => starboard_interaction(ctx, command).await,
+        "hug" | "pat" | "slap" | "kiss" | "disgust" | "cry" | "cringe" | "gifsearch" => {
+            image_interaction(ctx, command).await
+        }
+        "anime" | "manga" => japan_interaction(ctx, command).await,
+        "avatar" | "gavatar" | "banner" | "kang" | "einfo" | "spoiler" => {
+            utility_interaction(ctx, command).await
+        }
+        "remind" => reminder_interaction(ctx, command).await,
+        "poll" => poll_interaction(ctx, command).await,
+        "warn" | "kick" | "ban" | "unban" | "timeout" | "history" | "case" | "modlog" => {
+            moderation_interaction(ctx, command).await
+        }
+        "automod" => automod_interaction(ctx, command).await,
+        // Stale global commands can still be used, and the deferred response needs an answer
+        _ => {
+            command
+                .create_followup_message(&ctx.http, |m| m.content("This command is unavailable!"))
+                .await?;
+
+            Ok(())
+        }
+    }
+}
@@ -1,2 +1,3 @@
 pub mod event_handler;
 pub mod framework;
+pub mod interaction_handler;
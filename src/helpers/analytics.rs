@@ -0,0 +1,137 @@
+use serenity::{
+    client::Context,
+    framework::standard::CommandResult,
+    model::id::{ChannelId, GuildId, UserId},
+};
+use sqlx::{AnyPool, FromRow};
+use std::time::Duration;
+
+use crate::{helpers::command_utils, ConnectionPool};
+
+pub struct CommandUsage {
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    pub user_id: UserId,
+    pub command: String,
+    pub duration: Duration,
+    pub error: Option<String>,
+}
+
+#[derive(FromRow)]
+pub struct CommandStat {
+    pub command: String,
+    pub uses: i64,
+    pub errors: i64,
+    pub average_ms: i64,
+}
+
+pub struct UserStat {
+    pub user_id: UserId,
+    pub uses: i64,
+}
+
+pub async fn record_usage(ctx: &Context, usage: CommandUsage) -> CommandResult {
+    let pool = ctx
+        .data
+        .read()
+        .await
+        .get::<ConnectionPool>()
+        .cloned()
+        .unwrap();
+
+    sqlx::query(
+        "INSERT INTO command_usage(guild_id, channel_id, user_id, command, duration_ms, success, error, used_at)
+            VALUES($1, $2, $3, $4, $5, $6, $7, $8)",
+    )
+    .bind(usage.guild_id.map(|x| x.0 as i64))
+    .bind(usage.channel_id.0 as i64)
+    .bind(usage.user_id.0 as i64)
+    .bind(usage.command)
+    .bind(usage.duration.as_millis() as i64)
+    .bind(usage.error.is_none())
+    .bind(usage.error)
+    .bind(command_utils::get_unix_time())
+    .execute(&pool)
+    .await?;
+
+    Ok(())
+}
+
+/*
+ * Stats are only fetched from after the given time
+ * If there's no guild ID, stats come from every guild and DM
+ */
+pub async fn fetch_command_stats(
+    pool: &AnyPool,
+    guild_id: Option<GuildId>,
+    since: i64,
+    limit: i64,
+) -> CommandResult<Vec<CommandStat>> {
+    let stat_data = sqlx::query_as::<_, CommandStat>(
+        "SELECT command,
+            COUNT(*) AS uses,
+            SUM(CASE WHEN success THEN 0 ELSE 1 END) AS errors,
+            CAST(AVG(duration_ms) AS bigint) AS average_ms
+            FROM command_usage
+            WHERE (CAST($1 AS bigint) IS NULL OR guild_id = $1) AND used_at >= $2
+            GROUP BY command
+            ORDER BY 2 DESC
+            LIMIT $3",
+    )
+    .bind(guild_id.map(|x| x.0 as i64))
+    .bind(since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(stat_data)
+}
+
+pub async fn fetch_user_stats(
+    pool: &AnyPool,
+    guild_id: Option<GuildId>,
+    since: i64,
+    limit: i64,
+) -> CommandResult<Vec<UserStat>> {
+    let stat_data = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT user_id, COUNT(*) AS uses
+            FROM command_usage
+            WHERE (CAST($1 AS bigint) IS NULL OR guild_id = $1) AND used_at >= $2
+            GROUP BY user_id
+            ORDER BY 2 DESC
+            LIMIT $3",
+    )
+    .bind(guild_id.map(|x| x.0 as i64))
+    .bind(since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(stat_data
+        .into_iter()
+        .map(|(user_id, uses)| UserStat {
+            user_id: UserId(user_id as u64),
+            uses,
+        })
+        .collect())
+}
+
+// Returns the total uses and total errors
+pub async fn fetch_totals(
+    pool: &AnyPool,
+    guild_id: Option<GuildId>,
+    since: i64,
+) -> CommandResult<(i64, i64)> {
+    let total_data = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT COUNT(*) AS uses,
+            COALESCE(SUM(CASE WHEN success THEN 0 ELSE 1 END), 0) AS errors
+            FROM command_usage
+            WHERE (CAST($1 AS bigint) IS NULL OR guild_id = $1) AND used_at >= $2",
+    )
+    .bind(guild_id.map(|x| x.0 as i64))
+    .bind(since)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(total_data)
+}
@@ -0,0 +1,816 @@
+use dashmap::DashMap;
+use regex::{Regex, RegexBuilder};
+use serenity::{
+    client::Context,
+    framework::standard::CommandResult,
+    model::{
+        channel::Message,
+        id::{ChannelId, GuildId, RoleId, UserId},
+        prelude::Mentionable,
+        Timestamp,
+    },
+};
+use std::{
+    collections::{hash_map::DefaultHasher, VecDeque},
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+    sync::{Arc, LazyLock},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    helpers::{
+        command_utils, guild_settings, mod_log,
+        moderation::{self, ModAction},
+        permissions_helper,
+    },
+    repositories::{AutomodRule, AutomodSettings, ModCase},
+    AutomodTracker, RepositoryContainer,
+};
+
+// Used when a rule is set without its own timeout length
+pub const DEFAULT_TIMEOUT_SECS: i64 = 600;
+
+// Rate limit and duplicate windows can't be longer than this, so the tracker stays small
+pub const MAX_INTERVAL_SECS: i64 = 300;
+
+// Messages kept per user, which also caps rate limit and duplicate thresholds
+pub const MAX_TRACKED_MESSAGES: usize = 50;
+
+// Users who stopped talking are only cleared out once the tracker grows past this
+const TRACKER_CLEANUP_SIZE: usize = 10000;
+
+// Keeps regexes from the word filter list from taking too much memory
+const REGEX_SIZE_LIMIT: usize = 100000;
+
+// Every message in a guild with automod is checked, so these are only compiled once
+static INVITE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:discord(?:app)?\.com/invite|discord\.gg)/([a-z0-9-]+)").unwrap()
+});
+static LINK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)https?://([^\s/?#<>]+)").unwrap());
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleKind {
+    RateLimit,
+    Duplicates,
+    Invites,
+    Links,
+    Mentions,
+    Words,
+}
+
+impl RuleKind {
+    pub const ALL: [RuleKind; 6] = [
+        RuleKind::RateLimit,
+        RuleKind::Duplicates,
+        RuleKind::Invites,
+        RuleKind::Links,
+        RuleKind::Mentions,
+        RuleKind::Words,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match *self {
+            RuleKind::RateLimit => "Sending too many messages at once",
+            RuleKind::Duplicates => "Repeating the same message",
+            RuleKind::Invites => "Posting server invites that aren't allowed",
+            RuleKind::Links => "Posting links that aren't allowed",
+            RuleKind::Mentions => "Mentioning too many users or roles",
+            RuleKind::Words => "Using a filtered word",
+        }
+    }
+
+    // Messages for rate limits and duplicates, mentions for the mention cap
+    pub fn default_threshold(&self) -> Option<i64> {
+        match *self {
+            RuleKind::RateLimit => Some(5),
+            RuleKind::Duplicates => Some(3),
+            RuleKind::Mentions => Some(5),
+            _ => None,
+        }
+    }
+
+    pub fn default_interval(&self) -> Option<i64> {
+        match *self {
+            RuleKind::RateLimit => Some(5),
+            RuleKind::Duplicates => Some(30),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RuleKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RuleKind::RateLimit => write!(f, "ratelimit"),
+            RuleKind::Duplicates => write!(f, "duplicates"),
+            RuleKind::Invites => write!(f, "invites"),
+            RuleKind::Links => write!(f, "links"),
+            RuleKind::Mentions => write!(f, "mentions"),
+            RuleKind::Words => write!(f, "words"),
+        }
+    }
+}
+
+impl FromStr for RuleKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ratelimit" => Ok(RuleKind::RateLimit),
+            "duplicates" => Ok(RuleKind::Duplicates),
+            "invites" => Ok(RuleKind::Invites),
+            "links" => Ok(RuleKind::Links),
+            "mentions" => Ok(RuleKind::Mentions),
+            "words" => Ok(RuleKind::Words),
+            _ => Err(format!("{} isn't an automod rule!", s)),
+        }
+    }
+}
+
+// Every action also deletes the message that broke the rule
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutomodAction {
+    Delete,
+    Warn,
+    Timeout,
+}
+
+impl fmt::Display for AutomodAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AutomodAction::Delete => write!(f, "delete"),
+            AutomodAction::Warn => write!(f, "warn"),
+            AutomodAction::Timeout => write!(f, "timeout"),
+        }
+    }
+}
+
+impl FromStr for AutomodAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "delete" => Ok(AutomodAction::Delete),
+            "warn" => Ok(AutomodAction::Warn),
+            "timeout" => Ok(AutomodAction::Timeout),
+            _ => Err(format!("{} isn't an automod action!", s)),
+        }
+    }
+}
+
+/*
+ * Invite codes are allowed, and link domains are either allowed or denied
+ * If any domains are allowed, every other link breaks the links rule
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterList {
+    Invites,
+    AllowedLinks,
+    DeniedLinks,
+    Words,
+}
+
+impl FilterList {
+    pub const ALL: [FilterList; 4] = [
+        FilterList::Invites,
+        FilterList::AllowedLinks,
+        FilterList::DeniedLinks,
+        FilterList::Words,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match *self {
+            FilterList::Invites => "Allowed invites",
+            FilterList::AllowedLinks => "Allowed links",
+            FilterList::DeniedLinks => "Denied links",
+            FilterList::Words => "Word filters",
+        }
+    }
+}
+
+impl fmt::Display for FilterList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FilterList::Invites => write!(f, "invites"),
+            FilterList::AllowedLinks => write!(f, "allowlinks"),
+            FilterList::DeniedLinks => write!(f, "denylinks"),
+            FilterList::Words => write!(f, "words"),
+        }
+    }
+}
+
+impl FromStr for FilterList {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "invites" => Ok(FilterList::Invites),
+            "allowlinks" => Ok(FilterList::AllowedLinks),
+            "denylinks" => Ok(FilterList::DeniedLinks),
+            "words" => Ok(FilterList::Words),
+            _ => Err(format!("{} isn't an automod filter list!", s)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutomodExemption {
+    Role(RoleId),
+    Channel(ChannelId),
+}
+
+impl AutomodExemption {
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            AutomodExemption::Role(_) => "role",
+            AutomodExemption::Channel(_) => "channel",
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        match *self {
+            AutomodExemption::Role(role_id) => role_id.0 as i64,
+            AutomodExemption::Channel(channel_id) => channel_id.0 as i64,
+        }
+    }
+
+    pub fn from_row(kind: &str, id: i64) -> Option<AutomodExemption> {
+        match kind {
+            "role" => Some(AutomodExemption::Role(RoleId(id as u64))),
+            "channel" => Some(AutomodExemption::Channel(ChannelId(id as u64))),
+            _ => None,
+        }
+    }
+}
+
+/*
+ * The automod settings as they're checked on every message
+ * Word filters are compiled once here instead of on every message
+ */
+#[derive(Debug)]
+pub struct AutomodConfig {
+    pub rules: Vec<AutomodRule>,
+    pub allowed_invites: Vec<String>,
+    pub allowed_links: Vec<String>,
+    pub denied_links: Vec<String>,
+    pub word_filters: Vec<Regex>,
+    pub exempt_roles: Vec<RoleId>,
+    pub exempt_channels: Vec<ChannelId>,
+}
+
+// Guilds without rules don't get a config, so their messages skip automod entirely
+pub fn build_config(settings: AutomodSettings) -> Option<Arc<AutomodConfig>> {
+    if settings.rules.is_empty() {
+        return None;
+    }
+
+    let mut rules = settings.rules;
+    rules.sort_by_key(|rule| RuleKind::ALL.iter().position(|x| *x == rule.kind));
+
+    let mut config = AutomodConfig {
+        rules,
+        allowed_invites: Vec::new(),
+        allowed_links: Vec::new(),
+        denied_links: Vec::new(),
+        word_filters: Vec::new(),
+        exempt_roles: Vec::new(),
+        exempt_channels: Vec::new(),
+    };
+
+    for (list, pattern) in settings.filters {
+        match list {
+            FilterList::Invites => config.allowed_invites.push(pattern.to_lowercase()),
+            FilterList::AllowedLinks => config.allowed_links.push(pattern),
+            FilterList::DeniedLinks => config.denied_links.push(pattern),
+            FilterList::Words => match compile_filter(&pattern) {
+                Ok(regex) => config.word_filters.push(regex),
+                Err(e) => eprintln!("Skipping invalid word filter {}! {}", pattern, e),
+            },
+        }
+    }
+
+    for exemption in settings.exemptions {
+        match exemption {
+            AutomodExemption::Role(role_id) => config.exempt_roles.push(role_id),
+            AutomodExemption::Channel(channel_id) => config.exempt_channels.push(channel_id),
+        }
+    }
+
+    Some(Arc::new(config))
+}
+
+// Word filters are regexes that ignore case
+pub fn compile_filter(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+}
+
+// Domains are stored without a scheme or www, like "example.com"
+pub fn normalize_domain(domain: &str) -> String {
+    let domain = domain.trim().to_lowercase();
+    let domain = domain
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("www.");
+
+    domain
+        .split(['/', '?', '#', ':'])
+        .next()
+        .unwrap_or_default()
+        .to_owned()
+}
+
+// Subdomains count as their parent domain, so "example.com" also covers "cdn.example.com"
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+fn get_invite_codes(content: &str) -> Vec<String> {
+    INVITE_REGEX
+        .captures_iter(content)
+        .map(|x| x[1].to_lowercase())
+        .collect()
+}
+
+fn get_link_hosts(content: &str) -> Vec<String> {
+    LINK_REGEX
+        .captures_iter(content)
+        .map(|x| normalize_domain(&x[1]))
+        .collect()
+}
+
+fn hash_content(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.trim().to_lowercase().hash(&mut hasher);
+
+    hasher.finish()
+}
+
+/*
+ * Remembers when each user's recent messages were sent, along with a hash of their content
+ * Entries older than the longest rule window are dropped as new messages come in
+ */
+fn track_message(
+    tracker: &DashMap<(GuildId, UserId), VecDeque<(Instant, u64)>>,
+    msg: &Message,
+    guild_id: GuildId,
+    window: Duration,
+    now: Instant,
+) -> Vec<(Instant, u64)> {
+    if tracker.len() > TRACKER_CLEANUP_SIZE {
+        tracker.retain(|_, history| {
+            history
+                .back()
+                .is_some_and(|(sent, _)| now.duration_since(*sent) < window)
+        });
+    }
+
+    let mut history = tracker.entry((guild_id, msg.author.id)).or_default();
+
+    history.push_back((now, hash_content(&msg.content)));
+
+    while history.len() > MAX_TRACKED_MESSAGES
+        || history
+            .front()
+            .is_some_and(|(sent, _)| now.duration_since(*sent) >= window)
+    {
+        history.pop_front();
+    }
+
+    history.iter().copied().collect()
+}
+
+fn count_recent(history: &[(Instant, u64)], now: Instant, interval: i64, hash: Option<u64>) -> i64 {
+    history
+        .iter()
+        .filter(|(sent, _)| now.duration_since(*sent) < Duration::from_secs(interval as u64))
+        .filter(|(_, sent_hash)| hash.is_none() || hash == Some(*sent_hash))
+        .count() as i64
+}
+
+// Returns true if the message breaks the rule
+fn check_rule(
+    config: &AutomodConfig,
+    rule: &AutomodRule,
+    msg: &Message,
+    history: &[(Instant, u64)],
+    now: Instant,
+) -> bool {
+    let threshold = rule
+        .threshold
+        .or_else(|| rule.kind.default_threshold())
+        .unwrap_or_default();
+    let interval = rule
+        .interval_secs
+        .or_else(|| rule.kind.default_interval())
+        .unwrap_or_default();
+
+    match rule.kind {
+        RuleKind::RateLimit => count_recent(history, now, interval, None) > threshold,
+        RuleKind::Duplicates => {
+            // Attachments without text aren't duplicates of each other
+            !msg.content.trim().is_empty()
+                && count_recent(history, now, interval, Some(hash_content(&msg.content)))
+                    >= threshold
+        }
+        RuleKind::Invites => get_invite_codes(&msg.content)
+            .iter()
+            .any(|code| !config.allowed_invites.contains(code)),
+        RuleKind::Links => get_link_hosts(&msg.content).iter().any(|host| {
+            if config.allowed_links.is_empty() {
+                config
+                    .denied_links
+                    .iter()
+                    .any(|domain| domain_matches(host, domain))
+            } else {
+                !config
+                    .allowed_links
+                    .iter()
+                    .any(|domain| domain_matches(host, domain))
+            }
+        }),
+        RuleKind::Mentions => {
+            let mention_count = msg.mentions.len()
+                + msg.mention_roles.len()
+                + if msg.mention_everyone { 1 } else { 0 };
+
+            mention_count as i64 > threshold
+        }
+        RuleKind::Words => config
+            .word_filters
+            .iter()
+            .any(|regex| regex.is_match(&msg.content)),
+    }
+}
+
+/*
+ * Runs on every guild message. Moderators, exempt roles, and exempt channels are skipped
+ * Only the first broken rule is acted on, in the order of RuleKind::ALL
+ */
+pub async fn check_message(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(guild_id) if !msg.author.bot && msg.webhook_id.is_none() => guild_id,
+        _ => return Ok(()),
+    };
+
+    let config = match guild_settings::get_guild_settings(ctx, guild_id)
+        .await?
+        .automod
+    {
+        Some(config) => config,
+        None => return Ok(()),
+    };
+
+    if permissions_helper::check_automod_exemption(
+        ctx,
+        msg,
+        &config.exempt_roles,
+        &config.exempt_channels,
+    )
+    .await?
+    {
+        return Ok(());
+    }
+
+    let tracker = ctx
+        .data
+        .read()
+        .await
+        .get::<AutomodTracker>()
+        .cloned()
+        .unwrap();
+
+    let window = config
+        .rules
+        .iter()
+        .filter_map(|x| x.interval_secs.or_else(|| x.kind.default_interval()))
+        .max();
+
+    let now = Instant::now();
+    let history = match window {
+        Some(window) => track_message(
+            &tracker,
+            msg,
+            guild_id,
+            Duration::from_secs(window as u64),
+            now,
+        ),
+        None => Vec::new(),
+    };
+
+    let rule = match config
+        .rules
+        .iter()
+        .find(|rule| check_rule(&config, rule, msg, &history, now))
+    {
+        Some(rule) => rule,
+        None => return Ok(()),
+    };
+
+    // Otherwise every message after a burst would break the rule again
+    if matches!(rule.kind, RuleKind::RateLimit | RuleKind::Duplicates) {
+        tracker.remove(&(guild_id, msg.author.id));
+    }
+
+    apply_action(ctx, msg, guild_id, rule).await
+}
+
+/*
+ * Automod moderates as the bot, so it's held to the same checks as moderation commands
+ * Returns false if the bot can't time out the user, like when they have a higher role
+ */
+async fn timeout_user(
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    duration: i64,
+) -> CommandResult<bool> {
+    let (permission, _) = ModAction::Timeout.permission();
+    let bot_member = guild_id.member(ctx, ctx.cache.current_user_id()).await?;
+    let member = guild_id.member(ctx, user_id).await?;
+
+    if !bot_member.permissions(ctx)?.contains(permission)
+        || moderation::get_hierarchy_error(ctx, &member, &bot_member, &bot_member)?.is_some()
+    {
+        return Ok(false);
+    }
+
+    let until = Timestamp::from_unix_timestamp(command_utils::get_unix_time() + duration)?;
+
+    guild_id
+        .edit_member(ctx, user_id, |m| {
+            m.disable_communication_until_datetime(until)
+        })
+        .await?;
+
+    Ok(true)
+}
+
+async fn apply_action(
+    ctx: &Context,
+    msg: &Message,
+    guild_id: GuildId,
+    rule: &AutomodRule,
+) -> CommandResult {
+    if let Err(e) = msg.delete(ctx).await {
+        eprintln!("Error when deleting a message with automod! {}", e);
+    }
+
+    let reason = format!("Automod: {}", rule.kind.description());
+
+    let duration = match rule.action {
+        AutomodAction::Delete => return Ok(()),
+        AutomodAction::Warn => None,
+        AutomodAction::Timeout => {
+            let duration = rule.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS);
+
+            match timeout_user(ctx, guild_id, msg.author.id, duration).await {
+                Ok(true) => Some(duration),
+                Ok(false) => None,
+                Err(e) => {
+                    eprintln!("Error when timing out a user with automod! {}", e);
+                    None
+                }
+            }
+        }
+    };
+
+    // Users that can't be timed out are warned instead
+    let (mod_action, response) = match duration {
+        Some(_) => (
+            ModAction::Timeout,
+            format!(
+                "{} has been timed out! Reason: {}",
+                msg.author.mention(),
+                rule.kind.description()
+            ),
+        ),
+        None => (
+            ModAction::Warn,
+            format!(
+                "{}, you've been warned! Reason: {}",
+                msg.author.mention(),
+                rule.kind.description()
+            ),
+        ),
+    };
+
+    msg.channel_id.say(ctx, response).await?;
+
+    let repository = ctx
+        .data
+        .read()
+        .await
+        .get::<RepositoryContainer>()
+        .cloned()
+        .unwrap();
+
+    // Automod cases are saved with the bot as the moderator
+    let mut case = ModCase {
+        guild_id,
+        case_number: 0,
+        action: mod_action,
+        user_id: msg.author.id,
+        moderator_id: ctx.cache.current_user_id(),
+        reason: Some(reason),
+        duration_secs: duration,
+        created_at: command_utils::get_unix_time(),
+    };
+
+    case.case_number = repository.add_case(case.clone()).await?;
+
+    mod_log::log_case(ctx, &case, false).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const GUILD: GuildId = GuildId(1);
+
+    fn new_user(id: u64) -> serde_json::Value {
+        json!({ "id": id.to_string(), "username": "user", "discriminator": "0001", "avatar": null })
+    }
+
+    fn new_message(content: &str, mentions: u64, everyone: bool) -> Message {
+        serde_json::from_value(json!({
+            "id": "1",
+            "channel_id": "2",
+            "author": new_user(3),
+            "content": content,
+            "timestamp": "2026-10-18T00:00:00Z",
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": everyone,
+            "mentions": (0..mentions).map(|x| new_user(100 + x)).collect::<Vec<_>>(),
+            "mention_roles": [],
+            "attachments": [],
+            "embeds": [],
+            "pinned": false,
+            "type": 0,
+        }))
+        .unwrap()
+    }
+
+    fn new_rule(kind: RuleKind, threshold: Option<i64>, interval_secs: Option<i64>) -> AutomodRule {
+        AutomodRule {
+            kind,
+            action: AutomodAction::Delete,
+            threshold,
+            interval_secs,
+            timeout_secs: None,
+        }
+    }
+
+    fn new_config(rule: AutomodRule, filters: &[(FilterList, &str)]) -> Arc<AutomodConfig> {
+        build_config(AutomodSettings {
+            rules: vec![rule],
+            filters: filters
+                .iter()
+                .map(|(list, pattern)| (*list, pattern.to_string()))
+                .collect(),
+            exemptions: Vec::new(),
+        })
+        .unwrap()
+    }
+
+    fn breaks_rule(config: &AutomodConfig, content: &str) -> bool {
+        let msg = new_message(content, 0, false);
+
+        check_rule(config, &config.rules[0], &msg, &[], Instant::now())
+    }
+
+    #[test]
+    fn domains_are_normalized() {
+        assert_eq!(
+            normalize_domain("https://www.Example.com/path?x=1"),
+            "example.com"
+        );
+        assert_eq!(
+            normalize_domain(" cdn.example.com:8080 "),
+            "cdn.example.com"
+        );
+        assert_eq!(normalize_domain("example.com#top"), "example.com");
+    }
+
+    #[test]
+    fn subdomains_match_their_parent() {
+        assert!(domain_matches("example.com", "example.com"));
+        assert!(domain_matches("cdn.example.com", "example.com"));
+        assert!(!domain_matches("badexample.com", "example.com"));
+        assert!(!domain_matches("example.com", "cdn.example.com"));
+    }
+
+    #[test]
+    fn denied_links_cover_subdomains() {
+        let config = new_config(
+            new_rule(RuleKind::Links, None, None),
+            &[(FilterList::DeniedLinks, "example.com")],
+        );
+
+        assert!(breaks_rule(&config, "look https://cdn.example.com/x.png"));
+        assert!(!breaks_rule(&config, "look https://other.com"));
+        assert!(!breaks_rule(&config, "no links here"));
+    }
+
+    #[test]
+    fn allowed_links_take_precedence_over_denied_links() {
+        let config = new_config(
+            new_rule(RuleKind::Links, None, None),
+            &[
+                (FilterList::AllowedLinks, "example.com"),
+                (FilterList::DeniedLinks, "example.com"),
+            ],
+        );
+
+        assert!(!breaks_rule(&config, "https://www.example.com/page"));
+        assert!(breaks_rule(&config, "https://other.com"));
+        assert!(breaks_rule(
+            &config,
+            "https://example.com and http://other.com"
+        ));
+    }
+
+    #[test]
+    fn allowed_invites_pass() {
+        let config = new_config(
+            new_rule(RuleKind::Invites, None, None),
+            &[(FilterList::Invites, "Jester")],
+        );
+
+        assert!(!breaks_rule(&config, "join discord.gg/jester"));
+        assert!(!breaks_rule(&config, "https://discord.com/invite/JESTER"));
+        assert!(breaks_rule(&config, "join discord.gg/other"));
+        assert!(breaks_rule(
+            &config,
+            "discord.gg/jester or discordapp.com/invite/other"
+        ));
+    }
+
+    #[test]
+    fn mentions_count_everyone() {
+        let config = new_config(new_rule(RuleKind::Mentions, Some(3), None), &[]);
+        let check = |mentions, everyone| {
+            let msg = new_message("hi", mentions, everyone);
+            check_rule(&config, &config.rules[0], &msg, &[], Instant::now())
+        };
+
+        assert!(!check(3, false));
+        assert!(check(3, true));
+        assert!(check(4, false));
+    }
+
+    #[test]
+    fn duplicates_compare_message_hashes() {
+        let config = new_config(new_rule(RuleKind::Duplicates, Some(3), Some(30)), &[]);
+        let tracker = DashMap::new();
+        let window = Duration::from_secs(30);
+        let start = Instant::now();
+
+        let check = |content: &str, offset: u64| {
+            let msg = new_message(content, 0, false);
+            let now = start + Duration::from_secs(offset);
+            let history = track_message(&tracker, &msg, GUILD, window, now);
+
+            check_rule(&config, &config.rules[0], &msg, &history, now)
+        };
+
+        assert!(!check("spam", 0));
+        assert!(!check("something else", 1));
+        assert!(!check(" SPAM ", 2));
+        assert!(check("spam", 3));
+    }
+
+    #[test]
+    fn rate_limits_only_count_the_window() {
+        let config = new_config(new_rule(RuleKind::RateLimit, Some(2), Some(5)), &[]);
+        let tracker = DashMap::new();
+        let window = Duration::from_secs(5);
+        let start = Instant::now();
+
+        let check = |offset: u64| {
+            let msg = new_message("hi", 0, false);
+            let now = start + Duration::from_secs(offset);
+            let history = track_message(&tracker, &msg, GUILD, window, now);
+
+            (
+                history.len(),
+                check_rule(&config, &config.rules[0], &msg, &history, now),
+            )
+        };
+
+        assert_eq!(check(0), (1, false));
+        assert_eq!(check(1), (2, false));
+        assert_eq!(check(2), (3, true));
+
+        // The first two messages have left the window by now
+        assert_eq!(check(6), (2, false));
+        assert_eq!(check(20), (1, false));
+    }
+}
@@ -0,0 +1,356 @@
+use serenity::{
+    client::Context,
+    framework::standard::{CommandGroup, CommandResult},
+    model::id::{ChannelId, GuildId},
+    prelude::Mentionable,
+};
+use std::collections::HashMap;
+
+use crate::{
+    helpers::guild_settings,
+    repositories::CommandRules,
+    structures::errors::{JesterError, PermissionType},
+    CommandScopeMap,
+};
+
+// Bot configuration commands can't be disabled or restricted, otherwise admins could lock themselves out
+pub const PROTECTED_GROUP: &str = "botconfiguration";
+
+#[derive(Clone, Debug)]
+pub struct CommandScope {
+    pub command: String,
+    pub group: String,
+    pub subcommand: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CommandAccess {
+    Allowed,
+    Disabled,
+    WrongChannel(Vec<ChannelId>),
+}
+
+// Group keys are the group names without spaces, ex. "Text Modification" becomes "textmodification"
+pub fn get_group_key(group: &CommandGroup) -> String {
+    group.name.to_lowercase().replace(' ', "")
+}
+
+/*
+ * Maps every command name, alias, and subcommand name to the top level command and group it belongs to
+ * Subcommand names can be shared between commands, so each name can have multiple scopes
+ */
+pub fn build_command_scopes(
+    groups: &[&'static CommandGroup],
+) -> HashMap<String, Vec<CommandScope>> {
+    let mut scopes: HashMap<String, Vec<CommandScope>> = HashMap::new();
+
+    for group in groups {
+        let group_key = get_group_key(group);
+
+        for command in group.options.commands {
+            let command_name = command.options.names[0].to_owned();
+
+            for name in command.options.names {
+                scopes
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(CommandScope {
+                        command: command_name.to_owned(),
+                        group: group_key.to_owned(),
+                        subcommand: false,
+                    });
+            }
+
+            for sub_command in command.options.sub_commands {
+                for name in sub_command.options.names {
+                    scopes
+                        .entry(name.to_string())
+                        .or_default()
+                        .push(CommandScope {
+                            command: command_name.to_owned(),
+                            group: group_key.to_owned(),
+                            subcommand: true,
+                        });
+                }
+            }
+        }
+    }
+
+    scopes
+}
+
+/*
+ * Finds which command an invoked name belongs to
+ * If a subcommand name is shared, the parent command used in the message content wins
+ */
+pub fn resolve_scope<'a>(
+    scopes: &'a [CommandScope],
+    content: Option<&str>,
+) -> Option<&'a CommandScope> {
+    if scopes.len() < 2 {
+        return scopes.first();
+    }
+
+    if let Some(content) = content {
+        let words = content.to_lowercase();
+
+        for word in words.split_whitespace() {
+            if let Some(scope) = scopes.iter().find(|x| word.ends_with(&x.command)) {
+                return Some(scope);
+            }
+        }
+    }
+
+    scopes.iter().find(|x| !x.subcommand)
+}
+
+// Gets the name of an invoked command, including the parent command if it's a subcommand
+pub fn get_full_command_name(
+    scope_map: &HashMap<String, Vec<CommandScope>>,
+    invoked_name: &str,
+    content: Option<&str>,
+) -> String {
+    match scope_map
+        .get(invoked_name)
+        .and_then(|scopes| resolve_scope(scopes, content))
+    {
+        Some(scope) if scope.subcommand => format!("{} {}", scope.command, invoked_name),
+        Some(scope) => scope.command.to_owned(),
+        None => invoked_name.to_owned(),
+    }
+}
+
+// Resolves a name given by an admin to a command or group key
+pub fn resolve_rule_name(
+    scope_map: &HashMap<String, Vec<CommandScope>>,
+    name: &str,
+) -> Option<(String, String)> {
+    let name = name.to_lowercase();
+
+    if let Some(scope) = scope_map
+        .get(&name)
+        .and_then(|scopes| scopes.iter().find(|x| !x.subcommand))
+    {
+        return Some((scope.command.to_owned(), scope.group.to_owned()));
+    }
+
+    scope_map
+        .values()
+        .flatten()
+        .find(|x| x.group == name)
+        .map(|x| (x.group.to_owned(), x.group.to_owned()))
+}
+
+/*
+ * Checks if a command can run in a given guild channel
+ * A command's channel list takes priority over its group's channel list
+ * If neither has a channel list, the command can be used anywhere
+ */
+pub async fn check_command_access(
+    ctx: &Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    invoked_name: &str,
+    content: Option<&str>,
+) -> CommandResult<CommandAccess> {
+    let scope_map = ctx
+        .data
+        .read()
+        .await
+        .get::<CommandScopeMap>()
+        .cloned()
+        .unwrap();
+
+    let scope = match scope_map
+        .get(invoked_name)
+        .and_then(|scopes| resolve_scope(scopes, content))
+    {
+        Some(scope) if scope.group != PROTECTED_GROUP => scope,
+        _ => return Ok(CommandAccess::Allowed),
+    };
+
+    let settings = guild_settings::get_guild_settings(ctx, guild_id).await?;
+
+    Ok(get_command_access(
+        &settings.command_rules,
+        scope,
+        channel_id,
+    ))
+}
+
+pub fn get_command_access(
+    rules: &CommandRules,
+    scope: &CommandScope,
+    channel_id: ChannelId,
+) -> CommandAccess {
+    if rules.disabled.contains(&scope.command) || rules.disabled.contains(&scope.group) {
+        return CommandAccess::Disabled;
+    }
+
+    let get_channels = |name: &str| {
+        rules
+            .channels
+            .iter()
+            .filter(|(x, _)| x == name)
+            .map(|(_, channel_id)| *channel_id)
+            .collect::<Vec<ChannelId>>()
+    };
+
+    let mut allowed_channels = get_channels(&scope.command);
+
+    if allowed_channels.is_empty() {
+        allowed_channels = get_channels(&scope.group);
+    }
+
+    if allowed_channels.is_empty() || allowed_channels.contains(&channel_id) {
+        CommandAccess::Allowed
+    } else {
+        CommandAccess::WrongChannel(allowed_channels)
+    }
+}
+
+pub fn get_channel_message(locale: &str, channels: &[ChannelId]) -> String {
+    let channel_string = channels
+        .iter()
+        .map(|x| x.mention().to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    JesterError::PermissionError(PermissionType::Channel(&channel_string)).localized(locale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        helpers::cooldowns::{self, Cooldown, CooldownScope},
+        repositories::{memory::MemoryRepository, Repository},
+    };
+    use serenity::model::id::GuildId;
+    use std::sync::Arc;
+
+    const GUILD: GuildId = GuildId(1);
+
+    async fn setup() -> Arc<dyn Repository> {
+        let repository: Arc<dyn Repository> = Arc::new(MemoryRepository::new());
+        repository.add_guild(GUILD).await.unwrap();
+
+        repository
+    }
+
+    fn new_scope(command: &str, group: &str) -> CommandScope {
+        CommandScope {
+            command: command.to_owned(),
+            group: group.to_owned(),
+            subcommand: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn disabled_groups_disable_their_commands() {
+        let repository = setup().await;
+        repository.set_disabled(GUILD, "fun", true).await.unwrap();
+        repository.set_disabled(GUILD, "ping", true).await.unwrap();
+        repository.set_disabled(GUILD, "ping", false).await.unwrap();
+
+        let rules = repository.fetch_command_rules(GUILD).await.unwrap();
+
+        assert_eq!(
+            get_command_access(&rules, &new_scope("hug", "fun"), ChannelId(5)),
+            CommandAccess::Disabled
+        );
+        assert_eq!(
+            get_command_access(&rules, &new_scope("ping", "utility"), ChannelId(5)),
+            CommandAccess::Allowed
+        );
+    }
+
+    #[tokio::test]
+    async fn command_channels_take_priority_over_group_channels() {
+        let repository = setup().await;
+        repository
+            .add_command_channels(GUILD, "utility", &[ChannelId(5)])
+            .await
+            .unwrap();
+        repository
+            .add_command_channels(GUILD, "ping", &[ChannelId(7), ChannelId(6)])
+            .await
+            .unwrap();
+
+        let rules = repository.fetch_command_rules(GUILD).await.unwrap();
+        let ping = new_scope("ping", "utility");
+        let remind = new_scope("remind", "utility");
+
+        assert_eq!(
+            get_command_access(&rules, &ping, ChannelId(5)),
+            CommandAccess::WrongChannel(vec![ChannelId(6), ChannelId(7)])
+        );
+        assert_eq!(
+            get_command_access(&rules, &ping, ChannelId(7)),
+            CommandAccess::Allowed
+        );
+        assert_eq!(
+            get_command_access(&rules, &remind, ChannelId(5)),
+            CommandAccess::Allowed
+        );
+
+        repository
+            .clear_command_channels(GUILD, "utility")
+            .await
+            .unwrap();
+        let rules = repository.fetch_command_rules(GUILD).await.unwrap();
+
+        assert_eq!(
+            get_command_access(&rules, &remind, ChannelId(9)),
+            CommandAccess::Allowed
+        );
+    }
+
+    #[tokio::test]
+    async fn cooldown_overrides_replace_the_default() {
+        let repository = setup().await;
+        let channel_cooldown = Cooldown {
+            scope: CooldownScope::Channel,
+            seconds: 30,
+        };
+
+        repository
+            .set_cooldown(GUILD, "ping", Some(channel_cooldown))
+            .await
+            .unwrap();
+        repository
+            .set_cooldown(
+                GUILD,
+                "hug",
+                Some(Cooldown {
+                    scope: CooldownScope::User,
+                    seconds: 0,
+                }),
+            )
+            .await
+            .unwrap();
+
+        let rules = repository.fetch_command_rules(GUILD).await.unwrap();
+
+        assert_eq!(
+            cooldowns::get_cooldown(Some(&rules), "ping"),
+            Some(channel_cooldown)
+        );
+        assert_eq!(cooldowns::get_cooldown(Some(&rules), "hug"), None);
+        assert_eq!(
+            cooldowns::get_cooldown(Some(&rules), "vibecheck"),
+            cooldowns::get_default_cooldown("vibecheck")
+        );
+
+        // DMs only have the default cooldowns
+        assert_eq!(
+            cooldowns::get_cooldown(None, "hug"),
+            cooldowns::get_default_cooldown("hug")
+        );
+
+        repository.set_cooldown(GUILD, "ping", None).await.unwrap();
+        let rules = repository.fetch_command_rules(GUILD).await.unwrap();
+
+        assert_eq!(cooldowns::get_cooldown(Some(&rules), "ping"), None);
+    }
+}
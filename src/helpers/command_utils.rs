@@ -9,7 +9,9 @@ use serenity::{
     },
 };
 
-use crate::structures::cmd_data::{PrefixMap, PubCreds};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{helpers::guild_settings, structures::cmd_data::PubCreds};
 
 pub fn get_message_url(guild_id: GuildId, channel_id: ChannelId, message_id: MessageId) -> String {
     format!(
@@ -18,6 +20,14 @@ pub fn get_message_url(guild_id: GuildId, channel_id: ChannelId, message_id: Mes
     )
 }
 
+// All stored timestamps are in seconds since the unix epoch
+pub fn get_unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards?")
+        .as_secs() as i64
+}
+
 // `str::split_whitespace` returns substrings to the original string
 // this means we can calculate the index to the beginning of the substring
 // in the original string with simple arithmetic on their pointers
@@ -51,57 +61,209 @@ pub fn truncate(s: &str, words: usize) -> String {
     format!("{}...", &s[..n])
 }
 
-#[allow(clippy::needless_lifetimes)]
-pub async fn get_command_name<'a>(ctx: &Context, msg: &'a Message) -> &'a str {
-    let (prefixes, default_prefix) = {
-        let data = ctx.data.read().await;
-
-        let prefixes = data.get::<PrefixMap>().unwrap().clone();
-        let default_prefix = data
-            .get::<PubCreds>()
-            .unwrap()
-            .get("default prefix")
-            .cloned()
-            .unwrap();
-
-        (prefixes, default_prefix)
+// DMs and guilds without custom prefixes use the default prefix
+pub async fn get_guild_prefixes(ctx: &Context, guild_id: Option<GuildId>) -> Vec<String> {
+    let default_prefix = ctx
+        .data
+        .read()
+        .await
+        .get::<PubCreds>()
+        .unwrap()
+        .get("default prefix")
+        .cloned()
+        .unwrap();
+
+    let guild_id = match guild_id {
+        Some(guild_id) => guild_id,
+        None => return vec![default_prefix],
     };
 
-    let guild_id = msg.guild_id.unwrap();
+    match guild_settings::get_guild_settings(ctx, guild_id).await {
+        Ok(settings) if !settings.prefixes.is_empty() => settings.prefixes,
+        Ok(_) => vec![default_prefix],
+        Err(e) => {
+            eprintln!(
+                "Error when loading the prefixes of guild {}! {}",
+                guild_id.0, e
+            );
+            vec![default_prefix]
+        }
+    }
+}
 
-    let prefix_length = match prefixes.get(&guild_id) {
-        Some(prefix_guard) => prefix_guard.value().len(),
-        None => default_prefix.len(),
-    };
+/*
+ * Prefixes are case insensitive, so this returns the prefix as it was written in the message
+ * The longest prefix is checked first in case one prefix starts with another
+ */
+pub fn find_prefix<'a>(content: &'a str, prefixes: &[String]) -> Option<&'a str> {
+    let mut prefixes = prefixes.iter().collect::<Vec<&String>>();
+    prefixes.sort_by_key(|prefix| std::cmp::Reverse(prefix.chars().count()));
+
+    prefixes.into_iter().find_map(|prefix| {
+        let end = content
+            .char_indices()
+            .nth(prefix.chars().count())
+            .map_or(content.len(), |(index, _)| index);
+        let written_prefix = &content[..end];
+
+        if written_prefix.to_lowercase() == prefix.to_lowercase() {
+            Some(written_prefix)
+        } else {
+            None
+        }
+    })
+}
+
+#[allow(clippy::needless_lifetimes)]
+pub async fn get_command_name<'a>(ctx: &Context, msg: &'a Message) -> &'a str {
+    let prefixes = get_guild_prefixes(ctx, msg.guild_id).await;
 
     let words = msg.content.split_whitespace().collect::<Vec<&str>>();
     let command = words[0];
+    let prefix_length = find_prefix(command, &prefixes).map_or(0, str::len);
 
     &command[prefix_length..]
 }
 
 /*
-pub fn deconstruct_time(input: String) -> CommandResult<u64> {
-    let mut segments = input.rsplit(':');
+ * Everything after the invoked command name
+ * The first word ending with the command name is the prefix and command
+ */
+pub fn get_command_args<'a>(content: &'a str, command_name: &str) -> &'a str {
+    match content
+        .split_whitespace()
+        .find(|word| word.to_lowercase().ends_with(&command_name.to_lowercase()))
+    {
+        Some(word) => content[ptr_diff(word, content) + word.len()..].trim(),
+        None => "",
+    }
+}
+
+/*
+ * Durations are written like 2h30m, using w, d, h, m, and s
+ * Returns the duration in seconds
+ */
+pub fn parse_duration(input: &str) -> Option<i64> {
+    if input.is_empty() {
+        return None;
+    }
 
-    let seconds = match segments.next().unwrap_or("0").parse::<u64>() {
-        Ok(secs) => secs,
-        Err(_) => return Err("seconds".into()),
-    };
-    let minutes = match segments.next().unwrap_or("0").parse::<u64>() {
-        Ok(mins) => mins,
-        Err(_) => return Err("minutes".into()),
+    let mut total: i64 = 0;
+    let mut number = String::new();
+
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let unit = match c.to_ascii_lowercase() {
+            'w' => 604800,
+            'd' => 86400,
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+
+        let value = number.parse::<i64>().ok()?;
+        total = total.checked_add(value.checked_mul(unit)?)?;
+        number.clear();
+    }
+
+    // Every number needs a unit after it
+    if !number.is_empty() {
+        return None;
+    }
+
+    Some(total)
+}
+
+/*
+ * Absolute times are in UTC and written as YYYY-MM-DD HH:MM, YYYY-MM-DD, or HH:MM
+ * A time without a date is the next time the clock shows it
+ * Discord timestamps like <t:1700000000:R> also work, since they show up in everyone's timezone
+ */
+pub fn parse_time(input: &str, current_time: i64) -> Option<i64> {
+    if let Some(timestamp) = input.strip_prefix("<t:").and_then(|x| x.strip_suffix('>')) {
+        return timestamp.split(':').next()?.parse::<i64>().ok();
+    }
+
+    let (date, time) = match input.split_once(' ') {
+        Some((date, time)) => (Some(date), Some(time)),
+        None if input.contains('-') => (Some(input), None),
+        None => (None, Some(input)),
     };
-    let hours = match segments.next().unwrap_or("0").parse::<u64>() {
-        Ok(mins) => mins,
-        Err(_) => return Err("hours".into()),
+
+    let seconds = match time {
+        Some(time) => {
+            let (hours, minutes) = time.split_once(':')?;
+            let hours = hours.parse::<i64>().ok().filter(|x| (0..24).contains(x))?;
+            let minutes = minutes
+                .parse::<i64>()
+                .ok()
+                .filter(|x| (0..60).contains(x))?;
+
+            hours * 3600 + minutes * 60
+        }
+        None => 0,
     };
 
-    let result = seconds + (minutes * 60) + (hours * 3600);
+    match date {
+        Some(date) => {
+            let mut segments = date.splitn(3, '-');
+            let year = segments.next()?.parse::<i64>().ok()?;
+            let month = segments.next()?.parse::<i64>().ok()?;
+            let day = segments.next()?.parse::<i64>().ok()?;
+
+            if !(1..=12).contains(&month) || day < 1 || day > get_days_in_month(year, month) {
+                return None;
+            }
+
+            get_days_from_epoch(year, month, day)?
+                .checked_mul(86400)?
+                .checked_add(seconds)
+        }
+        None => {
+            let time = current_time - current_time.rem_euclid(86400) + seconds;
+
+            if time <= current_time {
+                Some(time + 86400)
+            } else {
+                Some(time)
+            }
+        }
+    }
+}
+
+fn get_days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/*
+ * Counts the days from 1970-01-01 in the proleptic Gregorian calendar
+ * Returns None if the year is too far away to count in an i64
+ */
+fn get_days_from_epoch(year: i64, month: i64, day: i64) -> Option<i64> {
+    let year = if month <= 2 {
+        year.checked_sub(1)?
+    } else {
+        year
+    };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
 
-    Ok(result)
+    era.checked_mul(146097)?
+        .checked_add(day_of_era)?
+        .checked_sub(719468)
 }
-*/
 
 pub fn check_mention_prefix(msg: &Message) -> bool {
     let words = msg.content.split_whitespace().collect::<Vec<&str>>();
@@ -140,3 +302,99 @@ pub async fn fetch_avatar(ctx: &Context, user_id: UserId, guild: Option<Guild>)
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("2h30m"), Some(9000));
+        assert_eq!(parse_duration("1d2h"), Some(93600));
+        assert_eq!(parse_duration("1w1s"), Some(604801));
+        assert_eq!(parse_duration("1H"), Some(3600));
+        assert_eq!(parse_duration("0m"), Some(0));
+    }
+
+    #[test]
+    fn rejects_bad_durations() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("5"), None);
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("5x"), None);
+        assert_eq!(parse_duration("1h 2m"), None);
+        assert_eq!(parse_duration("-5m"), None);
+    }
+
+    #[test]
+    fn rejects_overflowing_durations() {
+        assert_eq!(parse_duration("99999999999999999999s"), None);
+        assert_eq!(parse_duration("15250284452472w"), None);
+        assert_eq!(parse_duration("9223372036854775807s1s"), None);
+        assert_eq!(
+            parse_duration("9223372036854775807s"),
+            Some(9223372036854775807)
+        );
+    }
+
+    #[test]
+    fn counts_days_in_month() {
+        assert_eq!(get_days_in_month(2024, 2), 29);
+        assert_eq!(get_days_in_month(2023, 2), 28);
+        assert_eq!(get_days_in_month(2000, 2), 29);
+        assert_eq!(get_days_in_month(1900, 2), 28);
+        assert_eq!(get_days_in_month(2023, 4), 30);
+        assert_eq!(get_days_in_month(2023, 12), 31);
+    }
+
+    #[test]
+    fn counts_days_from_epoch() {
+        assert_eq!(get_days_from_epoch(1970, 1, 1), Some(0));
+        assert_eq!(get_days_from_epoch(1969, 12, 31), Some(-1));
+        assert_eq!(get_days_from_epoch(2026, 1, 1), Some(20454));
+        assert_eq!(get_days_from_epoch(i64::MAX, 1, 1), None);
+        assert_eq!(get_days_from_epoch(i64::MIN, 1, 1), None);
+    }
+
+    #[test]
+    fn parses_dates() {
+        assert_eq!(parse_time("1970-01-01", 0), Some(0));
+        assert_eq!(parse_time("2024-02-29", 0), Some(1709164800));
+        assert_eq!(parse_time("2000-02-29", 0), Some(951782400));
+        assert_eq!(parse_time("2025-12-31 23:30", 0), Some(1767223800));
+        assert_eq!(parse_time("2026-01-01 00:00", 0), Some(1767225600));
+    }
+
+    #[test]
+    fn rejects_bad_dates() {
+        assert_eq!(parse_time("2023-02-29", 0), None);
+        assert_eq!(parse_time("1900-02-29", 0), None);
+        assert_eq!(parse_time("2023-13-01", 0), None);
+        assert_eq!(parse_time("2023-04-31", 0), None);
+        assert_eq!(parse_time("2023-01-01 24:00", 0), None);
+        assert_eq!(parse_time("2023-01-01 12:60", 0), None);
+        assert_eq!(parse_time("tomorrow", 0), None);
+    }
+
+    #[test]
+    fn rejects_overflowing_dates() {
+        assert_eq!(parse_time("9223372036854775807-01-01", 0), None);
+        assert_eq!(parse_time("999999999999999-01-01", 0), None);
+    }
+
+    #[test]
+    fn times_without_a_date_roll_over() {
+        // 2025-12-31 23:30, so 00:15 is in the next day, month, and year
+        let current_time = 1767223800;
+
+        assert_eq!(parse_time("00:15", current_time), Some(1767226500));
+        assert_eq!(parse_time("23:45", current_time), Some(1767224700));
+        assert_eq!(parse_time("23:30", current_time), Some(1767310200));
+    }
+
+    #[test]
+    fn parses_discord_timestamps() {
+        assert_eq!(parse_time("<t:1700000000:R>", 0), Some(1700000000));
+        assert_eq!(parse_time("<t:1700000000>", 0), Some(1700000000));
+    }
+}
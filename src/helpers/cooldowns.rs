@@ -0,0 +1,151 @@
+use serenity::{
+    client::Context,
+    framework::standard::CommandResult,
+    model::id::{ChannelId, GuildId, UserId},
+};
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    helpers::{guild_settings, locale},
+    repositories::CommandRules,
+    CooldownMap,
+};
+
+// Expired cooldowns are only cleared once the map gets this big
+const CLEANUP_THRESHOLD: usize = 10000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CooldownScope {
+    User,
+    Channel,
+    Guild,
+}
+
+impl fmt::Display for CooldownScope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CooldownScope::User => write!(f, "user"),
+            CooldownScope::Channel => write!(f, "channel"),
+            CooldownScope::Guild => write!(f, "guild"),
+        }
+    }
+}
+
+impl FromStr for CooldownScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "user" => Ok(CooldownScope::User),
+            "channel" => Ok(CooldownScope::Channel),
+            "guild" | "server" => Ok(CooldownScope::Guild),
+            _ => Err(format!("{} isn't a cooldown scope!", s)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cooldown {
+    pub scope: CooldownScope,
+    pub seconds: u64,
+}
+
+/*
+ * Default cooldowns for commands that call external APIs or are easy to spam
+ * Guilds can override these or add cooldowns to other commands
+ */
+pub fn get_default_cooldown(command_name: &str) -> Option<Cooldown> {
+    let (scope, seconds) = match command_name {
+        "vibecheck" => (CooldownScope::User, 10),
+        "hug" | "pat" | "slap" | "kiss" | "disgust" | "cry" | "cringe" => (CooldownScope::User, 5),
+        "gifsearch" => (CooldownScope::User, 5),
+        "anime" => (CooldownScope::Channel, 10),
+        _ => return None,
+    };
+
+    Some(Cooldown { scope, seconds })
+}
+
+// Guild rules are None in DMs, where only default cooldowns apply
+pub fn get_cooldown(rules: Option<&CommandRules>, command_name: &str) -> Option<Cooldown> {
+    let guild_cooldown = rules.and_then(|rules| {
+        rules
+            .cooldowns
+            .iter()
+            .find(|(name, _)| name == command_name)
+            .map(|(_, cooldown)| *cooldown)
+    });
+
+    // An override of 0 seconds turns the cooldown off
+    match guild_cooldown {
+        Some(cooldown) if cooldown.seconds == 0 => None,
+        Some(cooldown) => Some(cooldown),
+        None => get_default_cooldown(command_name),
+    }
+}
+
+/*
+ * Starts the command's cooldown if it isn't running
+ * Returns the seconds left if the command is still on cooldown
+ * In DMs, guild cooldowns fall back to the channel
+ */
+pub async fn check_cooldown(
+    ctx: &Context,
+    guild_id: Option<GuildId>,
+    channel_id: ChannelId,
+    user_id: UserId,
+    command_name: &str,
+) -> CommandResult<Option<u64>> {
+    let cooldowns = ctx.data.read().await.get::<CooldownMap>().cloned().unwrap();
+
+    let rules = match guild_id {
+        Some(guild_id) => Some(
+            guild_settings::get_guild_settings(ctx, guild_id)
+                .await?
+                .command_rules,
+        ),
+        None => None,
+    };
+
+    let cooldown = match get_cooldown(rules.as_deref(), command_name) {
+        Some(cooldown) => cooldown,
+        None => return Ok(None),
+    };
+
+    let target_id = match (cooldown.scope, guild_id) {
+        (CooldownScope::User, _) => user_id.0,
+        (CooldownScope::Guild, Some(guild_id)) => guild_id.0,
+        (CooldownScope::Guild, None) | (CooldownScope::Channel, _) => channel_id.0,
+    };
+
+    let now = Instant::now();
+    let key = (command_name.to_owned(), cooldown.scope, target_id);
+
+    if let Some(expiry) = cooldowns.get(&key).map(|x| *x.value()) {
+        if expiry > now {
+            let remaining = expiry.duration_since(now).as_secs_f64().ceil() as u64;
+
+            return Ok(Some(remaining.max(1)));
+        }
+    }
+
+    if cooldowns.len() > CLEANUP_THRESHOLD {
+        cooldowns.retain(|_, expiry| *expiry > now);
+    }
+
+    cooldowns.insert(key, now + Duration::from_secs(cooldown.seconds));
+
+    Ok(None)
+}
+
+pub fn get_cooldown_message(locale: &str, remaining: u64) -> String {
+    locale::get_formatted(
+        locale,
+        "error.cooldown",
+        &[("seconds", &remaining.to_string())],
+    )
+}
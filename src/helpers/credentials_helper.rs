@@ -1,9 +1,48 @@
-use serde::{Deserialize, Serialize};
-use serenity::framework::standard::CommandResult;
-use std::fs;
-use std::io::BufReader;
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    error::Error,
+    fmt, fs,
+    path::Path,
+};
+
+use crate::{commands::config::validate_prefix, helpers::database_helper::is_sqlite_url};
+
+// Every key can be overridden with an environment variable, like COURTJESTER_BOT_TOKEN
+const ENV_PREFIX: &str = "COURTJESTER_";
+
+const KEYS: [&str; 8] = [
+    "bot_token",
+    "application_id",
+    "default_prefix",
+    "db_connection",
+    "tenor_key",
+    "mal_key",
+    "shard_count",
+    "shard_range",
+];
+
+/*
+ * Secrets can also be read from a file with <key>_file, like bot_token_file
+ * Systemd credentials (LoadCredential=bot_token:...) are picked up automatically
+ */
+const SECRET_KEYS: [&str; 4] = ["bot_token", "db_connection", "tenor_key", "mal_key"];
+
+// Commands that can't work without their API key
+const TENOR_COMMANDS: [&str; 9] = [
+    "hug",
+    "pat",
+    "slap",
+    "kiss",
+    "disgust",
+    "cry",
+    "cringe",
+    "gifsearch",
+    "gif",
+];
+const MAL_COMMANDS: [&str; 2] = ["anime", "manga"];
 
-#[derive(Serialize, Deserialize)]
 pub struct Credentials {
     pub bot_token: String,
     pub application_id: u64,
@@ -11,17 +50,305 @@ pub struct Credentials {
     pub db_connection: String,
     // pub lavalink_host: String,
     // pub lavalink_auth: String,
-    pub tenor_key: String,
+    pub tenor_key: Option<String>,
     // pub spotify_client_id: String,
     // pub spotify_client_secret: String,
-    pub mal_key: String,
+    pub mal_key: Option<String>,
+    // Discord picks the shard count if it isn't set
+    pub shard_count: Option<u64>,
+    // The first and last shard this process runs, or every shard if it isn't set
+    pub shard_range: Option<[u64; 2]>,
+}
+
+impl Credentials {
+    // Missing API keys turn off the commands that use them instead of failing at runtime
+    pub fn get_unavailable_commands(&self) -> HashSet<String> {
+        let mut commands = HashSet::new();
+
+        if self.tenor_key.is_none() {
+            commands.extend(TENOR_COMMANDS.iter().map(|x| x.to_string()));
+        }
+
+        if self.mal_key.is_none() {
+            commands.extend(MAL_COMMANDS.iter().map(|x| x.to_string()));
+        }
+
+        commands
+    }
 }
 
-pub fn read_creds(path: &str) -> CommandResult<Credentials> {
-    let file = fs::File::open(path)?;
-    let reader = BufReader::new(file);
+#[derive(Debug)]
+pub struct ConfigError {
+    errors: Vec<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "The bot's configuration is invalid!")?;
+
+        for error in &self.errors {
+            writeln!(f, "  - {}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for ConfigError {}
+
+/*
+ * Configuration is layered in this order, with later layers taking priority:
+ * 1. The config file (TOML if it ends in .toml, JSON otherwise)
+ * 2. COURTJESTER_* environment variables
+ *
+ * The file is optional if everything is provided through the environment
+ */
+pub fn read_creds(path: Option<&str>) -> Result<Credentials, ConfigError> {
+    let mut errors = Vec::new();
+
+    let path = path
+        .map(|x| x.to_owned())
+        .or_else(|| env::var(format!("{}CONFIG", ENV_PREFIX)).ok());
+
+    let mut values = match path {
+        Some(path) => read_file(&path, &mut errors),
+        None => HashMap::new(),
+    };
+
+    for key in get_valid_keys() {
+        if let Ok(value) = env::var(format!("{}{}", ENV_PREFIX, key.to_uppercase())) {
+            values.insert(key, value);
+        }
+    }
+
+    // Empty values are treated as missing so a blank optional key doesn't count as set
+    values.retain(|_, value| !value.trim().is_empty());
+
+    let bot_token = get_secret(&values, "bot_token", &mut errors);
+    let db_connection = get_secret(&values, "db_connection", &mut errors);
+    let tenor_key = get_secret(&values, "tenor_key", &mut errors);
+    let mal_key = get_secret(&values, "mal_key", &mut errors);
+
+    if bot_token.is_none() {
+        errors.push(get_missing_error("bot_token"));
+    }
+
+    let application_id = match values.get("application_id") {
+        Some(value) => match value.trim().parse::<u64>() {
+            Ok(id) if id > 0 => Some(id),
+            _ => {
+                errors.push("`application_id` must be the bot's application ID".to_owned());
+                None
+            }
+        },
+        None => {
+            errors.push(get_missing_error("application_id"));
+            None
+        }
+    };
+
+    let default_prefix = values.get("default_prefix").cloned();
+
+    match default_prefix.as_deref() {
+        Some(prefix) => {
+            if let Err(reason) = validate_prefix(prefix) {
+                errors.push(format!("`default_prefix` is invalid: {}", reason));
+            }
+        }
+        None => errors.push(get_missing_error("default_prefix")),
+    }
+
+    match db_connection.as_deref() {
+        Some(url)
+            if !url.starts_with("postgres://")
+                && !url.starts_with("postgresql://")
+                && !is_sqlite_url(url) =>
+        {
+            errors.push("`db_connection` must be a postgres:// or sqlite:// URL".to_owned())
+        }
+        Some(_) => {}
+        None => errors.push(get_missing_error("db_connection")),
+    }
+
+    let shard_count = match values.get("shard_count") {
+        Some(value) => match value.trim().parse::<u64>() {
+            Ok(count) if count > 0 => Some(count),
+            _ => {
+                errors.push("`shard_count` must be a number greater than 0".to_owned());
+                None
+            }
+        },
+        None => None,
+    };
+
+    let shard_range = match values.get("shard_range") {
+        Some(value) => match parse_shard_range(value) {
+            Some(range) => Some(range),
+            None => {
+                errors.push("`shard_range` must be a range of shards, like 0-3".to_owned());
+                None
+            }
+        },
+        None => None,
+    };
+
+    // A process can only own part of the shards if every process agrees on the total
+    if let Some([_, last]) = shard_range {
+        match shard_count {
+            Some(count) if last >= count => errors.push(format!(
+                "`shard_range` can't go past the last shard ({})",
+                count - 1
+            )),
+            Some(_) => {}
+            None if !values.contains_key("shard_count") => {
+                errors.push("`shard_range` needs `shard_count` to be set".to_owned())
+            }
+            None => {}
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(ConfigError { errors });
+    }
+
+    Ok(Credentials {
+        bot_token: bot_token.unwrap(),
+        application_id: application_id.unwrap(),
+        default_prefix: default_prefix.unwrap(),
+        db_connection: db_connection.unwrap(),
+        tenor_key,
+        mal_key,
+        shard_count,
+        shard_range,
+    })
+}
+
+// Ranges are inclusive, and a single shard can be written as just its number
+fn parse_shard_range(value: &str) -> Option<[u64; 2]> {
+    let (first, last) = match value.split_once('-') {
+        Some((first, last)) => (first.trim().parse().ok()?, last.trim().parse().ok()?),
+        None => {
+            let shard = value.trim().parse().ok()?;
+            (shard, shard)
+        }
+    };
+
+    (first <= last).then_some([first, last])
+}
+
+fn get_valid_keys() -> Vec<String> {
+    KEYS.iter()
+        .map(|x| x.to_string())
+        .chain(SECRET_KEYS.iter().map(|x| format!("{}_file", x)))
+        .collect()
+}
+
+fn get_missing_error(key: &str) -> String {
+    format!(
+        "`{}` is required. Set it in the config file or with {}{}",
+        key,
+        ENV_PREFIX,
+        key.to_uppercase()
+    )
+}
+
+fn read_file(path: &str, errors: &mut Vec<String>) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            errors.push(format!("Couldn't read the config file `{}`: {}", path, e));
+            return values;
+        }
+    };
+
+    let is_toml = Path::new(path)
+        .extension()
+        .is_some_and(|x| x.eq_ignore_ascii_case("toml"));
+
+    // TOML tables are converted to JSON so both formats are checked the same way
+    let parsed = if is_toml {
+        toml::from_str::<toml::Value>(&content)
+            .map_err(|e| e.to_string())
+            .and_then(|x| serde_json::to_value(x).map_err(|e| e.to_string()))
+    } else {
+        serde_json::from_str::<Value>(&content).map_err(|e| e.to_string())
+    };
+
+    let table = match parsed {
+        Ok(Value::Object(table)) => table,
+        Ok(_) => {
+            errors.push(format!(
+                "The config file `{}` must be a table of keys",
+                path
+            ));
+            return values;
+        }
+        Err(e) => {
+            errors.push(format!("Couldn't parse the config file `{}`: {}", path, e));
+            return values;
+        }
+    };
+
+    let valid_keys = get_valid_keys();
+
+    for (key, value) in table {
+        if !valid_keys.contains(&key) {
+            errors.push(format!("`{}` isn't a config key", key));
+            continue;
+        }
+
+        match value {
+            Value::String(value) => {
+                values.insert(key, value);
+            }
+            Value::Number(value) => {
+                values.insert(key, value.to_string());
+            }
+            _ => errors.push(format!("`{}` must be a string or a number", key)),
+        }
+    }
+
+    values
+}
+
+// A value set directly always wins over one read from a file
+fn get_secret(
+    values: &HashMap<String, String>,
+    key: &str,
+    errors: &mut Vec<String>,
+) -> Option<String> {
+    if let Some(value) = values.get(key) {
+        return Some(value.to_owned());
+    }
+
+    let file_key = format!("{}_file", key);
+
+    let path = match values.get(&file_key) {
+        Some(path) => path.to_owned(),
+        None => {
+            let credential = env::var("CREDENTIALS_DIRECTORY")
+                .ok()
+                .map(|dir| Path::new(&dir).join(key))
+                .filter(|x| x.is_file())?;
 
-    let info: Credentials = serde_json::from_reader(reader).unwrap();
+            credential.to_string_lossy().into_owned()
+        }
+    };
 
-    Ok(info)
+    match fs::read_to_string(&path) {
+        Ok(secret) if !secret.trim().is_empty() => Some(secret.trim().to_owned()),
+        Ok(_) => {
+            errors.push(format!("The secret file `{}` for `{}` is empty", path, key));
+            None
+        }
+        Err(e) => {
+            errors.push(format!(
+                "Couldn't read the secret file `{}` for `{}`: {}",
+                path, key, e
+            ));
+            None
+        }
+    }
 }
@@ -0,0 +1,423 @@
+use serde::Deserialize;
+use serenity::{
+    builder::CreateEmbed,
+    client::Context,
+    framework::standard::CommandResult,
+    model::{
+        channel::Message,
+        id::{ChannelId, GuildId, RoleId},
+        Permissions,
+    },
+    prelude::Mentionable,
+};
+use std::{fmt, str::FromStr};
+
+use crate::{
+    helpers::{
+        locale,
+        templates::{Template, TemplateData},
+    },
+    repositories::Repository,
+    structures::errors::{JesterError, PermissionType},
+};
+
+// Discord's limits for embeds
+const TITLE_LIMIT: usize = 256;
+const FIELD_NAME_LIMIT: usize = 256;
+const FIELD_VALUE_LIMIT: usize = 1024;
+const FOOTER_LIMIT: usize = 2048;
+const MAX_FIELDS: usize = 25;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CustomKind {
+    Text,
+    Embed,
+    Image,
+}
+
+impl fmt::Display for CustomKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CustomKind::Text => write!(f, "text"),
+            CustomKind::Embed => write!(f, "embed"),
+            CustomKind::Image => write!(f, "image"),
+        }
+    }
+}
+
+impl FromStr for CustomKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(CustomKind::Text),
+            "embed" => Ok(CustomKind::Embed),
+            "image" => Ok(CustomKind::Image),
+            _ => Err(format!("{} isn't a custom command type!", s)),
+        }
+    }
+}
+
+pub struct CustomCommand {
+    pub name: String,
+    pub content: String,
+    pub kind: CustomKind,
+    pub embed: Option<String>,
+    pub image_url: Option<String>,
+    pub reply: bool,
+}
+
+/*
+ * Embeds are stored as a flat JSON object to keep them easy to write in Discord
+ * Every text value can use the same placeholders as a text command
+ */
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmbedTemplate {
+    title: Option<String>,
+    description: Option<String>,
+    url: Option<String>,
+    color: Option<u32>,
+    #[serde(default)]
+    fields: Vec<EmbedFieldTemplate>,
+    image: Option<String>,
+    thumbnail: Option<String>,
+    footer: Option<String>,
+    author: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EmbedFieldTemplate {
+    name: String,
+    value: String,
+    #[serde(default)]
+    inline: bool,
+}
+
+impl EmbedTemplate {
+    pub fn parse(json: &str) -> Result<EmbedTemplate, String> {
+        let embed: EmbedTemplate = serde_json::from_str(json)
+            .map_err(|e| format!("The embed JSON is invalid! ({})", e))?;
+
+        if embed.title.is_none()
+            && embed.description.is_none()
+            && embed.fields.is_empty()
+            && embed.image.is_none()
+        {
+            return Err(
+                "The embed needs at least a title, description, field, or image!".to_owned(),
+            );
+        }
+
+        if embed.fields.len() > MAX_FIELDS {
+            return Err(format!("Embeds can only have up to {} fields!", MAX_FIELDS));
+        }
+
+        let texts = [
+            &embed.title,
+            &embed.description,
+            &embed.footer,
+            &embed.author,
+        ];
+
+        for text in texts.iter().filter_map(|x| x.as_ref()) {
+            Template::parse(text)?;
+        }
+
+        for field in &embed.fields {
+            Template::parse(&field.name)?;
+            Template::parse(&field.value)?;
+        }
+
+        let urls = [&embed.url, &embed.image, &embed.thumbnail];
+
+        for url in urls.iter().filter_map(|x| x.as_ref()) {
+            validate_url(url)?;
+        }
+
+        Ok(embed)
+    }
+
+    pub fn render(&self, data: &TemplateData) -> CreateEmbed {
+        let mut eb = CreateEmbed::default();
+
+        if let Some(title) = &self.title {
+            eb.title(render_text(title, data, TITLE_LIMIT));
+        }
+
+        if let Some(description) = &self.description {
+            eb.description(render_text(description, data, usize::MAX));
+        }
+
+        if let Some(url) = &self.url {
+            eb.url(url);
+        }
+
+        if let Some(color) = self.color {
+            eb.color(color);
+        }
+
+        for field in &self.fields {
+            eb.field(
+                render_text(&field.name, data, FIELD_NAME_LIMIT),
+                render_text(&field.value, data, FIELD_VALUE_LIMIT),
+                field.inline,
+            );
+        }
+
+        if let Some(image) = &self.image {
+            eb.image(image);
+        }
+
+        if let Some(thumbnail) = &self.thumbnail {
+            eb.thumbnail(thumbnail);
+        }
+
+        if let Some(footer) = &self.footer {
+            eb.footer(|f| f.text(render_text(footer, data, FOOTER_LIMIT)));
+        }
+
+        if let Some(author) = &self.author {
+            eb.author(|a| a.name(render_text(author, data, TITLE_LIMIT)));
+        }
+
+        eb
+    }
+}
+
+// Embed values can't be empty, so an empty render becomes a placeholder character
+fn render_text(text: &str, data: &TemplateData, limit: usize) -> String {
+    let output = Template::parse_or_literal(text).render(data);
+
+    if output.trim().is_empty() {
+        "\u{200b}".to_owned()
+    } else {
+        output.chars().take(limit).collect()
+    }
+}
+
+pub fn validate_url(url: &str) -> Result<(), String> {
+    if url.starts_with("https://") || url.starts_with("http://") {
+        Ok(())
+    } else {
+        Err(format!("`{}` isn't a valid link!", url))
+    }
+}
+
+/*
+ * Renders the message content and embed for a custom command
+ * Image commands send the image in an embed with the content as a caption
+ */
+pub fn build_response(
+    command: &CustomCommand,
+    data: &TemplateData,
+) -> (String, Option<CreateEmbed>) {
+    let content = Template::parse_or_literal(&command.content).render(data);
+
+    let embed = match command.kind {
+        CustomKind::Text => None,
+        CustomKind::Embed => command
+            .embed
+            .as_deref()
+            .and_then(|json| EmbedTemplate::parse(json).ok())
+            .map(|embed| embed.render(data)),
+        CustomKind::Image => command.image_url.as_ref().map(|url| {
+            let mut eb = CreateEmbed::default();
+            eb.image(url);
+
+            eb
+        }),
+    };
+
+    (content, embed)
+}
+
+// Aliases are resolved to the real command
+pub async fn fetch_custom_command(
+    repository: &dyn Repository,
+    guild_id: GuildId,
+    name: &str,
+) -> CommandResult<Option<CustomCommand>> {
+    let name = match repository.resolve_name(guild_id, name).await? {
+        Some(real_name) => real_name,
+        None => name.to_owned(),
+    };
+
+    let cmd_data = match repository.fetch_command(guild_id, &name).await? {
+        Some(cmd_data) => cmd_data,
+        None => return Ok(None),
+    };
+
+    Ok(Some(CustomCommand {
+        name: cmd_data.name,
+        content: cmd_data.content.unwrap_or_default(),
+        kind: cmd_data.kind.parse()?,
+        embed: cmd_data.embed,
+        image_url: cmd_data.image_url,
+        reply: cmd_data.reply,
+    }))
+}
+
+/*
+ * Requirements limit who can run a custom command and where
+ * A user needs one of the roles and all of the permissions
+ * The command can run in any of the channels
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Requirement {
+    Role(RoleId),
+    Permission(Permissions),
+    Channel(ChannelId),
+}
+
+impl Requirement {
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            Requirement::Role(_) => "role",
+            Requirement::Permission(_) => "permission",
+            Requirement::Channel(_) => "channel",
+        }
+    }
+
+    pub fn value(&self) -> i64 {
+        match *self {
+            Requirement::Role(role_id) => role_id.0 as i64,
+            Requirement::Permission(permission) => permission.bits() as i64,
+            Requirement::Channel(channel_id) => channel_id.0 as i64,
+        }
+    }
+
+    pub fn from_row(kind: &str, value: i64) -> Option<Requirement> {
+        match kind {
+            "role" => Some(Requirement::Role(RoleId(value as u64))),
+            "permission" => Some(Requirement::Permission(Permissions::from_bits_truncate(
+                value as u64,
+            ))),
+            "channel" => Some(Requirement::Channel(ChannelId(value as u64))),
+            _ => None,
+        }
+    }
+}
+
+// Roles are shown by name so nobody gets pinged
+pub fn format_requirement(ctx: &Context, requirement: &Requirement) -> String {
+    match *requirement {
+        Requirement::Role(role_id) => format!("`{}`", get_role_name(ctx, role_id)),
+        Requirement::Permission(permission) => format!("`{}`", get_permission_names(permission)),
+        Requirement::Channel(channel_id) => channel_id.mention().to_string(),
+    }
+}
+
+fn get_role_name(ctx: &Context, role_id: RoleId) -> String {
+    role_id
+        .to_role_cached(ctx)
+        .map(|role| role.name)
+        .unwrap_or_else(|| role_id.0.to_string())
+}
+
+// Accepts names like "manage messages", "manage_messages", or "MANAGE_MESSAGES"
+pub fn parse_permission(name: &str) -> Option<Permissions> {
+    let name = name.to_lowercase().replace('_', " ");
+
+    (0..64)
+        .map(|bit| Permissions::from_bits_truncate(1 << bit))
+        .filter(|permission| !permission.is_empty())
+        .find(|permission| {
+            permission
+                .get_permission_names()
+                .iter()
+                .any(|x| x.to_lowercase() == name)
+        })
+}
+
+fn get_permission_names(permissions: Permissions) -> String {
+    permissions
+        .get_permission_names()
+        .iter()
+        .map(|x| x.to_lowercase())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/*
+ * Returns a permission error message if the user can't run the command here
+ * Administrators can always run the command, but only in the allowed channels
+ */
+pub async fn check_requirements(
+    ctx: &Context,
+    msg: &Message,
+    repository: &dyn Repository,
+    guild_id: GuildId,
+    name: &str,
+) -> CommandResult<Option<String>> {
+    let requirements = repository.fetch_requirements(guild_id, name).await?;
+
+    if requirements.is_empty() {
+        return Ok(None);
+    }
+
+    let locale = locale::get_guild_locale(ctx, Some(guild_id)).await;
+
+    let mut roles = Vec::new();
+    let mut channels = Vec::new();
+    let mut needed_permissions = Permissions::empty();
+
+    for requirement in requirements {
+        match requirement {
+            Requirement::Role(role_id) => roles.push(role_id),
+            Requirement::Permission(permission) => needed_permissions |= permission,
+            Requirement::Channel(channel_id) => channels.push(channel_id),
+        }
+    }
+
+    if !channels.is_empty() && !channels.contains(&msg.channel_id) {
+        let channel_list = channels
+            .iter()
+            .map(|x| x.mention().to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        return Ok(Some(
+            JesterError::PermissionError(PermissionType::Channel(&channel_list)).localized(&locale),
+        ));
+    }
+
+    let permissions = match msg.channel(ctx).await?.guild() {
+        Some(channel) => channel.permissions_for_user(ctx, msg.author.id)?,
+        None => Permissions::empty(),
+    };
+
+    if permissions.administrator() {
+        return Ok(None);
+    }
+
+    let missing_permissions = needed_permissions - permissions;
+
+    if !missing_permissions.is_empty() {
+        let permission_names = get_permission_names(missing_permissions);
+
+        return Ok(Some(
+            JesterError::PermissionError(PermissionType::UserPerm(&permission_names))
+                .localized(&locale),
+        ));
+    }
+
+    if !roles.is_empty() {
+        let member = msg.member(ctx).await?;
+
+        if !roles.iter().any(|x| member.roles.contains(x)) {
+            let role_list = roles
+                .iter()
+                .map(|x| format!("`{}`", get_role_name(ctx, *x)))
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            return Ok(Some(
+                JesterError::PermissionError(PermissionType::Role(&role_list)).localized(&locale),
+            ));
+        }
+    }
+
+    Ok(None)
+}
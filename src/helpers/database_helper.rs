@@ -1,28 +1,97 @@
-use dashmap::DashMap;
-use serenity::{framework::standard::CommandResult, model::id::GuildId};
-use sqlx::postgres::{PgPool, PgPoolOptions};
+use serenity::framework::standard::CommandResult;
+use sqlx::{
+    any::{AnyConnectOptions, AnyKind, AnyPool, AnyPoolOptions},
+    migrate::Migrator,
+    postgres::PgConnectOptions,
+    sqlite::SqliteConnectOptions,
+};
+use std::str::FromStr;
 
-pub async fn obtain_db_pool(db_connection: String) -> CommandResult<PgPool> {
-    let pool = PgPoolOptions::new()
+/*
+ * Every migration is compiled into the binary
+ * The SQLite migrations mirror the Postgres ones with the same versions
+ */
+static POSTGRES_MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations_sqlite");
+
+/*
+ * The backend is picked from the connection URL
+ * postgres:// uses Postgres and sqlite:// uses a database file, which is created if it doesn't exist
+ */
+pub async fn obtain_db_pool(db_connection: String) -> CommandResult<AnyPool> {
+    let options: AnyConnectOptions = if is_sqlite_url(&db_connection) {
+        SqliteConnectOptions::from_str(&db_connection)?
+            .create_if_missing(true)
+            .into()
+    } else {
+        PgConnectOptions::from_str(&db_connection)?.into()
+    };
+
+    let pool = AnyPoolOptions::new()
         .max_connections(10)
-        .connect(&db_connection)
+        .connect_with(options)
         .await?;
 
     Ok(pool)
 }
 
-pub async fn fetch_prefixes(pool: &PgPool) -> CommandResult<DashMap<GuildId, String>> {
-    let prefixes: DashMap<GuildId, String> = DashMap::new();
+pub fn is_sqlite_url(db_connection: &str) -> bool {
+    db_connection.starts_with("sqlite:")
+}
+
+/*
+ * Applies any migrations the database doesn't have yet
+ * A database migrated by a newer version of the bot is refused since old queries could break it
+ */
+pub async fn run_migrations(pool: &AnyPool) -> CommandResult {
+    let (migrator, table_query) = match pool.any_kind() {
+        AnyKind::Sqlite => (
+            &SQLITE_MIGRATOR,
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+        ),
+        _ => (
+            &POSTGRES_MIGRATOR,
+            "SELECT COUNT(*) FROM pg_tables WHERE tablename = '_sqlx_migrations'",
+        ),
+    };
+
+    let latest_version = migrator.iter().map(|x| x.version).max().unwrap_or_default();
+
+    let table_count = sqlx::query_scalar::<_, i64>(table_query)
+        .fetch_one(pool)
+        .await?;
 
-    let cursor = sqlx::query!("SELECT guild_id, prefix FROM guild_info")
-        .fetch_all(pool)
+    if table_count > 0 {
+        let db_version = sqlx::query_scalar::<_, Option<i64>>(
+            "SELECT MAX(version) FROM _sqlx_migrations WHERE success",
+        )
+        .fetch_one(pool)
         .await?;
 
-    for i in cursor {
-        if let Some(prefix) = i.prefix {
-            prefixes.insert(GuildId::from(i.guild_id as u64), prefix);
+        if let Some(db_version) = db_version.filter(|x| *x > latest_version) {
+            return Err(format!(
+                "The database schema ({}) is newer than this version of the bot ({})! Please update the bot",
+                db_version, latest_version
+            )
+            .into());
         }
     }
 
-    Ok(prefixes)
+    migrator.run(pool).await?;
+
+    Ok(())
+}
+
+// Every test gets its own migrated SQLite file, so tests can run in parallel
+#[cfg(test)]
+pub async fn obtain_test_pool(name: &str) -> AnyPool {
+    let path = std::env::temp_dir().join(format!("courtjester-{}-{}.db", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let pool = obtain_db_pool(format!("sqlite://{}", path.display()))
+        .await
+        .unwrap();
+    run_migrations(&pool).await.unwrap();
+
+    pool
 }
@@ -74,3 +74,27 @@ pub fn get_anime_embed(japan_result: &JapanResult) -> CreateEmbed {
 
     eb
 }
+
+/*
+ * Only the incident ID is shown to users
+ * The actual error is stored and can be looked up with the incident command
+ */
+pub fn get_error_embed(command_name: &str, incident_id: Option<&str>) -> CreateEmbed {
+    let part_1 = "Looks like the bot encountered an error! \n";
+    let part_2 = "Please use the `support` command and send the incident ID to the support server!";
+    let error_string = format!("{}{}", part_1, part_2);
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0xff69b4);
+    eb.title("Aw Snap!");
+    eb.description(error_string);
+    eb.field("Command Name", command_name, false);
+    eb.field(
+        "Incident ID",
+        format!("`{}`", incident_id.unwrap_or("Unavailable")),
+        false,
+    );
+
+    eb
+}
@@ -0,0 +1,109 @@
+use dashmap::DashMap;
+use serenity::{client::Context, framework::standard::CommandResult, model::id::GuildId};
+use sqlx::postgres::PgListener;
+use std::{sync::Arc, time::Duration};
+use tokio::time::sleep;
+
+use crate::{
+    helpers::automod::{self, AutomodConfig},
+    repositories::{CommandRules, SenderChannels},
+    GuildSettingsMap, RepositoryContainer,
+};
+
+// Set by the triggers in the settings_notify migration
+const SETTINGS_CHANNEL: &str = "guild_settings";
+
+/*
+ * Settings that are read on every message or reaction
+ * They're loaded once per guild and cached until they change
+ */
+#[derive(Clone, Debug, Default)]
+pub struct GuildSettings {
+    // Empty if the guild uses the default prefix
+    pub prefixes: Vec<String>,
+    pub locale: Option<String>,
+    pub starboard_threshold: Option<i32>,
+    pub sender_channels: Option<SenderChannels>,
+    // None if the guild has no automod rules
+    pub automod: Option<Arc<AutomodConfig>>,
+    // Checked before every command, along with cooldown overrides
+    pub command_rules: Arc<CommandRules>,
+}
+
+pub async fn get_guild_settings(ctx: &Context, guild_id: GuildId) -> CommandResult<GuildSettings> {
+    let (repository, settings_map) = {
+        let data = ctx.data.read().await;
+
+        let repository = data.get::<RepositoryContainer>().cloned().unwrap();
+        let settings_map = data.get::<GuildSettingsMap>().cloned().unwrap();
+
+        (repository, settings_map)
+    };
+
+    if let Some(settings) = settings_map.get(&guild_id) {
+        return Ok(settings.value().clone());
+    }
+
+    let settings = GuildSettings {
+        prefixes: repository.fetch_prefixes(guild_id).await?,
+        locale: repository.fetch_locale(guild_id).await?,
+        starboard_threshold: repository.fetch_starboard_threshold(guild_id).await?,
+        sender_channels: repository.fetch_sender_channels(guild_id).await?,
+        automod: automod::build_config(repository.fetch_automod_settings(guild_id).await?),
+        command_rules: Arc::new(repository.fetch_command_rules(guild_id).await?),
+    };
+
+    settings_map.insert(guild_id, settings.clone());
+
+    Ok(settings)
+}
+
+// Call this after any write to the guild's settings so the next read loads them again
+pub async fn invalidate_guild_settings(ctx: &Context, guild_id: GuildId) {
+    let settings_map = ctx
+        .data
+        .read()
+        .await
+        .get::<GuildSettingsMap>()
+        .cloned()
+        .unwrap();
+
+    settings_map.remove(&guild_id);
+}
+
+/*
+ * Postgres sends a guild's ID whenever its settings change, even from another bot process or a manual edit
+ * Notifications sent while the connection is down are lost, so the whole cache is cleared when it drops
+ */
+pub async fn listen_for_changes(
+    db_connection: String,
+    settings_map: Arc<DashMap<GuildId, GuildSettings>>,
+) -> CommandResult {
+    let mut listener = PgListener::connect(&db_connection).await?;
+    listener.listen(SETTINGS_CHANNEL).await?;
+
+    loop {
+        // The listener reconnects on the next call after a lost connection
+        match listener.try_recv().await {
+            Ok(Some(notification)) => match notification.payload().parse::<u64>() {
+                Ok(guild_id) => {
+                    settings_map.remove(&GuildId(guild_id));
+                }
+                Err(_) => eprintln!(
+                    "Invalid guild settings notification: {}",
+                    notification.payload()
+                ),
+            },
+            Ok(None) => {
+                eprintln!("Lost the guild settings listener connection! Reconnecting...");
+                settings_map.clear();
+            }
+            Err(e) => {
+                eprintln!("Error in the guild settings listener! {}", e);
+                settings_map.clear();
+
+                sleep(Duration::from_secs(5)).await;
+            }
+        }
+    }
+}
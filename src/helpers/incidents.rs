@@ -0,0 +1,138 @@
+use rand::{prelude::StdRng, Rng, SeedableRng};
+use serenity::{
+    client::Context,
+    framework::standard::{CommandError, CommandResult},
+    model::id::{ChannelId, GuildId, UserId},
+};
+use sqlx::{AnyPool, FromRow};
+
+use crate::{helpers::command_utils, ConnectionPool};
+
+// Similar looking characters are left out so IDs are easy to read back
+const ID_CHARACTERS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const ID_LENGTH: usize = 8;
+
+pub struct NewIncident<'a> {
+    pub command: &'a str,
+    pub args: &'a str,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    pub user_id: UserId,
+    pub error: &'a CommandError,
+}
+
+pub struct Incident {
+    pub id: String,
+    pub command: String,
+    pub args: String,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    pub user_id: UserId,
+    pub error: String,
+    pub source_chain: String,
+    pub created_at: i64,
+}
+
+fn generate_incident_id() -> String {
+    let mut rng = StdRng::from_entropy();
+
+    (0..ID_LENGTH)
+        .map(|_| ID_CHARACTERS[rng.gen_range(0..ID_CHARACTERS.len())] as char)
+        .collect()
+}
+
+/*
+ * Walks through every error that caused the command error
+ * The debug output comes first since it usually has the error's type
+ */
+pub fn get_source_chain(error: &CommandError) -> String {
+    let mut chain = vec![format!("0: {:?}", error)];
+    let mut source = error.source();
+
+    while let Some(cause) = source {
+        chain.push(format!("{}: {}", chain.len(), cause));
+        source = cause.source();
+    }
+
+    chain.join("\n")
+}
+
+/*
+ * Stores a failed command and returns the incident ID
+ * On the rare chance of an ID collision, generate a new one
+ */
+pub async fn record_incident(ctx: &Context, incident: NewIncident<'_>) -> CommandResult<String> {
+    let pool = ctx
+        .data
+        .read()
+        .await
+        .get::<ConnectionPool>()
+        .cloned()
+        .unwrap();
+
+    let error = incident.error.to_string();
+    let source_chain = get_source_chain(incident.error);
+    let created_at = command_utils::get_unix_time();
+
+    for _ in 0..3 {
+        let incident_id = generate_incident_id();
+
+        let result = sqlx::query(
+            "INSERT INTO incidents(id, command, args, guild_id, channel_id, user_id, error, source_chain, created_at)
+                VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT DO NOTHING",
+        )
+        .bind(&incident_id)
+        .bind(incident.command)
+        .bind(incident.args)
+        .bind(incident.guild_id.map(|x| x.0 as i64))
+        .bind(incident.channel_id.0 as i64)
+        .bind(incident.user_id.0 as i64)
+        .bind(&error)
+        .bind(&source_chain)
+        .bind(created_at)
+        .execute(&pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            return Ok(incident_id);
+        }
+    }
+
+    Err("Could not generate a unique incident ID".into())
+}
+
+#[derive(FromRow)]
+struct IncidentRow {
+    id: String,
+    command: String,
+    args: String,
+    guild_id: Option<i64>,
+    channel_id: i64,
+    user_id: i64,
+    error: String,
+    source_chain: String,
+    created_at: i64,
+}
+
+pub async fn fetch_incident(pool: &AnyPool, incident_id: &str) -> CommandResult<Option<Incident>> {
+    let incident_data = sqlx::query_as::<_, IncidentRow>(
+        "SELECT id, command, args, guild_id, channel_id, user_id, error, source_chain, created_at
+            FROM incidents WHERE id = $1",
+    )
+    .bind(incident_id.to_uppercase())
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(incident_data.map(|x| Incident {
+        id: x.id,
+        command: x.command,
+        args: x.args,
+        guild_id: x.guild_id.map(|id| GuildId::from(id as u64)),
+        channel_id: ChannelId::from(x.channel_id as u64),
+        user_id: UserId::from(x.user_id as u64),
+        error: x.error,
+        source_chain: x.source_chain,
+        created_at: x.created_at,
+    }))
+}
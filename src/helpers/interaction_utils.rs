@@ -0,0 +1,90 @@
+use serenity::model::{
+    application::{
+        command::CommandOptionType,
+        interaction::application_command::{CommandDataOption, CommandDataOptionValue},
+    },
+    channel::Attachment,
+    id::{ChannelId, RoleId},
+    user::User,
+};
+
+// Finds the resolved value of a slash command option by name
+pub fn get_option<'a>(
+    options: &'a [CommandDataOption],
+    name: &str,
+) -> Option<&'a CommandDataOptionValue> {
+    options
+        .iter()
+        .find(|option| option.name == name)
+        .and_then(|option| option.resolved.as_ref())
+}
+
+pub fn get_string_option(options: &[CommandDataOption], name: &str) -> Option<String> {
+    match get_option(options, name) {
+        Some(CommandDataOptionValue::String(value)) => Some(value.to_owned()),
+        _ => None,
+    }
+}
+
+pub fn get_integer_option(options: &[CommandDataOption], name: &str) -> Option<i64> {
+    match get_option(options, name) {
+        Some(CommandDataOptionValue::Integer(value)) => Some(*value),
+        _ => None,
+    }
+}
+
+pub fn get_bool_option(options: &[CommandDataOption], name: &str) -> Option<bool> {
+    match get_option(options, name) {
+        Some(CommandDataOptionValue::Boolean(value)) => Some(*value),
+        _ => None,
+    }
+}
+
+pub fn get_user_option(options: &[CommandDataOption], name: &str) -> Option<User> {
+    match get_option(options, name) {
+        Some(CommandDataOptionValue::User(user, _)) => Some(user.to_owned()),
+        _ => None,
+    }
+}
+
+pub fn get_channel_option(options: &[CommandDataOption], name: &str) -> Option<ChannelId> {
+    match get_option(options, name) {
+        Some(CommandDataOptionValue::Channel(channel)) => Some(channel.id),
+        _ => None,
+    }
+}
+
+pub fn get_role_option(options: &[CommandDataOption], name: &str) -> Option<RoleId> {
+    match get_option(options, name) {
+        Some(CommandDataOptionValue::Role(role)) => Some(role.id),
+        _ => None,
+    }
+}
+
+pub fn get_attachment_option(options: &[CommandDataOption], name: &str) -> Option<Attachment> {
+    match get_option(options, name) {
+        Some(CommandDataOptionValue::Attachment(attachment)) => Some(attachment.to_owned()),
+        _ => None,
+    }
+}
+
+// Slash commands with subcommands store the chosen subcommand as the first option
+pub fn get_subcommand(options: &[CommandDataOption]) -> Option<&CommandDataOption> {
+    options
+        .iter()
+        .find(|option| option.kind == CommandOptionType::SubCommand)
+}
+
+// Formats the given options like `name: value`, used to store what a slash command was called with
+pub fn format_options(options: &[CommandDataOption]) -> String {
+    options
+        .iter()
+        .map(|option| match &option.value {
+            Some(value) => format!("{}: {}", option.name, value),
+            None => format!("{} {}", option.name, format_options(&option.options))
+                .trim_end()
+                .to_owned(),
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -0,0 +1,126 @@
+use serde_json::Value;
+use serenity::{builder::CreateEmbed, client::Context, model::id::GuildId};
+use std::{collections::HashMap, sync::OnceLock};
+
+use crate::helpers::guild_settings;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+/*
+ * Every catalog is compiled into the binary
+ * Keys missing from a catalog fall back to English, then to the key itself
+ */
+const CATALOGS: [(&str, &str); 2] = [
+    ("en", include_str!("../../locales/en.json")),
+    ("es", include_str!("../../locales/es.json")),
+];
+
+static LOCALES: OnceLock<HashMap<&'static str, HashMap<String, Value>>> = OnceLock::new();
+
+fn get_catalogs() -> &'static HashMap<&'static str, HashMap<String, Value>> {
+    LOCALES.get_or_init(|| {
+        CATALOGS
+            .iter()
+            .map(|(code, catalog)| {
+                let entries = serde_json::from_str(catalog)
+                    .unwrap_or_else(|e| panic!("Locale catalog {} is invalid! {}", code, e));
+
+                (*code, entries)
+            })
+            .collect()
+    })
+}
+
+fn get_value(locale: &str, key: &str) -> Option<&'static Value> {
+    let catalogs = get_catalogs();
+
+    catalogs
+        .get(locale)
+        .and_then(|catalog| catalog.get(key))
+        .or_else(|| catalogs[DEFAULT_LOCALE].get(key))
+}
+
+pub fn get_text(locale: &str, key: &str) -> String {
+    match get_value(locale, key) {
+        Some(Value::String(text)) => text.to_owned(),
+        _ => key.to_owned(),
+    }
+}
+
+// Named arguments are written as {name} in the catalogs
+pub fn get_formatted(locale: &str, key: &str, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(get_text(locale, key), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+pub fn get_list(locale: &str, key: &str) -> Vec<String> {
+    match get_value(locale, key) {
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(|x| x.as_str().map(|x| x.to_owned()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+pub fn is_supported(locale: &str) -> bool {
+    CATALOGS.iter().any(|(code, _)| *code == locale)
+}
+
+// Lists each locale as "code (name)"
+pub fn get_locale_list() -> String {
+    CATALOGS
+        .iter()
+        .map(|(code, _)| format!("`{}` ({})", code, get_text(code, "language.name")))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// DMs and guilds without a language use English
+pub async fn get_guild_locale(ctx: &Context, guild_id: Option<GuildId>) -> String {
+    let guild_id = match guild_id {
+        Some(guild_id) => guild_id,
+        None => return DEFAULT_LOCALE.to_owned(),
+    };
+
+    match guild_settings::get_guild_settings(ctx, guild_id).await {
+        Ok(settings) => settings.locale.unwrap_or_else(|| DEFAULT_LOCALE.to_owned()),
+        Err(e) => {
+            eprintln!(
+                "Error when loading the language of guild {}! {}",
+                guild_id.0, e
+            );
+            DEFAULT_LOCALE.to_owned()
+        }
+    }
+}
+
+/*
+ * Help embeds are stored as help.<category>.title, .description, and .commands
+ * The footer is optional since most categories don't have one
+ */
+pub fn get_help_embed(locale: &str, category: &str) -> CreateEmbed {
+    let key = format!("help.{}", category);
+    let footer_key = format!("{}.footer", key);
+
+    let mut eb = CreateEmbed::default();
+
+    eb.title(get_text(locale, &format!("{}.title", key)));
+    eb.description(get_text(locale, &format!("{}.description", key)));
+    eb.field(
+        get_text(locale, "help.commands"),
+        get_text(locale, &format!("{}.commands", key)),
+        false,
+    );
+
+    if get_value(locale, &footer_key).is_some() {
+        eb.footer(|f| {
+            f.text(get_text(locale, &footer_key));
+            f
+        });
+    }
+
+    eb
+}
@@ -1,10 +1,25 @@
+pub mod analytics;
+pub mod automod;
 pub mod botinfo;
+pub mod command_rules;
 pub mod command_utils;
+pub mod cooldowns;
 pub mod credentials_helper;
+pub mod custom_commands;
 pub mod database_helper;
 pub mod embed_store;
+pub mod guild_settings;
+pub mod incidents;
+pub mod interaction_utils;
+pub mod locale;
+pub mod mod_log;
+pub mod moderation;
 pub mod permissions_helper;
+pub mod polls;
 pub mod reaction_gifs;
+pub mod reminders;
+pub mod scheduler;
 pub mod start_loops;
+pub mod templates;
 pub mod textmod_helper;
 pub mod voice_utils;
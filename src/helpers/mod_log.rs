@@ -0,0 +1,443 @@
+use dashmap::DashMap;
+use serenity::{
+    builder::CreateEmbed,
+    client::Context,
+    framework::standard::CommandResult,
+    model::{
+        channel::Message,
+        event::MessageUpdateEvent,
+        guild::Member,
+        id::{ChannelId, GuildId, MessageId, RoleId, UserId},
+        prelude::Mentionable,
+        user::User,
+    },
+};
+use std::{collections::VecDeque, sync::Mutex};
+
+use crate::{
+    commands::moderation::get_case_embed, helpers::guild_settings, repositories::ModCase,
+    MessageCache,
+};
+
+// Messages kept in memory so deleted and edited content can be logged
+const MESSAGE_CACHE_SIZE: usize = 10000;
+
+// Discord's limits for embed fields and descriptions
+const FIELD_LIMIT: usize = 1024;
+const DESCRIPTION_LIMIT: usize = 4096;
+
+#[derive(Clone)]
+pub struct CachedMessage {
+    pub author_id: UserId,
+    pub content: String,
+    pub attachments: Vec<String>,
+}
+
+/*
+ * Holds the newest messages from guilds with a mod log
+ * Once it's full, the oldest message is dropped for every new one
+ */
+#[derive(Default)]
+pub struct BoundedMessageCache {
+    messages: DashMap<MessageId, CachedMessage>,
+    order: Mutex<VecDeque<MessageId>>,
+}
+
+impl BoundedMessageCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, message_id: MessageId, message: CachedMessage) {
+        self.messages.insert(message_id, message);
+
+        let mut order = self.order.lock().unwrap();
+        order.push_back(message_id);
+
+        while order.len() > MESSAGE_CACHE_SIZE {
+            if let Some(oldest_id) = order.pop_front() {
+                self.messages.remove(&oldest_id);
+            }
+        }
+    }
+
+    // Returns the old content if the message was cached
+    pub fn update(&self, message_id: MessageId, content: &str) -> Option<String> {
+        self.messages
+            .get_mut(&message_id)
+            .map(|mut message| std::mem::replace(&mut message.content, content.to_owned()))
+    }
+
+    pub fn remove(&self, message_id: MessageId) -> Option<CachedMessage> {
+        self.messages
+            .remove(&message_id)
+            .map(|(_, message)| message)
+    }
+}
+
+pub async fn get_mod_log_channel(ctx: &Context, guild_id: GuildId) -> Option<ChannelId> {
+    match guild_settings::get_guild_settings(ctx, guild_id).await {
+        Ok(settings) => settings.sender_channels.and_then(|x| x.mod_log),
+        Err(e) => {
+            eprintln!("Error when fetching the mod log channel! {}", e);
+
+            None
+        }
+    }
+}
+
+async fn send_log(ctx: &Context, channel_id: ChannelId, embed: CreateEmbed) -> CommandResult {
+    channel_id.send_message(ctx, |m| m.set_embed(embed)).await?;
+
+    Ok(())
+}
+
+// Cuts text to a number of characters, since embeds reject anything longer
+fn limit_text(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_owned();
+    }
+
+    format!("{}...", text.chars().take(limit - 3).collect::<String>())
+}
+
+fn format_content(content: &str, attachments: &[String]) -> String {
+    let mut text = content.to_owned();
+
+    for attachment in attachments {
+        text.push_str(&format!("\n[Attachment]({})", attachment));
+    }
+
+    if text.trim().is_empty() {
+        "No text".to_owned()
+    } else {
+        limit_text(&text, FIELD_LIMIT)
+    }
+}
+
+// Messages are only cached for guilds with a mod log, and the log channel itself is skipped
+pub async fn cache_message(ctx: &Context, msg: &Message) {
+    let guild_id = match msg.guild_id {
+        Some(guild_id) if !msg.author.bot => guild_id,
+        _ => return,
+    };
+
+    match get_mod_log_channel(ctx, guild_id).await {
+        Some(log_channel) if log_channel != msg.channel_id => {}
+        _ => return,
+    }
+
+    let message_cache = ctx
+        .data
+        .read()
+        .await
+        .get::<MessageCache>()
+        .cloned()
+        .unwrap();
+
+    message_cache.insert(
+        msg.id,
+        CachedMessage {
+            author_id: msg.author.id,
+            content: msg.content.clone(),
+            attachments: msg.attachments.iter().map(|x| x.url.clone()).collect(),
+        },
+    );
+}
+
+pub async fn log_message_edit(ctx: &Context, event: &MessageUpdateEvent) -> CommandResult {
+    // Embed and pin updates don't change the content
+    let (guild_id, new_content) = match (event.guild_id, event.content.as_ref()) {
+        (Some(guild_id), Some(new_content)) => (guild_id, new_content),
+        _ => return Ok(()),
+    };
+
+    let log_channel = match get_mod_log_channel(ctx, guild_id).await {
+        Some(log_channel) if log_channel != event.channel_id => log_channel,
+        _ => return Ok(()),
+    };
+
+    let message_cache = ctx
+        .data
+        .read()
+        .await
+        .get::<MessageCache>()
+        .cloned()
+        .unwrap();
+
+    // Only cached messages are logged, since bots aren't cached and the old content is needed
+    let old_content = match message_cache.update(event.id, new_content) {
+        Some(old_content) if &old_content != new_content => old_content,
+        _ => return Ok(()),
+    };
+
+    let author = event.author.as_ref().map_or_else(
+        || "Unknown".to_owned(),
+        |x| format!("{} ({})", x.mention(), x.tag()),
+    );
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0xf1c40f);
+    eb.title("Message edited");
+    eb.description(format!(
+        "In {} \n[Jump!]({})",
+        event.channel_id.mention(),
+        event.id.link(event.channel_id, Some(guild_id))
+    ));
+    eb.field("Author", author, false);
+    eb.field("Before", format_content(&old_content, &[]), false);
+    eb.field("After", format_content(new_content, &[]), false);
+    eb.footer(|f| {
+        f.text(format!("Message ID: {}", event.id.0));
+        f
+    });
+
+    send_log(ctx, log_channel, eb).await
+}
+
+pub async fn log_message_delete(
+    ctx: &Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    message_id: MessageId,
+) -> CommandResult {
+    let log_channel = match get_mod_log_channel(ctx, guild_id).await {
+        Some(log_channel) if log_channel != channel_id => log_channel,
+        _ => return Ok(()),
+    };
+
+    let message_cache = ctx
+        .data
+        .read()
+        .await
+        .get::<MessageCache>()
+        .cloned()
+        .unwrap();
+
+    // Messages from bots or from before the bot started can't be recovered
+    let message = match message_cache.remove(message_id) {
+        Some(message) => message,
+        None => return Ok(()),
+    };
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0xe74c3c);
+    eb.title("Message deleted");
+    eb.description(format!("In {}", channel_id.mention()));
+    eb.field("Author", message.author_id.mention(), false);
+    eb.field(
+        "Content",
+        format_content(&message.content, &message.attachments),
+        false,
+    );
+    eb.footer(|f| {
+        f.text(format!("Message ID: {}", message_id.0));
+        f
+    });
+
+    send_log(ctx, log_channel, eb).await
+}
+
+pub async fn log_bulk_delete(
+    ctx: &Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    message_ids: &[MessageId],
+) -> CommandResult {
+    let log_channel = match get_mod_log_channel(ctx, guild_id).await {
+        Some(log_channel) if log_channel != channel_id => log_channel,
+        _ => return Ok(()),
+    };
+
+    let message_cache = ctx
+        .data
+        .read()
+        .await
+        .get::<MessageCache>()
+        .cloned()
+        .unwrap();
+
+    let message_list = message_ids
+        .iter()
+        .filter_map(|x| message_cache.remove(*x))
+        .map(|x| {
+            format!(
+                "{}: {}",
+                x.author_id.mention(),
+                limit_text(&x.content.replace('\n', " "), 100)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0xe74c3c);
+    eb.title(format!("{} messages deleted", message_ids.len()));
+    eb.description(limit_text(
+        &format!("In {} \n\n{}", channel_id.mention(), message_list),
+        DESCRIPTION_LIMIT,
+    ));
+
+    send_log(ctx, log_channel, eb).await
+}
+
+pub async fn log_member_join(ctx: &Context, member: &Member) -> CommandResult {
+    let log_channel = match get_mod_log_channel(ctx, member.guild_id).await {
+        Some(log_channel) => log_channel,
+        None => return Ok(()),
+    };
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0x2ecc71);
+    eb.title("Member joined");
+    eb.thumbnail(member.user.face());
+    eb.description(format!("{} ({})", member.mention(), member.user.tag()));
+    eb.field(
+        "Account created",
+        format!("<t:{}:R>", member.user.created_at().unix_timestamp()),
+        false,
+    );
+    eb.footer(|f| {
+        f.text(format!("User ID: {}", member.user.id.0));
+        f
+    });
+
+    send_log(ctx, log_channel, eb).await
+}
+
+pub async fn log_member_leave(
+    ctx: &Context,
+    guild_id: GuildId,
+    user: &User,
+    member: Option<&Member>,
+) -> CommandResult {
+    let log_channel = match get_mod_log_channel(ctx, guild_id).await {
+        Some(log_channel) => log_channel,
+        None => return Ok(()),
+    };
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0xe67e22);
+    eb.title("Member left");
+    eb.thumbnail(user.face());
+    eb.description(format!("{} ({})", user.mention(), user.tag()));
+
+    if let Some(member) = member {
+        if let Some(joined_at) = member.joined_at {
+            eb.field(
+                "Joined",
+                format!("<t:{}:R>", joined_at.unix_timestamp()),
+                false,
+            );
+        }
+
+        if !member.roles.is_empty() {
+            eb.field("Roles", format_roles(&member.roles), false);
+        }
+    }
+
+    eb.footer(|f| {
+        f.text(format!("User ID: {}", user.id.0));
+        f
+    });
+
+    send_log(ctx, log_channel, eb).await
+}
+
+fn format_roles(roles: &[RoleId]) -> String {
+    let role_list = roles
+        .iter()
+        .map(|x| x.mention().to_string())
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    limit_text(&role_list, FIELD_LIMIT)
+}
+
+// Without the old member, there's nothing to compare against
+pub async fn log_member_update(ctx: &Context, old: Option<&Member>, new: &Member) -> CommandResult {
+    let old = match old {
+        Some(old) => old,
+        None => return Ok(()),
+    };
+
+    let added_roles = new
+        .roles
+        .iter()
+        .filter(|x| !old.roles.contains(x))
+        .copied()
+        .collect::<Vec<RoleId>>();
+    let removed_roles = old
+        .roles
+        .iter()
+        .filter(|x| !new.roles.contains(x))
+        .copied()
+        .collect::<Vec<RoleId>>();
+
+    if old.nick == new.nick && added_roles.is_empty() && removed_roles.is_empty() {
+        return Ok(());
+    }
+
+    let log_channel = match get_mod_log_channel(ctx, new.guild_id).await {
+        Some(log_channel) => log_channel,
+        None => return Ok(()),
+    };
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0x3498db);
+    eb.title("Member updated");
+    eb.description(format!("{} ({})", new.mention(), new.user.tag()));
+
+    if old.nick != new.nick {
+        eb.field(
+            "Nickname",
+            format!(
+                "{} -> {}",
+                old.nick.as_deref().unwrap_or("None"),
+                new.nick.as_deref().unwrap_or("None")
+            ),
+            false,
+        );
+    }
+
+    if !added_roles.is_empty() {
+        eb.field("Roles added", format_roles(&added_roles), false);
+    }
+
+    if !removed_roles.is_empty() {
+        eb.field("Roles removed", format_roles(&removed_roles), false);
+    }
+
+    eb.footer(|f| {
+        f.text(format!("User ID: {}", new.user.id.0));
+        f
+    });
+
+    send_log(ctx, log_channel, eb).await
+}
+
+// Actions from the bot's moderation commands, logged again when their reason is edited
+pub async fn log_case(ctx: &Context, case: &ModCase, reason_updated: bool) -> CommandResult {
+    let log_channel = match get_mod_log_channel(ctx, case.guild_id).await {
+        Some(log_channel) => log_channel,
+        None => return Ok(()),
+    };
+
+    let mut eb = get_case_embed(case);
+
+    if reason_updated {
+        eb.title(format!(
+            "Case #{} | {} (reason updated)",
+            case.case_number,
+            case.action.title()
+        ));
+    }
+
+    send_log(ctx, log_channel, eb).await
+}
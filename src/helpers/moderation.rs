@@ -0,0 +1,114 @@
+use serenity::{
+    client::Context,
+    framework::standard::CommandResult,
+    model::{guild::Member, permissions::Permissions},
+};
+use std::{fmt, str::FromStr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModAction {
+    Warn,
+    Kick,
+    Ban,
+    Unban,
+    Timeout,
+}
+
+impl ModAction {
+    pub fn title(&self) -> &'static str {
+        match *self {
+            ModAction::Warn => "Warn",
+            ModAction::Kick => "Kick",
+            ModAction::Ban => "Ban",
+            ModAction::Unban => "Unban",
+            ModAction::Timeout => "Timeout",
+        }
+    }
+
+    pub fn past_tense(&self) -> &'static str {
+        match *self {
+            ModAction::Warn => "warned",
+            ModAction::Kick => "kicked",
+            ModAction::Ban => "banned",
+            ModAction::Unban => "unbanned",
+            ModAction::Timeout => "timed out",
+        }
+    }
+
+    // The permission both the moderator and the bot need, along with its name for errors
+    pub fn permission(&self) -> (Permissions, &'static str) {
+        match *self {
+            ModAction::Warn | ModAction::Timeout => {
+                (Permissions::MODERATE_MEMBERS, "moderate members")
+            }
+            ModAction::Kick => (Permissions::KICK_MEMBERS, "kick members"),
+            ModAction::Ban | ModAction::Unban => (Permissions::BAN_MEMBERS, "ban members"),
+        }
+    }
+}
+
+// Why a member is out of reach of a moderator or the bot
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyError {
+    Administrator,
+    Moderator,
+    Bot,
+}
+
+impl fmt::Display for ModAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ModAction::Warn => write!(f, "warn"),
+            ModAction::Kick => write!(f, "kick"),
+            ModAction::Ban => write!(f, "ban"),
+            ModAction::Unban => write!(f, "unban"),
+            ModAction::Timeout => write!(f, "timeout"),
+        }
+    }
+}
+
+impl FromStr for ModAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "warn" => Ok(ModAction::Warn),
+            "kick" => Ok(ModAction::Kick),
+            "ban" => Ok(ModAction::Ban),
+            "unban" => Ok(ModAction::Unban),
+            "timeout" => Ok(ModAction::Timeout),
+            _ => Err(format!("{} isn't a moderation action!", s)),
+        }
+    }
+}
+
+/*
+ * The server owner can moderate anyone, and administrators can't be moderated
+ * Otherwise, the moderator and the bot both need a higher role than the user
+ */
+pub fn get_hierarchy_error(
+    ctx: &Context,
+    member: &Member,
+    moderator: &Member,
+    bot_member: &Member,
+) -> CommandResult<Option<HierarchyError>> {
+    let owner_id = ctx.cache.guild_field(member.guild_id, |g| g.owner_id);
+
+    if owner_id == Some(member.user.id) || member.permissions(ctx)?.administrator() {
+        return Ok(Some(HierarchyError::Administrator));
+    }
+
+    // Members without roles are at the bottom of the hierarchy
+    let get_position = |member: &Member| member.highest_role_info(ctx).map_or(0, |(_, x)| x);
+    let member_position = get_position(member);
+
+    if owner_id != Some(moderator.user.id) && get_position(moderator) <= member_position {
+        return Ok(Some(HierarchyError::Moderator));
+    }
+
+    if get_position(bot_member) <= member_position {
+        return Ok(Some(HierarchyError::Bot));
+    }
+
+    Ok(None)
+}
@@ -1,39 +1,48 @@
-use serenity::{model::prelude::*, prelude::*};
+use serenity::{framework::standard::CommandResult, model::prelude::*, prelude::*};
 
-use crate::structures::errors::{JesterError, PermissionType};
+use crate::{
+    helpers::locale,
+    structures::{
+        command_source::CommandSource,
+        errors::{JesterError, PermissionType},
+    },
+};
 
 pub async fn check_permission(
     ctx: &Context,
-    msg: &Message,
+    source: &CommandSource<'_>,
     user_id: Option<UserId>,
     check_admin: bool,
 ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-    let channel = msg
-        .channel_id
+    let channel = source
+        .channel_id()
         .to_channel(ctx)
         .await
         .unwrap()
         .guild()
         .unwrap();
-    let permissions = channel.permissions_for_user(ctx, user_id.unwrap_or(msg.author.id))?;
+    let permissions = channel.permissions_for_user(ctx, user_id.unwrap_or(source.author().id))?;
+    let locale = locale::get_guild_locale(ctx, source.guild_id()).await;
 
     if permissions.administrator() {
         Ok(true)
     } else if check_admin && user_id.is_none() {
-        msg.channel_id
+        source
             .say(
                 ctx,
-                JesterError::PermissionError(PermissionType::UserPerm("administrator")),
+                JesterError::PermissionError(PermissionType::UserPerm("administrator"))
+                    .localized(&locale),
             )
             .await?;
 
         Ok(false)
     } else {
         if user_id.is_none() && !permissions.manage_messages() {
-            msg.channel_id
+            source
                 .say(
                     ctx,
-                    JesterError::PermissionError(PermissionType::UserPerm("manage messages")),
+                    JesterError::PermissionError(PermissionType::UserPerm("manage messages"))
+                        .localized(&locale),
                 )
                 .await?;
         }
@@ -41,3 +50,27 @@ pub async fn check_permission(
         Ok(permissions.manage_messages())
     }
 }
+
+/*
+ * Automod skips anyone who could moderate the message themselves,
+ * along with the roles and channels a guild exempted
+ */
+pub async fn check_automod_exemption(
+    ctx: &Context,
+    msg: &Message,
+    exempt_roles: &[RoleId],
+    exempt_channels: &[ChannelId],
+) -> CommandResult<bool> {
+    if exempt_channels.contains(&msg.channel_id) {
+        return Ok(true);
+    }
+
+    if let Some(member) = msg.member.as_ref() {
+        if member.roles.iter().any(|x| exempt_roles.contains(x)) {
+            return Ok(true);
+        }
+    }
+
+    // Passing the user ID means nothing is sent if they're missing the permission
+    check_permission(ctx, &CommandSource::Prefix(msg), Some(msg.author.id), false).await
+}
@@ -0,0 +1,638 @@
+use serenity::{
+    builder::CreateEmbed,
+    client::Context,
+    framework::standard::CommandResult,
+    model::{
+        channel::{Reaction, ReactionType},
+        id::{ChannelId, GuildId, MessageId, UserId},
+    },
+};
+use sqlx::{AnyPool, FromRow};
+
+use crate::{
+    helpers::{
+        command_utils,
+        scheduler::{self, JobKind},
+    },
+    ConnectionPool,
+};
+
+// Each option is voted for with the reaction at its position
+pub const POLL_EMOJIS: [&str; 10] = [
+    "1\u{fe0f}\u{20e3}",
+    "2\u{fe0f}\u{20e3}",
+    "3\u{fe0f}\u{20e3}",
+    "4\u{fe0f}\u{20e3}",
+    "5\u{fe0f}\u{20e3}",
+    "6\u{fe0f}\u{20e3}",
+    "7\u{fe0f}\u{20e3}",
+    "8\u{fe0f}\u{20e3}",
+    "9\u{fe0f}\u{20e3}",
+    "\u{1f51f}",
+];
+
+pub struct NewPoll {
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    pub author_id: UserId,
+    pub question: String,
+    pub options: Vec<String>,
+    pub multiple_choice: bool,
+    pub end_time: Option<i64>,
+}
+
+pub struct Poll {
+    pub id: i64,
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    pub message_id: Option<MessageId>,
+    pub author_id: UserId,
+    pub question: String,
+    pub options: Vec<String>,
+    pub multiple_choice: bool,
+    pub end_time: Option<i64>,
+    pub job_id: Option<i64>,
+    pub closed: bool,
+}
+
+#[derive(FromRow)]
+struct PollRow {
+    id: i64,
+    guild_id: i64,
+    channel_id: i64,
+    message_id: Option<i64>,
+    author_id: i64,
+    question: String,
+    multiple_choice: bool,
+    end_time: Option<i64>,
+    job_id: Option<i64>,
+    closed: bool,
+}
+
+const POLL_COLUMNS: &str =
+    "id, guild_id, channel_id, message_id, author_id, question, multiple_choice, end_time, job_id, closed";
+
+async fn load_poll(pool: &AnyPool, row: PollRow) -> CommandResult<Poll> {
+    let options = sqlx::query_scalar::<_, String>(
+        "SELECT content FROM poll_options WHERE poll_id = $1 ORDER BY position",
+    )
+    .bind(row.id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Poll {
+        id: row.id,
+        guild_id: GuildId::from(row.guild_id as u64),
+        channel_id: ChannelId::from(row.channel_id as u64),
+        message_id: row.message_id.map(|x| MessageId::from(x as u64)),
+        author_id: UserId::from(row.author_id as u64),
+        question: row.question,
+        options,
+        multiple_choice: row.multiple_choice,
+        end_time: row.end_time,
+        job_id: row.job_id,
+        closed: row.closed,
+    })
+}
+
+/*
+ * The poll is saved before its message is sent so the message can show the poll's ID
+ * Call set_poll_message once the message exists, or remove_poll if it couldn't be sent
+ */
+pub async fn add_poll(pool: &AnyPool, poll: &NewPoll) -> CommandResult<i64> {
+    let mut transaction = pool.begin().await?;
+
+    let poll_id = sqlx::query_scalar::<_, i64>(
+        "INSERT INTO polls(guild_id, channel_id, author_id, question, multiple_choice, end_time, created_at)
+            VALUES($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id",
+    )
+    .bind(poll.guild_id.0 as i64)
+    .bind(poll.channel_id.0 as i64)
+    .bind(poll.author_id.0 as i64)
+    .bind(&poll.question)
+    .bind(poll.multiple_choice)
+    .bind(poll.end_time)
+    .bind(command_utils::get_unix_time())
+    .fetch_one(&mut transaction)
+    .await?;
+
+    for (position, content) in poll.options.iter().enumerate() {
+        sqlx::query("INSERT INTO poll_options(poll_id, position, content) VALUES($1, $2, $3)")
+            .bind(poll_id)
+            .bind(position as i32)
+            .bind(content)
+            .execute(&mut transaction)
+            .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(poll_id)
+}
+
+// Polls with an end time get a job that closes them
+pub async fn set_poll_message(
+    pool: &AnyPool,
+    poll_id: i64,
+    guild_id: GuildId,
+    message_id: MessageId,
+    end_time: Option<i64>,
+) -> CommandResult {
+    let mut transaction = pool.begin().await?;
+
+    let job_id = match end_time {
+        Some(end_time) => Some(
+            scheduler::schedule_job(
+                &mut transaction,
+                JobKind::PollClose,
+                "",
+                Some(guild_id),
+                end_time,
+            )
+            .await?,
+        ),
+        None => None,
+    };
+
+    sqlx::query("UPDATE polls SET message_id = $1, job_id = $2 WHERE id = $3")
+        .bind(message_id.0 as i64)
+        .bind(job_id)
+        .bind(poll_id)
+        .execute(&mut transaction)
+        .await?;
+
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+pub async fn remove_poll(pool: &AnyPool, poll_id: i64) -> CommandResult {
+    sqlx::query("DELETE FROM polls WHERE id = $1")
+        .bind(poll_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn fetch_poll(
+    pool: &AnyPool,
+    guild_id: GuildId,
+    poll_id: i64,
+) -> CommandResult<Option<Poll>> {
+    let row = sqlx::query_as::<_, PollRow>(&format!(
+        "SELECT {} FROM polls WHERE id = $1 AND guild_id = $2",
+        POLL_COLUMNS
+    ))
+    .bind(poll_id)
+    .bind(guild_id.0 as i64)
+    .fetch_optional(pool)
+    .await?;
+
+    match row {
+        Some(row) => Ok(Some(load_poll(pool, row).await?)),
+        None => Ok(None),
+    }
+}
+
+async fn fetch_poll_by_message(
+    pool: &AnyPool,
+    message_id: MessageId,
+) -> CommandResult<Option<Poll>> {
+    let row = sqlx::query_as::<_, PollRow>(&format!(
+        "SELECT {} FROM polls WHERE message_id = $1",
+        POLL_COLUMNS
+    ))
+    .bind(message_id.0 as i64)
+    .fetch_optional(pool)
+    .await?;
+
+    match row {
+        Some(row) => Ok(Some(load_poll(pool, row).await?)),
+        None => Ok(None),
+    }
+}
+
+// Vote counts in the same order as the options, along with the number of people who voted
+pub async fn fetch_results(pool: &AnyPool, poll: &Poll) -> CommandResult<(Vec<i64>, i64)> {
+    let votes = sqlx::query_as::<_, (i32, i64)>(
+        "SELECT position, COUNT(*) FROM poll_votes WHERE poll_id = $1 GROUP BY position",
+    )
+    .bind(poll.id)
+    .fetch_all(pool)
+    .await?;
+
+    let voters = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(DISTINCT user_id) FROM poll_votes WHERE poll_id = $1",
+    )
+    .bind(poll.id)
+    .fetch_one(pool)
+    .await?;
+
+    let mut counts = vec![0; poll.options.len()];
+
+    for (position, count) in votes {
+        if let Some(option_count) = counts.get_mut(position as usize) {
+            *option_count = count;
+        }
+    }
+
+    Ok((counts, voters))
+}
+
+pub fn get_poll_embed(poll: &Poll) -> CreateEmbed {
+    let option_list = poll
+        .options
+        .iter()
+        .enumerate()
+        .map(|(position, content)| format!("{} {}", POLL_EMOJIS[position], content))
+        .collect::<Vec<String>>()
+        .join("\n\n");
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0x3498db);
+    eb.title(&poll.question);
+    eb.description(option_list);
+
+    eb.field(
+        "Voting",
+        if poll.multiple_choice {
+            "React to vote for as many options as you want"
+        } else {
+            "React to vote for one option"
+        },
+        false,
+    );
+
+    if let Some(end_time) = poll.end_time {
+        eb.field("Ends", format!("<t:{}:R>", end_time), false);
+    }
+
+    eb.footer(|f| {
+        f.text(format!("Poll ID: {}", poll.id));
+        f
+    });
+
+    eb
+}
+
+pub fn get_results_embed(poll: &Poll, counts: &[i64], voters: i64) -> CreateEmbed {
+    let total_votes = counts.iter().sum::<i64>();
+
+    let result_list = poll
+        .options
+        .iter()
+        .zip(counts)
+        .enumerate()
+        .map(|(position, (content, count))| {
+            let percentage = if total_votes == 0 {
+                0
+            } else {
+                count * 100 / total_votes
+            };
+
+            let filled = (percentage / 10) as usize;
+
+            format!(
+                "{} {} \n`{}{}` {} ({}%)",
+                POLL_EMOJIS[position],
+                content,
+                "\u{2588}".repeat(filled),
+                "\u{2591}".repeat(10 - filled),
+                count,
+                percentage
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n");
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0x3498db);
+    eb.title(&poll.question);
+    eb.description(result_list);
+    eb.field("Voters", voters, true);
+    eb.field("Votes", total_votes, true);
+
+    if let Some(message_id) = poll.message_id {
+        eb.field(
+            "Poll",
+            format!(
+                "[Jump!]({})",
+                command_utils::get_message_url(poll.guild_id, poll.channel_id, message_id)
+            ),
+            true,
+        );
+    }
+
+    eb.footer(|f| {
+        if poll.closed {
+            f.text(format!("Poll ID: {} | Closed", poll.id));
+        } else {
+            f.text(format!("Poll ID: {} | Still open", poll.id));
+        }
+
+        f
+    });
+
+    eb
+}
+
+/*
+ * Closes a poll and posts its results in the poll's channel
+ * The poll message is edited to show the results too, and nobody can vote anymore
+ * Returns false if the poll was already closed
+ */
+pub async fn close_poll(ctx: &Context, pool: &AnyPool, mut poll: Poll) -> CommandResult<bool> {
+    if !set_poll_closed(pool, &poll).await? {
+        return Ok(false);
+    }
+
+    poll.closed = true;
+
+    let (counts, voters) = fetch_results(pool, &poll).await?;
+    let results_embed = get_results_embed(&poll, &counts, voters);
+
+    // The poll message might be gone, but the results can still be sent
+    if let Some(message_id) = poll.message_id {
+        let _ = poll
+            .channel_id
+            .edit_message(ctx, message_id, |m| m.set_embed(results_embed.clone()))
+            .await;
+    }
+
+    poll.channel_id
+        .send_message(ctx, |m| {
+            m.content("This poll has closed!").set_embed(results_embed)
+        })
+        .await?;
+
+    Ok(true)
+}
+
+// Returns false if the poll was already closed
+async fn set_poll_closed(pool: &AnyPool, poll: &Poll) -> CommandResult<bool> {
+    let closed = sqlx::query("UPDATE polls SET closed = $1 WHERE id = $2 AND closed = $3")
+        .bind(true)
+        .bind(poll.id)
+        .bind(false)
+        .execute(pool)
+        .await?
+        .rows_affected()
+        == 1;
+
+    // A poll closed early doesn't need its job anymore
+    if let (true, Some(job_id)) = (closed, poll.job_id) {
+        sqlx::query("DELETE FROM scheduled_jobs WHERE id = $1")
+            .bind(job_id)
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(closed)
+}
+
+pub async fn close_poll_job(ctx: &Context, pool: &AnyPool, job_id: i64) -> CommandResult {
+    let row = sqlx::query_as::<_, PollRow>(&format!(
+        "SELECT {} FROM polls WHERE job_id = $1",
+        POLL_COLUMNS
+    ))
+    .bind(job_id)
+    .fetch_optional(pool)
+    .await?;
+
+    if let Some(row) = row {
+        let poll = load_poll(pool, row).await?;
+        close_poll(ctx, pool, poll).await?;
+    }
+
+    Ok(())
+}
+
+/*
+ * Votes are stored per option, so removing a reaction removes that vote
+ * In single choice polls, a new vote replaces the old one and the old reaction is removed
+ */
+pub async fn handle_vote(ctx: &Context, reaction: &Reaction, remove: bool) -> CommandResult {
+    let position = match POLL_EMOJIS
+        .iter()
+        .position(|x| reaction.emoji == ReactionType::Unicode(x.to_string()))
+    {
+        Some(position) => position,
+        None => return Ok(()),
+    };
+
+    let user_id = match reaction.user_id {
+        Some(user_id) if user_id != ctx.cache.current_user_id() => user_id,
+        _ => return Ok(()),
+    };
+
+    let pool = ctx
+        .data
+        .read()
+        .await
+        .get::<ConnectionPool>()
+        .cloned()
+        .unwrap();
+
+    let poll = match fetch_poll_by_message(&pool, reaction.message_id).await? {
+        Some(poll) => poll,
+        None => return Ok(()),
+    };
+
+    // The close job might not have run yet
+    let ended = poll
+        .end_time
+        .is_some_and(|x| x <= command_utils::get_unix_time());
+
+    if poll.closed || ended || position >= poll.options.len() {
+        return Ok(());
+    }
+
+    if remove {
+        return remove_vote(&pool, &poll, user_id, position).await;
+    }
+
+    let old_positions = save_vote(&pool, &poll, user_id, position).await?;
+
+    // Needs manage messages, but the vote still counts without it
+    for old_position in old_positions {
+        let _ = reaction
+            .channel_id
+            .delete_reaction(
+                ctx,
+                reaction.message_id,
+                Some(user_id),
+                ReactionType::Unicode(POLL_EMOJIS[old_position as usize].to_string()),
+            )
+            .await;
+    }
+
+    Ok(())
+}
+
+async fn remove_vote(
+    pool: &AnyPool,
+    poll: &Poll,
+    user_id: UserId,
+    position: usize,
+) -> CommandResult {
+    sqlx::query("DELETE FROM poll_votes WHERE poll_id = $1 AND user_id = $2 AND position = $3")
+        .bind(poll.id)
+        .bind(user_id.0 as i64)
+        .bind(position as i32)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/*
+ * Single choice votes are unique per user, so the new vote replaces the old one
+ * Reactions added at the same time can't leave a user with two votes
+ * Returns the positions of the votes that were replaced
+ */
+async fn save_vote(
+    pool: &AnyPool,
+    poll: &Poll,
+    user_id: UserId,
+    position: usize,
+) -> CommandResult<Vec<i32>> {
+    if poll.multiple_choice {
+        sqlx::query(
+            "INSERT INTO poll_votes(poll_id, user_id, position) VALUES($1, $2, $3)
+                ON CONFLICT DO NOTHING",
+        )
+        .bind(poll.id)
+        .bind(user_id.0 as i64)
+        .bind(position as i32)
+        .execute(pool)
+        .await?;
+
+        return Ok(Vec::new());
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    let old_positions = sqlx::query_scalar::<_, i32>(
+        "SELECT position FROM poll_votes WHERE poll_id = $1 AND user_id = $2 AND position != $3",
+    )
+    .bind(poll.id)
+    .bind(user_id.0 as i64)
+    .bind(position as i32)
+    .fetch_all(&mut transaction)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO poll_votes(poll_id, user_id, position, single_choice) VALUES($1, $2, $3, true)
+            ON CONFLICT (poll_id, user_id) WHERE single_choice
+            DO UPDATE SET position = EXCLUDED.position",
+    )
+    .bind(poll.id)
+    .bind(user_id.0 as i64)
+    .bind(position as i32)
+    .execute(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(old_positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::database_helper;
+
+    const GUILD: GuildId = GuildId(1);
+
+    async fn setup(name: &str, multiple_choice: bool) -> (AnyPool, Poll) {
+        let pool = database_helper::obtain_test_pool(name).await;
+
+        sqlx::query("INSERT INTO guild_info(guild_id) VALUES($1)")
+            .bind(GUILD.0 as i64)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let new_poll = NewPoll {
+            guild_id: GUILD,
+            channel_id: ChannelId(2),
+            author_id: UserId(3),
+            question: "Best color?".to_owned(),
+            options: vec!["Red".to_owned(), "Green".to_owned(), "Blue".to_owned()],
+            multiple_choice,
+            end_time: None,
+        };
+
+        let poll_id = add_poll(&pool, &new_poll).await.unwrap();
+        let poll = fetch_poll(&pool, GUILD, poll_id).await.unwrap().unwrap();
+
+        (pool, poll)
+    }
+
+    #[tokio::test]
+    async fn single_choice_votes_are_swapped() {
+        let (pool, poll) = setup("single-choice-votes", false).await;
+
+        assert!(save_vote(&pool, &poll, UserId(10), 0)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(save_vote(&pool, &poll, UserId(10), 2).await.unwrap(), [0]);
+
+        // Voting for the same option again doesn't replace anything
+        assert!(save_vote(&pool, &poll, UserId(10), 2)
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(
+            fetch_results(&pool, &poll).await.unwrap(),
+            (vec![0, 0, 1], 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn multiple_choice_votes_are_kept() {
+        let (pool, poll) = setup("multiple-choice-votes", true).await;
+
+        for position in [0, 1, 1] {
+            assert!(save_vote(&pool, &poll, UserId(10), position)
+                .await
+                .unwrap()
+                .is_empty());
+        }
+
+        remove_vote(&pool, &poll, UserId(10), 0).await.unwrap();
+
+        assert_eq!(
+            fetch_results(&pool, &poll).await.unwrap(),
+            (vec![0, 1, 0], 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn votes_are_counted_on_close() {
+        let (pool, poll) = setup("close-poll", false).await;
+
+        for (user_id, position) in [(10, 0), (11, 1), (12, 1), (10, 1), (13, 2)] {
+            save_vote(&pool, &poll, UserId(user_id), position)
+                .await
+                .unwrap();
+        }
+
+        assert!(set_poll_closed(&pool, &poll).await.unwrap());
+        assert!(!set_poll_closed(&pool, &poll).await.unwrap());
+
+        let poll = fetch_poll(&pool, GUILD, poll.id).await.unwrap().unwrap();
+        assert!(poll.closed);
+
+        assert_eq!(
+            fetch_results(&pool, &poll).await.unwrap(),
+            (vec![0, 3, 1], 4)
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use serenity::{
+    builder::CreateEmbed,
+    client::Context,
+    framework::standard::CommandResult,
+    model::{
+        id::{ChannelId, GuildId, UserId},
+        prelude::Mentionable,
+    },
+};
+use sqlx::{AnyPool, FromRow};
+
+use crate::helpers::{
+    command_utils,
+    scheduler::{self, JobKind},
+};
+
+pub struct NewReminder {
+    pub user_id: UserId,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    pub content: String,
+    pub message_url: Option<String>,
+    pub remind_at: i64,
+}
+
+pub struct Reminder {
+    pub id: i64,
+    pub user_id: UserId,
+    pub channel_id: ChannelId,
+    pub content: String,
+    pub message_url: Option<String>,
+    pub remind_at: i64,
+    pub created_at: i64,
+}
+
+#[derive(FromRow)]
+struct ReminderRow {
+    id: i64,
+    user_id: i64,
+    channel_id: i64,
+    content: String,
+    message_url: Option<String>,
+    remind_at: i64,
+    created_at: i64,
+}
+
+impl From<ReminderRow> for Reminder {
+    fn from(row: ReminderRow) -> Self {
+        Reminder {
+            id: row.id,
+            user_id: UserId::from(row.user_id as u64),
+            channel_id: ChannelId::from(row.channel_id as u64),
+            content: row.content,
+            message_url: row.message_url,
+            remind_at: row.remind_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+/*
+ * Every reminder is sent by its own scheduler job
+ * Removing the job removes the reminder, so cancelling and sending only have to touch the job
+ */
+pub async fn add_reminder(pool: &AnyPool, reminder: NewReminder) -> CommandResult<i64> {
+    let mut transaction = pool.begin().await?;
+
+    let job_id = scheduler::schedule_job(
+        &mut transaction,
+        JobKind::Reminder,
+        "",
+        reminder.guild_id,
+        reminder.remind_at,
+    )
+    .await?;
+
+    let reminder_id = sqlx::query_scalar::<_, i64>(
+        "INSERT INTO reminders(job_id, user_id, guild_id, channel_id, content, message_url, remind_at, created_at)
+            VALUES($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id",
+    )
+    .bind(job_id)
+    .bind(reminder.user_id.0 as i64)
+    .bind(reminder.guild_id.map(|x| x.0 as i64))
+    .bind(reminder.channel_id.0 as i64)
+    .bind(reminder.content)
+    .bind(reminder.message_url)
+    .bind(reminder.remind_at)
+    .bind(command_utils::get_unix_time())
+    .fetch_one(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(reminder_id)
+}
+
+// Sorted with the next reminder first
+pub async fn fetch_reminders(pool: &AnyPool, user_id: UserId) -> CommandResult<Vec<Reminder>> {
+    let reminder_data = sqlx::query_as::<_, ReminderRow>(
+        "SELECT id, user_id, channel_id, content, message_url, remind_at, created_at
+            FROM reminders WHERE user_id = $1
+            ORDER BY remind_at",
+    )
+    .bind(user_id.0 as i64)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(reminder_data.into_iter().map(Reminder::from).collect())
+}
+
+// Users can only cancel their own reminders
+pub async fn cancel_reminder(
+    pool: &AnyPool,
+    user_id: UserId,
+    reminder_id: i64,
+) -> CommandResult<bool> {
+    let result = sqlx::query(
+        "DELETE FROM scheduled_jobs
+            WHERE id = (SELECT job_id FROM reminders WHERE id = $1 AND user_id = $2)",
+    )
+    .bind(reminder_id)
+    .bind(user_id.0 as i64)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/*
+ * Sends the reminder in the channel it was made in
+ * If the bot can't talk there anymore, the user gets a DM instead
+ */
+pub async fn send_reminder(ctx: &Context, pool: &AnyPool, job_id: i64) -> CommandResult {
+    let reminder = match sqlx::query_as::<_, ReminderRow>(
+        "SELECT id, user_id, channel_id, content, message_url, remind_at, created_at
+            FROM reminders WHERE job_id = $1",
+    )
+    .bind(job_id)
+    .fetch_optional(pool)
+    .await?
+    {
+        Some(reminder) => Reminder::from(reminder),
+        None => return Ok(()),
+    };
+
+    let mut eb = CreateEmbed::default();
+
+    eb.color(0x3498db);
+    eb.title("Reminder");
+
+    if !reminder.content.is_empty() {
+        eb.description(&reminder.content);
+    }
+
+    if let Some(message_url) = reminder.message_url.as_ref() {
+        eb.field("Source", format!("[Jump!]({})", message_url), false);
+    }
+
+    eb.field("Set", format!("<t:{}:R>", reminder.created_at), false);
+
+    // The content is written by the user, so only they can be pinged
+    let channel_result = reminder
+        .channel_id
+        .send_message(ctx, |m| {
+            m.content(reminder.user_id.mention())
+                .allowed_mentions(|a| a.users(vec![reminder.user_id]))
+                .set_embed(eb.clone())
+        })
+        .await;
+
+    if channel_result.is_err() {
+        reminder
+            .user_id
+            .create_dm_channel(ctx)
+            .await?
+            .send_message(ctx, |m| m.set_embed(eb))
+            .await?;
+    }
+
+    Ok(())
+}
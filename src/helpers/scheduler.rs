@@ -0,0 +1,294 @@
+use serenity::{client::Context, framework::standard::CommandResult, model::id::GuildId};
+use sqlx::{Any, AnyPool, Executor, FromRow};
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+use tokio::time::sleep;
+
+use crate::{
+    helpers::{command_utils, polls, reminders, start_loops},
+    ConnectionPool, RepositoryContainer,
+};
+
+// How often the database is checked for jobs that are due
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+// A claimed job can't be picked up by another process until this many seconds pass
+const LOCK_SECS: i64 = 300;
+
+const MAX_ATTEMPTS: i32 = 3;
+const RETRY_DELAY_SECS: i64 = 60;
+
+// Run logs older than this are removed by the run log cleanup job
+const RUN_LOG_SECS: i64 = 2592000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JobKind {
+    StarboardCleanup,
+    RunLogCleanup,
+    Reminder,
+    PollClose,
+}
+
+impl fmt::Display for JobKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            JobKind::StarboardCleanup => write!(f, "starboard_cleanup"),
+            JobKind::RunLogCleanup => write!(f, "run_log_cleanup"),
+            JobKind::Reminder => write!(f, "reminder"),
+            JobKind::PollClose => write!(f, "poll_close"),
+        }
+    }
+}
+
+impl FromStr for JobKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "starboard_cleanup" => Ok(JobKind::StarboardCleanup),
+            "run_log_cleanup" => Ok(JobKind::RunLogCleanup),
+            "reminder" => Ok(JobKind::Reminder),
+            "poll_close" => Ok(JobKind::PollClose),
+            _ => Err(format!("{} isn't a job type!", s)),
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct Job {
+    id: i64,
+    kind: String,
+    interval_secs: Option<i64>,
+    attempts: i32,
+    max_attempts: i32,
+}
+
+/*
+ * Schedules a job that runs once at the given unix time
+ * The payload is up to the job type, and guild jobs are removed along with the guild
+ * This takes any executor so a job can be added in the same transaction as its data
+ */
+pub async fn schedule_job<'c, E>(
+    executor: E,
+    kind: JobKind,
+    payload: &str,
+    guild_id: Option<GuildId>,
+    run_at: i64,
+) -> CommandResult<i64>
+where
+    E: Executor<'c, Database = Any>,
+{
+    let job_id = sqlx::query_scalar::<_, i64>(
+        "INSERT INTO scheduled_jobs(kind, payload, guild_id, next_run, max_attempts, created_at)
+            VALUES($1, $2, $3, $4, $5, $6)
+            RETURNING id",
+    )
+    .bind(kind.to_string())
+    .bind(payload)
+    .bind(guild_id.map(|x| x.0 as i64))
+    .bind(run_at)
+    .bind(MAX_ATTEMPTS)
+    .bind(command_utils::get_unix_time())
+    .fetch_one(executor)
+    .await?;
+
+    Ok(job_id)
+}
+
+/*
+ * Recurring jobs are named after their type, so restarting the bot doesn't add them again
+ * A new job runs right away, and an existing one keeps its next run time
+ */
+pub async fn ensure_recurring_job(
+    pool: &AnyPool,
+    kind: JobKind,
+    interval: Duration,
+) -> CommandResult {
+    let current_time = command_utils::get_unix_time();
+
+    sqlx::query(
+        "INSERT INTO scheduled_jobs(name, kind, payload, next_run, interval_secs, max_attempts, created_at)
+            VALUES($1, $2, '', $3, $4, $5, $3)
+            ON CONFLICT (name) DO UPDATE SET kind = EXCLUDED.kind, interval_secs = EXCLUDED.interval_secs",
+    )
+    .bind(kind.to_string())
+    .bind(kind.to_string())
+    .bind(current_time)
+    .bind(interval.as_secs() as i64)
+    .bind(MAX_ATTEMPTS)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/*
+ * Checks for due jobs until the bot stops
+ * Every bot process polls the same table, so a job has to be claimed before it runs
+ */
+pub async fn run_scheduler(ctx: Context) {
+    let pool = ctx
+        .data
+        .read()
+        .await
+        .get::<ConnectionPool>()
+        .cloned()
+        .unwrap();
+
+    loop {
+        if let Err(e) = run_due_jobs(&ctx, &pool).await {
+            eprintln!("Error when checking for scheduled jobs! {}", e);
+        }
+
+        sleep(POLL_INTERVAL).await;
+    }
+}
+
+async fn run_due_jobs(ctx: &Context, pool: &AnyPool) -> CommandResult {
+    let current_time = command_utils::get_unix_time();
+
+    let jobs = sqlx::query_as::<_, Job>(
+        "SELECT id, kind, interval_secs, attempts, max_attempts
+            FROM scheduled_jobs
+            WHERE next_run <= $1 AND (locked_until IS NULL OR locked_until <= $1)
+            ORDER BY next_run
+            LIMIT 25",
+    )
+    .bind(current_time)
+    .fetch_all(pool)
+    .await?;
+
+    for job in jobs {
+        let claimed = sqlx::query(
+            "UPDATE scheduled_jobs SET locked_until = $1
+                WHERE id = $2 AND (locked_until IS NULL OR locked_until <= $3)",
+        )
+        .bind(current_time + LOCK_SECS)
+        .bind(job.id)
+        .bind(current_time)
+        .execute(pool)
+        .await?
+        .rows_affected()
+            == 1;
+
+        // Another process got to it first
+        if !claimed {
+            continue;
+        }
+
+        let ctx = ctx.clone();
+        let pool = pool.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = run_job(&ctx, &pool, job).await {
+                eprintln!("Error when finishing a scheduled job! {}", e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/*
+ * Every run is logged with its result
+ * Failed jobs are retried with a growing delay until they run out of attempts
+ * A recurring job that runs out skips to its next run, and a one-off job is dropped
+ */
+async fn run_job(ctx: &Context, pool: &AnyPool, job: Job) -> CommandResult {
+    let started_at = command_utils::get_unix_time();
+    let timer = Instant::now();
+
+    let result = match job.kind.parse::<JobKind>() {
+        Ok(kind) => execute_job(ctx, pool, kind, job.id).await,
+        Err(e) => Err(e.into()),
+    };
+
+    let error = result.err().map(|e| e.to_string());
+
+    if let Some(error) = error.as_ref() {
+        eprintln!("Scheduled job {} ({}) failed! {}", job.id, job.kind, error);
+    }
+
+    sqlx::query(
+        "INSERT INTO job_runs(job_id, kind, started_at, duration_ms, success, error)
+            VALUES($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(job.id)
+    .bind(&job.kind)
+    .bind(started_at)
+    .bind(timer.elapsed().as_millis() as i64)
+    .bind(error.is_none())
+    .bind(error.as_deref())
+    .execute(pool)
+    .await?;
+
+    let attempts = job.attempts + 1;
+
+    let next_run = match (error.is_some(), job.interval_secs) {
+        (true, _) if attempts < job.max_attempts => {
+            let retry_time = started_at + RETRY_DELAY_SECS * 2i64.pow(job.attempts as u32);
+
+            sqlx::query(
+                "UPDATE scheduled_jobs SET attempts = $1, next_run = $2, locked_until = NULL WHERE id = $3",
+            )
+            .bind(attempts)
+            .bind(retry_time)
+            .bind(job.id)
+            .execute(pool)
+            .await?;
+
+            return Ok(());
+        }
+        (_, Some(interval_secs)) => Some(started_at + interval_secs),
+        (_, None) => None,
+    };
+
+    match next_run {
+        Some(next_run) => {
+            sqlx::query(
+                "UPDATE scheduled_jobs SET attempts = 0, next_run = $1, locked_until = NULL WHERE id = $2",
+            )
+            .bind(next_run)
+            .bind(job.id)
+            .execute(pool)
+            .await?;
+        }
+        None => {
+            sqlx::query("DELETE FROM scheduled_jobs WHERE id = $1")
+                .bind(job.id)
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn execute_job(ctx: &Context, pool: &AnyPool, kind: JobKind, job_id: i64) -> CommandResult {
+    match kind {
+        JobKind::StarboardCleanup => {
+            let repository = ctx
+                .data
+                .read()
+                .await
+                .get::<RepositoryContainer>()
+                .cloned()
+                .unwrap();
+
+            start_loops::remove_expired_starboard_entries(&*repository).await
+        }
+        JobKind::RunLogCleanup => {
+            sqlx::query("DELETE FROM job_runs WHERE started_at < $1")
+                .bind(command_utils::get_unix_time() - RUN_LOG_SECS)
+                .execute(pool)
+                .await?;
+
+            Ok(())
+        }
+        JobKind::Reminder => reminders::send_reminder(ctx, pool, job_id).await,
+        JobKind::PollClose => polls::close_poll_job(ctx, pool, job_id).await,
+    }
+}
@@ -1,80 +1,62 @@
-use crate::ConnectionPool;
+use crate::{
+    helpers::command_utils, repositories::Repository, RepositoryContainer, ShardManagerContainer,
+};
 use rand::{prelude::StdRng, Rng, SeedableRng};
 use serenity::{
-    client::bridge::gateway::ShardMessenger,
-    framework::standard::CommandResult,
-    model::{id::GuildId, prelude::Activity},
-    prelude::*,
+    framework::standard::CommandResult, model::prelude::Activity, prelude::*, utils::shard_id,
 };
-use sqlx::PgPool;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::time::sleep;
+use std::{collections::HashSet, time::Duration};
+use tokio::time::interval;
 
-pub async fn starboard_removal_loop(pool: &PgPool) -> CommandResult {
-    loop {
-        let delete_data = sqlx::query!(
-            "SELECT guild_id, reaction_message_id, sent_message_id, delete_time FROM starboard"
-        )
-        .fetch_all(pool)
-        .await?;
+pub async fn remove_expired_starboard_entries(repository: &dyn Repository) -> CommandResult {
+    let delete_data = repository.fetch_starboard_entries().await?;
+    let current_time = command_utils::get_unix_time();
 
-        for i in delete_data {
+    for i in delete_data {
+        if i.delete_time <= current_time {
             println!(
-                "Checking delete status on starboard message {} in guild {}",
+                "Deleting starboard message {} in guild {} from the database",
                 i.sent_message_id, i.guild_id
             );
-
-            let current_time = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .expect("Time went backwards?")
-                .as_secs() as i64;
-
-            if i.delete_time <= current_time {
-                println!(
-                    "Deleting starboard message {} from the database",
-                    i.guild_id
-                );
-                sqlx::query!("DELETE FROM starboard WHERE guild_id = $1 AND reaction_message_id = $2 AND sent_message_id = $3",
-                        i.guild_id, i.reaction_message_id, i.sent_message_id)
-                    .execute(pool).await?;
-            } else {
-                println!(
-                    "Entry's time isn't greater than a week! Not deleting message {} in guild {}!",
-                    i.sent_message_id, i.guild_id
-                );
-            }
+            repository
+                .remove_starboard_entry(i.guild_id, i.reaction_message_id)
+                .await?;
         }
-
-        println!("\n");
-
-        sleep(Duration::from_secs(345600)).await;
     }
+
+    Ok(())
 }
 
 pub async fn guild_pruner(ctx: &Context) -> CommandResult {
-    let pool = ctx
-        .data
-        .read()
-        .await
-        .get::<ConnectionPool>()
-        .cloned()
-        .unwrap();
+    let (repository, shard_manager) = {
+        let data = ctx.data.read().await;
+
+        let repository = data.get::<RepositoryContainer>().cloned().unwrap();
+        let shard_manager = data.get::<ShardManagerContainer>().cloned().unwrap();
+
+        (repository, shard_manager)
+    };
 
     let guilds = ctx.cache.guilds();
+    let shard_count = ctx.cache.shard_count();
 
-    let guild_data = sqlx::query!("SELECT guild_id FROM guild_info")
-        .fetch_all(&pool)
-        .await?;
+    // Other processes own the rest of the shards, so their guilds aren't in this cache
+    let local_shards = {
+        let manager = shard_manager.lock().await;
+        let runners = manager.runners.lock().await;
+
+        runners.keys().map(|x| x.0).collect::<HashSet<u64>>()
+    };
+
+    let guild_data = repository.fetch_guild_ids().await?;
 
     println!(" ");
 
-    for guild in guild_data {
-        if !guilds.contains(&GuildId::from(guild.guild_id as u64)) {
-            println!("Removing guild: {}", guild.guild_id);
+    for guild_id in guild_data {
+        if local_shards.contains(&shard_id(guild_id, shard_count)) && !guilds.contains(&guild_id) {
+            println!("Removing guild: {}", guild_id);
 
-            sqlx::query!("DELETE FROM guild_info WHERE guild_id = $1", guild.guild_id)
-                .execute(&pool)
-                .await?;
+            repository.remove_guild(guild_id).await?;
         }
     }
 
@@ -83,7 +65,19 @@ pub async fn guild_pruner(ctx: &Context) -> CommandResult {
     Ok(())
 }
 
-pub async fn activity_loop(messenger: &ShardMessenger) {
+// The first tick is immediate, so the activity is set as soon as the loop starts
+pub async fn activity_loop(ctx: Context) {
+    let mut timer = interval(Duration::from_secs(7200));
+
+    loop {
+        timer.tick().await;
+
+        set_random_activity(&ctx).await;
+    }
+}
+
+// Every shard in this process gets the same activity
+async fn set_random_activity(ctx: &Context) {
     let activity_vec = vec![
         Activity::playing("as the fool"),
         Activity::listening("a tune!"),
@@ -100,12 +94,22 @@ pub async fn activity_loop(messenger: &ShardMessenger) {
     ];
 
     let mut rng = StdRng::from_entropy();
+    let val = rng.gen_range(0..=activity_vec.len() - 1);
 
-    loop {
-        let val = rng.gen_range(0..=activity_vec.len() - 1);
+    let shard_manager = ctx
+        .data
+        .read()
+        .await
+        .get::<ShardManagerContainer>()
+        .cloned()
+        .unwrap();
 
-        messenger.set_activity(Some(activity_vec[val].to_owned()));
+    let manager = shard_manager.lock().await;
+    let runners = manager.runners.lock().await;
 
-        sleep(Duration::from_secs(7200)).await;
+    for runner in runners.values() {
+        runner
+            .runner_tx
+            .set_activity(Some(activity_vec[val].to_owned()));
     }
 }
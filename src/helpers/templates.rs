@@ -0,0 +1,315 @@
+use rand::{prelude::StdRng, Rng, SeedableRng};
+use serenity::{
+    model::id::{ChannelId, UserId},
+    prelude::Mentionable,
+};
+
+// Keeps a runaway {args} from going over Discord's message limit
+const MESSAGE_LIMIT: usize = 2000;
+
+#[derive(Clone, Debug)]
+enum Segment {
+    Text(String),
+    User,
+    Username,
+    Args,
+    Arg(usize),
+    Channel,
+    Server,
+    MemberCount,
+    Uses,
+    Choice(Vec<String>),
+}
+
+/*
+ * Custom command templates are parsed once when the command is set
+ * so mistakes can be reported to the user before anyone runs the command
+ *
+ * Placeholders go in curly braces and literal braces are written as {{ and }}
+ */
+#[derive(Clone, Debug)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+pub struct TemplateData<'a> {
+    pub user_id: UserId,
+    pub username: &'a str,
+    pub args: &'a str,
+    pub channel_id: ChannelId,
+    pub server_name: &'a str,
+    pub member_count: u64,
+    pub uses: i64,
+}
+
+impl Template {
+    pub fn parse(content: &str) -> Result<Template, String> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut chars = content.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut placeholder = String::new();
+                    let mut closed = false;
+
+                    for c in chars.by_ref() {
+                        match c {
+                            '}' => {
+                                closed = true;
+                                break;
+                            }
+                            '{' => {
+                                return Err("Placeholders can't be put inside other placeholders!"
+                                    .to_owned())
+                            }
+                            _ => placeholder.push(c),
+                        }
+                    }
+
+                    if !closed {
+                        return Err(format!(
+                            "The placeholder `{{{}` is missing a closing brace!",
+                            placeholder
+                        ));
+                    }
+
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+
+                    segments.push(parse_placeholder(&placeholder)?);
+                }
+                '}' => {
+                    return Err(
+                        "Found a closing brace without an opening one! Use }} for a literal brace"
+                            .to_owned(),
+                    )
+                }
+                _ => text.push(c),
+            }
+        }
+
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+
+        Ok(Template { segments })
+    }
+
+    /*
+     * Old commands that don't parse are sent as they were written
+     * They only ever had {user}, so it's still replaced like it was before templates
+     */
+    pub fn parse_or_literal(content: &str) -> Template {
+        Template::parse(content).unwrap_or_else(|_| {
+            let mut segments = Vec::new();
+
+            for (i, text) in content.split("{user}").enumerate() {
+                if i > 0 {
+                    segments.push(Segment::User);
+                }
+
+                if !text.is_empty() {
+                    segments.push(Segment::Text(text.to_owned()));
+                }
+            }
+
+            Template { segments }
+        })
+    }
+
+    pub fn render(&self, data: &TemplateData) -> String {
+        let mut rng = StdRng::from_entropy();
+        let words = data.args.split_whitespace().collect::<Vec<&str>>();
+
+        let output = self
+            .segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.to_owned(),
+                Segment::User => data.user_id.mention().to_string(),
+                Segment::Username => data.username.to_owned(),
+                Segment::Args => data.args.to_owned(),
+                Segment::Arg(index) => words
+                    .get(index - 1)
+                    .map(|x| x.to_string())
+                    .unwrap_or_default(),
+                Segment::Channel => data.channel_id.mention().to_string(),
+                Segment::Server => data.server_name.to_owned(),
+                Segment::MemberCount => data.member_count.to_string(),
+                Segment::Uses => data.uses.to_string(),
+                Segment::Choice(choices) => choices[rng.gen_range(0..choices.len())].to_owned(),
+            })
+            .collect::<String>();
+
+        if output.chars().count() > MESSAGE_LIMIT {
+            output.chars().take(MESSAGE_LIMIT).collect()
+        } else {
+            output
+        }
+    }
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<Segment, String> {
+    let placeholder = placeholder.trim();
+
+    if let Some(choices) = placeholder.strip_prefix("choose:") {
+        let choices = choices
+            .split('|')
+            .map(|x| x.trim().to_owned())
+            .collect::<Vec<String>>();
+
+        if choices.len() < 2 || choices.iter().any(|x| x.is_empty()) {
+            return Err(
+                "A choice list needs at least two non-empty options, like `{choose:yes|no}`"
+                    .to_owned(),
+            );
+        }
+
+        return Ok(Segment::Choice(choices));
+    }
+
+    let segment = match placeholder {
+        "user" => Segment::User,
+        "username" => Segment::Username,
+        "args" => Segment::Args,
+        "channel" => Segment::Channel,
+        "server" => Segment::Server,
+        "membercount" => Segment::MemberCount,
+        "uses" => Segment::Uses,
+        _ => match placeholder.strip_prefix("arg") {
+            Some(index) if !index.is_empty() => match index.parse::<usize>() {
+                Ok(index) if index > 0 => Segment::Arg(index),
+                _ => {
+                    return Err(format!(
+                        "`{{{}}}` isn't a valid argument! Arguments start at `{{arg1}}`",
+                        placeholder
+                    ))
+                }
+            },
+            _ => {
+                return Err(format!(
+                    "`{{{}}}` isn't a placeholder! Use {{{{ and }}}} for literal braces",
+                    placeholder
+                ))
+            }
+        },
+    };
+
+    Ok(segment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(template: &Template) -> String {
+        let data = TemplateData {
+            user_id: UserId(1),
+            username: "jester",
+            args: "first second",
+            channel_id: ChannelId(2),
+            server_name: "court",
+            member_count: 42,
+            uses: 7,
+        };
+
+        template.render(&data)
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let template = Template::parse(
+            "{user} {username} {args} {arg2} {channel} {server} {membercount} {uses}",
+        )
+        .unwrap();
+
+        assert_eq!(
+            render(&template),
+            "<@1> jester first second second <#2> court 42 7"
+        );
+    }
+
+    #[test]
+    fn missing_args_render_empty() {
+        let template = Template::parse("[{arg3}]").unwrap();
+
+        assert_eq!(render(&template), "[]");
+    }
+
+    #[test]
+    fn escaped_braces_are_literal() {
+        let template = Template::parse("{{user}} is {user} }}").unwrap();
+
+        assert_eq!(render(&template), "{user} is <@1> }");
+    }
+
+    #[test]
+    fn choices_pick_an_option() {
+        let template = Template::parse("{choose: yes | no}").unwrap();
+
+        assert!(["yes", "no"].contains(&render(&template).as_str()));
+    }
+
+    #[test]
+    fn rejects_unterminated_placeholder() {
+        assert!(Template::parse("hello {user").is_err());
+        assert!(Template::parse("{").is_err());
+    }
+
+    #[test]
+    fn rejects_stray_closing_brace() {
+        assert!(Template::parse("hello }").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders() {
+        assert!(Template::parse("{nothing}").is_err());
+        assert!(Template::parse("{arg0}").is_err());
+        assert!(Template::parse("{argx}").is_err());
+        assert!(Template::parse("{choose:yes}").is_err());
+        assert!(Template::parse("{choose:yes|}").is_err());
+    }
+
+    #[test]
+    fn rejects_nested_placeholders() {
+        assert!(Template::parse("{choose:{user}|no}").is_err());
+    }
+
+    #[test]
+    fn old_commands_fall_back_to_literal_text() {
+        let saved = "function main() { return {a: 1}; }";
+
+        assert!(Template::parse(saved).is_err());
+        assert_eq!(render(&Template::parse_or_literal(saved)), saved);
+    }
+
+    #[test]
+    fn literal_commands_still_mention_the_user() {
+        let saved = "{user} wrote {a: 1} and {user}";
+
+        assert!(Template::parse(saved).is_err());
+        assert_eq!(
+            render(&Template::parse_or_literal(saved)),
+            "<@1> wrote {a: 1} and <@1>"
+        );
+    }
+
+    #[test]
+    fn valid_commands_are_not_literal() {
+        assert_eq!(
+            render(&Template::parse_or_literal("hi {username}")),
+            "hi jester"
+        );
+    }
+}
@@ -2,11 +2,17 @@ mod commands;
 mod handlers;
 mod helpers;
 mod reactions;
+mod repositories;
 mod structures;
 
 use crate::{
     handlers::{event_handler::SerenityHandler, framework::get_framework},
-    helpers::{command_utils, database_helper},
+    helpers::{
+        command_rules, command_utils, database_helper,
+        guild_settings::{self, GuildSettings},
+        mod_log::BoundedMessageCache,
+    },
+    repositories::{sql::SqlRepository, Repository},
     structures::{cmd_data::*, commands::*, errors::*},
 };
 // use aspotify::{Client as Spotify, ClientCredentials};
@@ -27,7 +33,13 @@ async fn main() -> CommandResult {
     tracing_subscriber::fmt::init();
 
     let args: Vec<String> = env::args().collect();
-    let creds = helpers::credentials_helper::read_creds(&args[1])?;
+    let creds = match helpers::credentials_helper::read_creds(args.get(1).map(|x| x.as_str())) {
+        Ok(creds) => creds,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     let token = &creds.bot_token;
 
     let http = Http::new_with_application_id(&token, creds.application_id);
@@ -48,8 +60,28 @@ async fn main() -> CommandResult {
         Err(why) => panic!("Could not access application info: {:?}", why),
     };
 
-    let pool = database_helper::obtain_db_pool(creds.db_connection).await?;
-    let prefixes = database_helper::fetch_prefixes(&pool).await?;
+    let pool = database_helper::obtain_db_pool(creds.db_connection.clone()).await?;
+
+    println!("Running database migrations!");
+    if let Err(e) = database_helper::run_migrations(&pool).await {
+        eprintln!("Error when migrating the database! {}", e);
+        std::process::exit(1);
+    }
+
+    let repository: Arc<dyn Repository> = Arc::new(SqlRepository::new(pool.clone()));
+    let guild_settings_map: Arc<DashMap<GuildId, GuildSettings>> = Arc::new(DashMap::new());
+
+    // SQLite databases only have one bot process, so there's no one else to hear changes from
+    if !database_helper::is_sqlite_url(&creds.db_connection) {
+        let db_connection = creds.db_connection.clone();
+        let settings_map = guild_settings_map.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = guild_settings::listen_for_changes(db_connection, settings_map).await {
+                eprintln!("Couldn't start the guild settings listener! {}", e);
+            }
+        });
+    }
     let voice_timer_map: DashMap<GuildId, AbortHandle> = DashMap::new();
 
     /*
@@ -60,10 +92,31 @@ async fn main() -> CommandResult {
         .await?;
     */
 
+    let unavailable_commands = creds.get_unavailable_commands();
+
+    if !unavailable_commands.is_empty() {
+        let mut command_list = unavailable_commands
+            .iter()
+            .cloned()
+            .collect::<Vec<String>>();
+        command_list.sort();
+
+        println!(
+            "Some API keys are missing! Disabling these commands: {}",
+            command_list.join(", ")
+        );
+    }
+
     let mut pub_creds = HashMap::new();
-    pub_creds.insert("tenor".to_string(), creds.tenor_key);
     pub_creds.insert("default prefix".to_string(), creds.default_prefix);
-    pub_creds.insert("mal".to_string(), creds.mal_key);
+
+    if let Some(tenor_key) = creds.tenor_key {
+        pub_creds.insert("tenor".to_string(), tenor_key);
+    }
+
+    if let Some(mal_key) = creds.mal_key {
+        pub_creds.insert("mal".to_string(), mal_key);
+    }
 
     /*
     let client_credentials = ClientCredentials {
@@ -89,17 +142,22 @@ async fn main() -> CommandResult {
         })
         .collect::<Vec<String>>();
 
+    let command_scopes = command_rules::build_command_scopes(MASTER_GROUP.options.sub_groups);
+
     let reqwest_client = Reqwest::builder()
         .user_agent("Mozilla/5.0 (X11; Linux x86_64; rv:73.0) Gecko/20100101 Firefox/73.0")
         .build()?;
 
     let mut intents = GatewayIntents::all();
-    intents.remove(GatewayIntents::DIRECT_MESSAGES);
-    intents.remove(GatewayIntents::DIRECT_MESSAGE_REACTIONS);
     intents.remove(GatewayIntents::DIRECT_MESSAGE_TYPING);
 
     let mut client = Client::builder(&token, intents)
-        .framework(get_framework(bot_id, owners))
+        .application_id(creds.application_id)
+        .framework(get_framework(
+            bot_id,
+            owners.clone(),
+            unavailable_commands.clone(),
+        ))
         .event_handler(SerenityHandler {
             run_loop: AtomicBool::new(true),
         })
@@ -112,21 +170,41 @@ async fn main() -> CommandResult {
         let mut data = client.data.write().await;
 
         data.insert::<ConnectionPool>(pool.clone());
+        data.insert::<RepositoryContainer>(repository);
         data.insert::<ShardManagerContainer>(Arc::clone(&client.shard_manager));
         // data.insert::<Lavalink>(lava_client);
         data.insert::<VoiceTimerMap>(Arc::new(voice_timer_map));
-        data.insert::<PrefixMap>(Arc::new(prefixes));
+        data.insert::<GuildSettingsMap>(guild_settings_map);
         data.insert::<CommandNameMap>(Arc::new(command_names));
+        data.insert::<CommandScopeMap>(Arc::new(command_scopes));
+        data.insert::<CooldownMap>(Arc::new(DashMap::new()));
+        data.insert::<CommandTimers>(Arc::new(DashMap::new()));
         data.insert::<ReqwestClient>(reqwest_client);
         data.insert::<PubCreds>(Arc::new(pub_creds));
         data.insert::<EmergencyCommands>(Arc::new(emergency_commands));
         data.insert::<BotId>(bot_id);
+        data.insert::<BotOwners>(Arc::new(owners));
+        data.insert::<UnavailableCommands>(Arc::new(unavailable_commands));
         // data.insert::<SpotifyClient>(Arc::new(spotify));
         data.insert::<ReactionImageCache>(Arc::new(DashMap::new()));
+        data.insert::<MessageCache>(Arc::new(BoundedMessageCache::new()));
+        data.insert::<AutomodTracker>(Arc::new(DashMap::new()));
     }
 
     // Start up the bot! If there's an error, let the user know
-    if let Err(why) = client.start_autosharded().await {
+    let start_result = match (creds.shard_count, creds.shard_range) {
+        (Some(shard_count), Some(shard_range)) => {
+            println!(
+                "Starting shards {} to {} of {}",
+                shard_range[0], shard_range[1], shard_count
+            );
+            client.start_shard_range(shard_range, shard_count).await
+        }
+        (Some(shard_count), None) => client.start_shards(shard_count).await,
+        _ => client.start_autosharded().await,
+    };
+
+    if let Err(why) = start_result {
         eprintln!("Client error: {:?}", why);
     }
 
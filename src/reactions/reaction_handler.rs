@@ -4,12 +4,19 @@ use serenity::{
     model::{channel::Reaction, prelude::ReactionType},
 };
 
-use crate::reactions::starboard;
+use crate::{helpers::polls, reactions::starboard};
 
 pub async fn dispatch_reaction(ctx: &Context, reaction: &Reaction, remove: bool) -> CommandResult {
+    // Reactions in DMs don't have anything to do
+    if reaction.guild_id.is_none() {
+        return Ok(());
+    }
+
     if let ReactionType::Unicode(emoji) = &reaction.emoji {
         if emoji == "⭐" {
             starboard::quote_reaction(ctx, reaction, remove).await?;
+        } else if polls::POLL_EMOJIS.contains(&emoji.as_str()) {
+            polls::handle_vote(ctx, reaction, remove).await?;
         }
     }
 
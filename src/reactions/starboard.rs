@@ -6,28 +6,26 @@ use serenity::{
     framework::standard::CommandResult,
     model::{
         channel::{Attachment, Reaction},
-        id::{ChannelId, GuildId, MessageId},
         prelude::{ReactionType, User},
     },
     prelude::Mentionable,
 };
-use sqlx::PgPool;
 
-use crate::{helpers::command_utils, structures::cmd_data::ConnectionPool};
-
-struct StarbotConfig {
-    starboard_threshold: Option<i32>,
-    quote_id: Option<i64>,
-}
+use crate::{
+    helpers::{command_utils, guild_settings},
+    repositories::StarboardEntry,
+    structures::cmd_data::RepositoryContainer,
+};
 
 pub async fn quote_reaction(ctx: &Context, reaction: &Reaction, remove: bool) -> CommandResult {
-    let pool = ctx
+    let repository = ctx
         .data
         .read()
         .await
-        .get::<ConnectionPool>()
+        .get::<RepositoryContainer>()
         .cloned()
         .unwrap();
+    let guild_id = reaction.guild_id.unwrap();
     let reaction_message = reaction.message(ctx).await?;
 
     let reaction_channel = reaction.channel(ctx).await?;
@@ -41,19 +39,20 @@ pub async fn quote_reaction(ctx: &Context, reaction: &Reaction, remove: bool) ->
         None => 0,
     };
 
-    let config_data = sqlx::query_as!(StarbotConfig, "SELECT guild_info.starboard_threshold, text_channels.quote_id
-                                    FROM guild_info
-                                    INNER JOIN text_channels ON guild_info.guild_id=text_channels.guild_id
-                                    WHERE guild_info.guild_id = $1", reaction.guild_id.unwrap().0 as i64)
-        .fetch_one(&pool).await?;
+    let settings = guild_settings::get_guild_settings(ctx, guild_id).await?;
 
-    if config_data.starboard_threshold.is_none() || config_data.quote_id.is_none() {
-        return Ok(());
-    }
+    let starboard_threshold = match settings.starboard_threshold {
+        Some(starboard_threshold) => starboard_threshold,
+        None => return Ok(()),
+    };
+
+    let star_channel_id = match settings.sender_channels.and_then(|x| x.quote) {
+        Some(star_channel_id) => star_channel_id,
+        None => return Ok(()),
+    };
 
     // Add timer calls
 
-    let star_channel_id = ChannelId(config_data.quote_id.unwrap() as u64);
     let star_channel = match ctx.cache.channel(star_channel_id) {
         Some(star_channel) => star_channel,
         None => {
@@ -78,7 +77,7 @@ pub async fn quote_reaction(ctx: &Context, reaction: &Reaction, remove: bool) ->
         return Ok(());
     }
 
-    if stars == config_data.starboard_threshold.unwrap() as u64 && !remove {
+    if stars == starboard_threshold as u64 && !remove {
         let first_message = format!(
             "\u{2b50} {} {} ID: {}",
             stars,
@@ -93,8 +92,11 @@ pub async fn quote_reaction(ctx: &Context, reaction: &Reaction, remove: bool) ->
             reaction_message.attachments,
         );
 
-        let guild_id = reaction.guild_id.unwrap();
-        if !check_starboard_message(&pool, guild_id, reaction_message.id).await? {
+        if repository
+            .fetch_starboard_message(guild_id, reaction_message.id)
+            .await?
+            .is_none()
+        {
             let sent_message = star_channel_id
                 .send_message(ctx, |m| {
                     m.content(first_message);
@@ -111,40 +113,35 @@ pub async fn quote_reaction(ctx: &Context, reaction: &Reaction, remove: bool) ->
                 .as_secs()
                 + 1210000;
 
-            sqlx::query!(
-                "INSERT INTO starboard VALUES($1, $2, $3, $4) ON CONFLICT DO NOTHING",
-                reaction.guild_id.unwrap().0 as i64,
-                reaction_message.id.0 as i64,
-                sent_message.id.0 as i64,
-                advance_time as i64
-            )
-            .execute(&pool)
-            .await?;
+            repository
+                .add_starboard_entry(StarboardEntry {
+                    guild_id,
+                    reaction_message_id: reaction_message.id,
+                    sent_message_id: sent_message.id,
+                    delete_time: advance_time as i64,
+                })
+                .await?;
         }
-    } else if (stars as i32) < config_data.starboard_threshold.unwrap() && remove {
-        let message_data = sqlx::query!("SELECT sent_message_id FROM starboard WHERE guild_id = $1 AND reaction_message_id = $2", 
-                reaction.guild_id.unwrap().0 as i64, reaction.message_id.0 as i64)
-            .fetch_optional(&pool).await?;
+    } else if (stars as i32) < starboard_threshold && remove {
+        let message_data = repository
+            .fetch_starboard_message(guild_id, reaction.message_id)
+            .await?;
 
-        if let Some(data) = message_data {
+        if let Some(sent_message_id) = message_data {
             ctx.http
-                .delete_message(star_channel_id.0 as u64, data.sent_message_id as u64)
+                .delete_message(star_channel_id.0, sent_message_id.0)
                 .await?;
 
-            sqlx::query!(
-                "DELETE FROM starboard WHERE guild_id = $1 and reaction_message_id = $2",
-                reaction.guild_id.unwrap().0 as i64,
-                reaction.message_id.0 as i64
-            )
-            .execute(&pool)
-            .await?;
+            repository
+                .remove_starboard_entry(guild_id, reaction.message_id)
+                .await?;
         }
-    } else if stars > config_data.starboard_threshold.unwrap() as u64 || remove {
-        let message_data = sqlx::query!("SELECT sent_message_id FROM starboard WHERE guild_id = $1 AND reaction_message_id = $2", 
-                reaction.guild_id.unwrap().0 as i64, reaction.message_id.0 as i64)
-            .fetch_optional(&pool).await?;
+    } else if stars > starboard_threshold as u64 || remove {
+        let message_data = repository
+            .fetch_starboard_message(guild_id, reaction.message_id)
+            .await?;
 
-        if let Some(data) = message_data {
+        if let Some(sent_message_id) = message_data {
             let first_message = format!(
                 "\u{2b50} {} {} ID: {}",
                 stars,
@@ -160,7 +157,7 @@ pub async fn quote_reaction(ctx: &Context, reaction: &Reaction, remove: bool) ->
 
             let mut sent_message = ctx
                 .http
-                .get_message(star_channel_id.0 as u64, data.sent_message_id as u64)
+                .get_message(star_channel_id.0, sent_message_id.0)
                 .await?;
             sent_message
                 .edit(ctx, |m| {
@@ -177,22 +174,6 @@ pub async fn quote_reaction(ctx: &Context, reaction: &Reaction, remove: bool) ->
     Ok(())
 }
 
-async fn check_starboard_message(
-    pool: &PgPool,
-    guild_id: GuildId,
-    reaction_msg_id: MessageId,
-) -> CommandResult<bool> {
-    let message_query = sqlx::query!(
-        "SELECT * FROM starboard WHERE guild_id=$1 AND reaction_message_id=$2",
-        guild_id.0 as i64,
-        reaction_msg_id.0 as i64
-    )
-    .fetch_optional(pool)
-    .await?;
-
-    Ok(message_query.is_some())
-}
-
 fn get_starboard_embed(
     reaction: &Reaction,
     user: &User,
@@ -0,0 +1,1032 @@
+use serenity::{
+    async_trait,
+    framework::standard::CommandResult,
+    model::id::{ChannelId, GuildId, MessageId, UserId},
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+};
+
+use super::{
+    AutomodRepository, AutomodRule, AutomodSettings, CommandRow, CommandRuleRepository,
+    CommandRules, CustomCommandRepository, GuildConfig, GuildSettingsRepository, ModCase,
+    ModerationRepository, SenderChannelRepository, SenderChannels, SenderKind, StarboardEntry,
+    StarboardRepository,
+};
+use crate::helpers::{
+    automod::{AutomodExemption, FilterList, RuleKind},
+    cooldowns::Cooldown,
+    custom_commands::{CustomKind, Requirement},
+};
+
+/*
+ * Keeps everything in memory, so command logic can be tested without a database
+ * Guilds have to be added first, like the foreign keys of the real tables
+ */
+#[derive(Default)]
+pub struct MemoryRepository {
+    guilds: Mutex<HashMap<GuildId, GuildData>>,
+}
+
+#[derive(Default)]
+struct GuildData {
+    prefixes: Vec<String>,
+    locale: Option<String>,
+    starboard_threshold: Option<i32>,
+    commands: BTreeMap<String, (CommandRow, i64)>,
+    aliases: BTreeMap<String, String>,
+    requirements: Vec<(String, Requirement)>,
+    sender_channels: Option<SenderChannels>,
+    starboard: BTreeMap<MessageId, StarboardEntry>,
+    cases: Vec<ModCase>,
+    automod: AutomodSettings,
+    command_rules: CommandRules,
+}
+
+impl MemoryRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Runs the closure on the guild's data, or fails if the guild was never added
+    fn with_guild<T>(
+        &self,
+        guild_id: GuildId,
+        f: impl FnOnce(&mut GuildData) -> T,
+    ) -> CommandResult<T> {
+        let mut guilds = self.guilds.lock().unwrap();
+
+        match guilds.get_mut(&guild_id) {
+            Some(guild) => Ok(f(guild)),
+            None => Err(format!("Guild {} doesn't exist!", guild_id.0).into()),
+        }
+    }
+
+    // Like with_guild, but a missing guild is treated as empty
+    fn read_guild<T: Default>(&self, guild_id: GuildId, f: impl FnOnce(&GuildData) -> T) -> T {
+        let guilds = self.guilds.lock().unwrap();
+
+        guilds.get(&guild_id).map(f).unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl GuildSettingsRepository for MemoryRepository {
+    async fn add_guild(&self, guild_id: GuildId) -> CommandResult {
+        self.guilds.lock().unwrap().entry(guild_id).or_default();
+
+        Ok(())
+    }
+
+    async fn remove_guild(&self, guild_id: GuildId) -> CommandResult {
+        self.guilds.lock().unwrap().remove(&guild_id);
+
+        Ok(())
+    }
+
+    async fn fetch_guild_ids(&self) -> CommandResult<Vec<GuildId>> {
+        Ok(self.guilds.lock().unwrap().keys().copied().collect())
+    }
+
+    async fn fetch_prefixes(&self, guild_id: GuildId) -> CommandResult<Vec<String>> {
+        Ok(self.read_guild(guild_id, |guild| guild.prefixes.clone()))
+    }
+
+    async fn set_prefixes(&self, guild_id: GuildId, prefixes: &[String]) -> CommandResult {
+        self.with_guild(guild_id, |guild| {
+            guild.prefixes = prefixes.to_vec();
+            guild.prefixes.sort();
+            guild.prefixes.dedup();
+        })
+    }
+
+    async fn fetch_locale(&self, guild_id: GuildId) -> CommandResult<Option<String>> {
+        Ok(self.read_guild(guild_id, |guild| guild.locale.clone()))
+    }
+
+    async fn set_locale(&self, guild_id: GuildId, locale: Option<&str>) -> CommandResult {
+        let _ = self.with_guild(guild_id, |guild| {
+            guild.locale = locale.map(|x| x.to_owned())
+        });
+
+        Ok(())
+    }
+
+    async fn fetch_starboard_threshold(&self, guild_id: GuildId) -> CommandResult<Option<i32>> {
+        Ok(self.read_guild(guild_id, |guild| guild.starboard_threshold))
+    }
+
+    async fn set_starboard_threshold(
+        &self,
+        guild_id: GuildId,
+        threshold: Option<i32>,
+    ) -> CommandResult {
+        let _ = self.with_guild(guild_id, |guild| guild.starboard_threshold = threshold);
+
+        Ok(())
+    }
+
+    async fn restore_guild(&self, guild_id: GuildId, config: &GuildConfig) -> CommandResult {
+        let mut guilds = self.guilds.lock().unwrap();
+        let guild = guilds.entry(guild_id).or_default();
+
+        guild.prefixes = config.prefixes.clone();
+        guild.prefixes.sort();
+        guild.prefixes.dedup();
+
+        guild.commands.clear();
+        guild.aliases.clear();
+        guild.requirements.clear();
+
+        for x in &config.commands {
+            let name = &x.command.name;
+
+            guild
+                .commands
+                .insert(name.to_owned(), (x.command.clone(), 0));
+
+            for alias in &x.aliases {
+                guild.aliases.insert(alias.to_owned(), name.to_owned());
+            }
+
+            for requirement in &x.requirements {
+                let entry = (name.to_owned(), *requirement);
+
+                if !guild.requirements.contains(&entry) {
+                    guild.requirements.push(entry);
+                }
+            }
+        }
+
+        guild.sender_channels = Some(config.sender_channels);
+        guild.starboard_threshold = config.starboard_threshold;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CustomCommandRepository for MemoryRepository {
+    async fn resolve_name(&self, guild_id: GuildId, alias: &str) -> CommandResult<Option<String>> {
+        Ok(self.read_guild(guild_id, |guild| guild.aliases.get(alias).cloned()))
+    }
+
+    async fn fetch_command(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+    ) -> CommandResult<Option<CommandRow>> {
+        Ok(self.read_guild(guild_id, |guild| {
+            guild.commands.get(name).map(|(row, _)| row.clone())
+        }))
+    }
+
+    async fn fetch_commands(&self, guild_id: GuildId) -> CommandResult<Vec<CommandRow>> {
+        Ok(self.read_guild(guild_id, |guild| {
+            guild
+                .commands
+                .values()
+                .map(|(row, _)| row.clone())
+                .collect()
+        }))
+    }
+
+    async fn save_command(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        content: &str,
+        kind: CustomKind,
+        embed: Option<&str>,
+        image_url: Option<&str>,
+    ) -> CommandResult {
+        self.with_guild(guild_id, |guild| {
+            let (row, _) = guild.commands.entry(name.to_owned()).or_insert_with(|| {
+                (
+                    CommandRow {
+                        name: name.to_owned(),
+                        content: None,
+                        kind: String::new(),
+                        embed: None,
+                        image_url: None,
+                        reply: false,
+                    },
+                    0,
+                )
+            });
+
+            row.content = Some(content.to_owned());
+            row.kind = kind.to_string();
+            row.embed = embed.map(|x| x.to_owned());
+            row.image_url = image_url.map(|x| x.to_owned());
+        })
+    }
+
+    async fn remove_command(&self, guild_id: GuildId, name: &str) -> CommandResult<bool> {
+        let removed = self.with_guild(guild_id, |guild| {
+            guild.aliases.retain(|_, command_name| command_name != name);
+            guild
+                .requirements
+                .retain(|(command_name, _)| command_name != name);
+
+            guild.commands.remove(name).is_some()
+        });
+
+        Ok(removed.unwrap_or(false))
+    }
+
+    async fn set_reply(&self, guild_id: GuildId, name: &str, enabled: bool) -> CommandResult<bool> {
+        let updated = self.with_guild(guild_id, |guild| match guild.commands.get_mut(name) {
+            Some((row, _)) => {
+                row.reply = enabled;
+                true
+            }
+            None => false,
+        });
+
+        Ok(updated.unwrap_or(false))
+    }
+
+    async fn increment_uses(&self, guild_id: GuildId, name: &str) -> CommandResult<i64> {
+        self.with_guild(guild_id, |guild| {
+            guild.commands.get_mut(name).map(|(_, uses)| {
+                *uses += 1;
+                *uses
+            })
+        })?
+        .ok_or_else(|| format!("Command {} doesn't exist!", name).into())
+    }
+
+    async fn fetch_aliases(&self, guild_id: GuildId) -> CommandResult<Vec<(String, String)>> {
+        Ok(self.read_guild(guild_id, |guild| {
+            guild
+                .aliases
+                .iter()
+                .map(|(alias, name)| (alias.to_owned(), name.to_owned()))
+                .collect()
+        }))
+    }
+
+    async fn save_alias(&self, guild_id: GuildId, alias: &str, name: &str) -> CommandResult {
+        self.with_guild(guild_id, |guild| -> CommandResult {
+            if guild.commands.contains_key(name) {
+                guild.aliases.insert(alias.to_owned(), name.to_owned());
+                Ok(())
+            } else {
+                Err(format!("Command {} doesn't exist!", name).into())
+            }
+        })?
+    }
+
+    async fn remove_alias(&self, guild_id: GuildId, alias: &str) -> CommandResult<bool> {
+        let removed = self.with_guild(guild_id, |guild| guild.aliases.remove(alias).is_some());
+
+        Ok(removed.unwrap_or(false))
+    }
+
+    async fn fetch_requirements(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+    ) -> CommandResult<Vec<Requirement>> {
+        Ok(self.read_guild(guild_id, |guild| {
+            guild
+                .requirements
+                .iter()
+                .filter(|(command_name, _)| command_name == name)
+                .map(|(_, requirement)| *requirement)
+                .collect()
+        }))
+    }
+
+    async fn fetch_restricted_commands(&self, guild_id: GuildId) -> CommandResult<Vec<String>> {
+        Ok(self.read_guild(guild_id, |guild| {
+            let mut names = guild
+                .requirements
+                .iter()
+                .map(|(command_name, _)| command_name.to_owned())
+                .collect::<Vec<String>>();
+            names.sort();
+            names.dedup();
+
+            names
+        }))
+    }
+
+    async fn add_requirement(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        requirement: Requirement,
+    ) -> CommandResult {
+        self.with_guild(guild_id, |guild| -> CommandResult {
+            if !guild.commands.contains_key(name) {
+                return Err(format!("Command {} doesn't exist!", name).into());
+            }
+
+            let entry = (name.to_owned(), requirement);
+
+            if !guild.requirements.contains(&entry) {
+                guild.requirements.push(entry);
+            }
+
+            Ok(())
+        })?
+    }
+
+    async fn remove_requirement(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        requirement: Option<Requirement>,
+    ) -> CommandResult {
+        let _ = self.with_guild(guild_id, |guild| {
+            guild.requirements.retain(|(command_name, x)| {
+                command_name != name || requirement.is_some_and(|requirement| *x != requirement)
+            })
+        });
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SenderChannelRepository for MemoryRepository {
+    async fn fetch_sender_channels(
+        &self,
+        guild_id: GuildId,
+    ) -> CommandResult<Option<SenderChannels>> {
+        Ok(self.read_guild(guild_id, |guild| guild.sender_channels))
+    }
+
+    async fn set_sender_channel(
+        &self,
+        guild_id: GuildId,
+        kind: SenderKind,
+        channel_id: Option<ChannelId>,
+    ) -> CommandResult {
+        self.with_guild(guild_id, |guild| {
+            // Clearing a channel doesn't set up the guild's channels
+            if guild.sender_channels.is_none() && channel_id.is_none() {
+                return;
+            }
+
+            let channels = guild
+                .sender_channels
+                .get_or_insert_with(SenderChannels::default);
+
+            match kind {
+                SenderKind::Nice => channels.nice = channel_id,
+                SenderKind::Bruh => channels.bruh = channel_id,
+                SenderKind::Quote => channels.quote = channel_id,
+                SenderKind::ModLog => channels.mod_log = channel_id,
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl StarboardRepository for MemoryRepository {
+    async fn fetch_starboard_entries(&self) -> CommandResult<Vec<StarboardEntry>> {
+        Ok(self
+            .guilds
+            .lock()
+            .unwrap()
+            .values()
+            .flat_map(|guild| guild.starboard.values().copied())
+            .collect())
+    }
+
+    async fn fetch_starboard_message(
+        &self,
+        guild_id: GuildId,
+        reaction_message_id: MessageId,
+    ) -> CommandResult<Option<MessageId>> {
+        Ok(self.read_guild(guild_id, |guild| {
+            guild
+                .starboard
+                .get(&reaction_message_id)
+                .map(|entry| entry.sent_message_id)
+        }))
+    }
+
+    async fn add_starboard_entry(&self, entry: StarboardEntry) -> CommandResult {
+        self.with_guild(entry.guild_id, |guild| {
+            guild
+                .starboard
+                .entry(entry.reaction_message_id)
+                .or_insert(entry);
+        })
+    }
+
+    async fn remove_starboard_entry(
+        &self,
+        guild_id: GuildId,
+        reaction_message_id: MessageId,
+    ) -> CommandResult {
+        let _ = self.with_guild(guild_id, |guild| {
+            guild.starboard.remove(&reaction_message_id)
+        });
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ModerationRepository for MemoryRepository {
+    async fn add_case(&self, mut case: ModCase) -> CommandResult<i64> {
+        self.with_guild(case.guild_id, |guild| {
+            case.case_number = guild.cases.len() as i64 + 1;
+            guild.cases.push(case);
+
+            guild.cases.len() as i64
+        })
+    }
+
+    async fn fetch_case(
+        &self,
+        guild_id: GuildId,
+        case_number: i64,
+    ) -> CommandResult<Option<ModCase>> {
+        Ok(self.read_guild(guild_id, |guild| {
+            guild
+                .cases
+                .iter()
+                .find(|x| x.case_number == case_number)
+                .cloned()
+        }))
+    }
+
+    async fn fetch_user_cases(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> CommandResult<Vec<ModCase>> {
+        Ok(self.read_guild(guild_id, |guild| {
+            guild
+                .cases
+                .iter()
+                .rev()
+                .filter(|x| x.user_id == user_id)
+                .cloned()
+                .collect()
+        }))
+    }
+
+    async fn set_case_reason(
+        &self,
+        guild_id: GuildId,
+        case_number: i64,
+        reason: &str,
+    ) -> CommandResult<bool> {
+        let updated = self.with_guild(guild_id, |guild| {
+            match guild
+                .cases
+                .iter_mut()
+                .find(|x| x.case_number == case_number)
+            {
+                Some(case) => {
+                    case.reason = Some(reason.to_owned());
+                    true
+                }
+                None => false,
+            }
+        });
+
+        Ok(updated.unwrap_or(false))
+    }
+}
+
+#[async_trait]
+impl AutomodRepository for MemoryRepository {
+    async fn fetch_automod_settings(&self, guild_id: GuildId) -> CommandResult<AutomodSettings> {
+        Ok(self.read_guild(guild_id, |guild| guild.automod.clone()))
+    }
+
+    async fn set_automod_rule(&self, guild_id: GuildId, rule: AutomodRule) -> CommandResult {
+        self.with_guild(guild_id, |guild| {
+            guild.automod.rules.retain(|x| x.kind != rule.kind);
+            guild.automod.rules.push(rule);
+        })
+    }
+
+    async fn remove_automod_rule(&self, guild_id: GuildId, kind: RuleKind) -> CommandResult<bool> {
+        let removed = self.with_guild(guild_id, |guild| {
+            let old_len = guild.automod.rules.len();
+            guild.automod.rules.retain(|x| x.kind != kind);
+
+            guild.automod.rules.len() != old_len
+        });
+
+        Ok(removed.unwrap_or(false))
+    }
+
+    async fn add_automod_filter(
+        &self,
+        guild_id: GuildId,
+        list: FilterList,
+        pattern: &str,
+    ) -> CommandResult<bool> {
+        self.with_guild(guild_id, |guild| {
+            let filter = (list, pattern.to_owned());
+
+            if guild.automod.filters.contains(&filter) {
+                false
+            } else {
+                guild.automod.filters.push(filter);
+                true
+            }
+        })
+    }
+
+    async fn remove_automod_filter(
+        &self,
+        guild_id: GuildId,
+        list: FilterList,
+        pattern: &str,
+    ) -> CommandResult<bool> {
+        let removed = self.with_guild(guild_id, |guild| {
+            let old_len = guild.automod.filters.len();
+            guild
+                .automod
+                .filters
+                .retain(|(x_list, x_pattern)| *x_list != list || x_pattern != pattern);
+
+            guild.automod.filters.len() != old_len
+        });
+
+        Ok(removed.unwrap_or(false))
+    }
+
+    async fn toggle_automod_exemption(
+        &self,
+        guild_id: GuildId,
+        exemption: AutomodExemption,
+    ) -> CommandResult<bool> {
+        self.with_guild(guild_id, |guild| {
+            let exemptions = &mut guild.automod.exemptions;
+
+            match exemptions.iter().position(|x| *x == exemption) {
+                Some(index) => {
+                    exemptions.remove(index);
+                    false
+                }
+                None => {
+                    exemptions.push(exemption);
+                    true
+                }
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl CommandRuleRepository for MemoryRepository {
+    async fn fetch_command_rules(&self, guild_id: GuildId) -> CommandResult<CommandRules> {
+        Ok(self.read_guild(guild_id, |guild| guild.command_rules.clone()))
+    }
+
+    async fn set_disabled(&self, guild_id: GuildId, name: &str, disabled: bool) -> CommandResult {
+        self.with_guild(guild_id, |guild| {
+            let rules = &mut guild.command_rules;
+            rules.disabled.retain(|x| x != name);
+
+            if disabled {
+                rules.disabled.push(name.to_owned());
+                rules.disabled.sort();
+            }
+        })
+    }
+
+    async fn add_command_channels(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        channels: &[ChannelId],
+    ) -> CommandResult {
+        self.with_guild(guild_id, |guild| {
+            let rules = &mut guild.command_rules;
+
+            for channel_id in channels {
+                let channel = (name.to_owned(), *channel_id);
+
+                if !rules.channels.contains(&channel) {
+                    rules.channels.push(channel);
+                }
+            }
+
+            rules.channels.sort();
+        })
+    }
+
+    async fn clear_command_channels(&self, guild_id: GuildId, name: &str) -> CommandResult {
+        self.with_guild(guild_id, |guild| {
+            guild.command_rules.channels.retain(|(x, _)| x != name);
+        })
+    }
+
+    async fn set_cooldown(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        cooldown: Option<Cooldown>,
+    ) -> CommandResult {
+        self.with_guild(guild_id, |guild| {
+            let rules = &mut guild.command_rules;
+            rules.cooldowns.retain(|(x, _)| x != name);
+
+            if let Some(cooldown) = cooldown {
+                rules.cooldowns.push((name.to_owned(), cooldown));
+                rules.cooldowns.sort_by(|a, b| a.0.cmp(&b.0));
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        helpers::{automod::AutomodAction, moderation::ModAction},
+        repositories::CommandConfig,
+    };
+    use serenity::model::{id::RoleId, Permissions};
+
+    const GUILD: GuildId = GuildId(1);
+
+    async fn setup() -> MemoryRepository {
+        let repository = MemoryRepository::new();
+        repository.add_guild(GUILD).await.unwrap();
+
+        repository
+    }
+
+    async fn save_text(repository: &MemoryRepository, name: &str, content: &str) {
+        repository
+            .save_command(GUILD, name, content, CustomKind::Text, None, None)
+            .await
+            .unwrap();
+    }
+
+    fn new_case(user_id: u64) -> ModCase {
+        ModCase {
+            guild_id: GUILD,
+            case_number: 0,
+            action: ModAction::Warn,
+            user_id: UserId(user_id),
+            moderator_id: UserId(10),
+            reason: None,
+            duration_secs: None,
+            created_at: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn writes_need_an_added_guild() {
+        let repository = MemoryRepository::new();
+
+        assert!(repository
+            .set_prefixes(GUILD, &["!".to_owned()])
+            .await
+            .is_err());
+        assert!(repository.fetch_prefixes(GUILD).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn prefixes_are_sorted_and_deduplicated() {
+        let repository = setup().await;
+
+        let prefixes = ["?", "!", "?"].map(|x| x.to_owned());
+        repository.set_prefixes(GUILD, &prefixes).await.unwrap();
+
+        assert_eq!(repository.fetch_prefixes(GUILD).await.unwrap(), ["!", "?"]);
+    }
+
+    #[tokio::test]
+    async fn removing_a_guild_removes_its_data() {
+        let repository = setup().await;
+        save_text(&repository, "hello", "hi").await;
+
+        repository.remove_guild(GUILD).await.unwrap();
+
+        assert!(repository.fetch_guild_ids().await.unwrap().is_empty());
+        assert!(repository.fetch_commands(GUILD).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn saving_a_command_keeps_uses_and_reply() {
+        let repository = setup().await;
+        save_text(&repository, "hello", "hi").await;
+
+        assert!(repository.set_reply(GUILD, "hello", true).await.unwrap());
+        assert_eq!(repository.increment_uses(GUILD, "hello").await.unwrap(), 1);
+
+        save_text(&repository, "hello", "hey").await;
+
+        let command = repository
+            .fetch_command(GUILD, "hello")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(command.content.as_deref(), Some("hey"));
+        assert!(command.reply);
+        assert_eq!(repository.increment_uses(GUILD, "hello").await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn missing_commands_are_reported() {
+        let repository = setup().await;
+
+        assert!(!repository.set_reply(GUILD, "hello", true).await.unwrap());
+        assert!(repository.increment_uses(GUILD, "hello").await.is_err());
+        assert!(repository.save_alias(GUILD, "hi", "hello").await.is_err());
+        assert!(repository
+            .add_requirement(GUILD, "hello", Requirement::Role(RoleId(2)))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn removing_a_command_removes_aliases_and_requirements() {
+        let repository = setup().await;
+        save_text(&repository, "hello", "hi").await;
+
+        repository.save_alias(GUILD, "hi", "hello").await.unwrap();
+        repository
+            .add_requirement(GUILD, "hello", Requirement::Role(RoleId(2)))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository
+                .resolve_name(GUILD, "hi")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("hello")
+        );
+        assert!(repository.remove_command(GUILD, "hello").await.unwrap());
+
+        assert!(repository
+            .resolve_name(GUILD, "hi")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(repository
+            .fetch_restricted_commands(GUILD)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(!repository.remove_command(GUILD, "hello").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn requirements_are_removed_one_or_all() {
+        let repository = setup().await;
+        save_text(&repository, "hello", "hi").await;
+
+        let role = Requirement::Role(RoleId(2));
+        let channel = Requirement::Channel(ChannelId(3));
+
+        for requirement in [role, channel, role] {
+            repository
+                .add_requirement(GUILD, "hello", requirement)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            repository.fetch_requirements(GUILD, "hello").await.unwrap(),
+            [role, channel]
+        );
+
+        repository
+            .remove_requirement(GUILD, "hello", Some(role))
+            .await
+            .unwrap();
+        assert_eq!(
+            repository.fetch_requirements(GUILD, "hello").await.unwrap(),
+            [channel]
+        );
+
+        repository
+            .remove_requirement(GUILD, "hello", None)
+            .await
+            .unwrap();
+        assert!(repository
+            .fetch_requirements(GUILD, "hello")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn clearing_a_channel_doesnt_set_up_channels() {
+        let repository = setup().await;
+
+        repository
+            .set_sender_channel(GUILD, SenderKind::Nice, None)
+            .await
+            .unwrap();
+        assert!(repository
+            .fetch_sender_channels(GUILD)
+            .await
+            .unwrap()
+            .is_none());
+
+        repository
+            .set_sender_channel(GUILD, SenderKind::Quote, Some(ChannelId(4)))
+            .await
+            .unwrap();
+
+        let channels = repository
+            .fetch_sender_channels(GUILD)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(channels.quote, Some(ChannelId(4)));
+        assert_eq!(channels.nice, None);
+    }
+
+    #[tokio::test]
+    async fn starboard_keeps_the_first_entry() {
+        let repository = setup().await;
+
+        let entry = |sent_message_id| StarboardEntry {
+            guild_id: GUILD,
+            reaction_message_id: MessageId(5),
+            sent_message_id: MessageId(sent_message_id),
+            delete_time: 0,
+        };
+
+        repository.add_starboard_entry(entry(6)).await.unwrap();
+        repository.add_starboard_entry(entry(7)).await.unwrap();
+
+        assert_eq!(
+            repository
+                .fetch_starboard_message(GUILD, MessageId(5))
+                .await
+                .unwrap(),
+            Some(MessageId(6))
+        );
+
+        repository
+            .remove_starboard_entry(GUILD, MessageId(5))
+            .await
+            .unwrap();
+        assert!(repository
+            .fetch_starboard_entries()
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn cases_are_numbered_per_guild() {
+        let repository = setup().await;
+
+        assert_eq!(repository.add_case(new_case(2)).await.unwrap(), 1);
+        assert_eq!(repository.add_case(new_case(3)).await.unwrap(), 2);
+        assert_eq!(repository.add_case(new_case(2)).await.unwrap(), 3);
+
+        let numbers = repository
+            .fetch_user_cases(GUILD, UserId(2))
+            .await
+            .unwrap()
+            .iter()
+            .map(|x| x.case_number)
+            .collect::<Vec<i64>>();
+        assert_eq!(numbers, [3, 1]);
+
+        assert!(repository.set_case_reason(GUILD, 2, "spam").await.unwrap());
+        assert!(!repository.set_case_reason(GUILD, 4, "spam").await.unwrap());
+
+        let case = repository.fetch_case(GUILD, 2).await.unwrap().unwrap();
+        assert_eq!(case.reason.as_deref(), Some("spam"));
+    }
+
+    #[tokio::test]
+    async fn automod_rules_replace_the_same_kind() {
+        let repository = setup().await;
+
+        for action in [AutomodAction::Delete, AutomodAction::Warn] {
+            let rule = AutomodRule {
+                kind: RuleKind::Invites,
+                action,
+                threshold: None,
+                interval_secs: None,
+                timeout_secs: None,
+            };
+
+            repository.set_automod_rule(GUILD, rule).await.unwrap();
+        }
+
+        let settings = repository.fetch_automod_settings(GUILD).await.unwrap();
+        assert_eq!(settings.rules.len(), 1);
+        assert_eq!(settings.rules[0].action, AutomodAction::Warn);
+
+        assert!(repository
+            .remove_automod_rule(GUILD, RuleKind::Invites)
+            .await
+            .unwrap());
+        assert!(!repository
+            .remove_automod_rule(GUILD, RuleKind::Invites)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn automod_filters_and_exemptions() {
+        let repository = setup().await;
+
+        assert!(repository
+            .add_automod_filter(GUILD, FilterList::Words, "bad")
+            .await
+            .unwrap());
+        assert!(!repository
+            .add_automod_filter(GUILD, FilterList::Words, "bad")
+            .await
+            .unwrap());
+        assert!(repository
+            .remove_automod_filter(GUILD, FilterList::Words, "bad")
+            .await
+            .unwrap());
+
+        let exemption = AutomodExemption::Channel(ChannelId(8));
+
+        assert!(repository
+            .toggle_automod_exemption(GUILD, exemption)
+            .await
+            .unwrap());
+        assert!(!repository
+            .toggle_automod_exemption(GUILD, exemption)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn restoring_replaces_the_configuration() {
+        let repository = setup().await;
+        save_text(&repository, "old", "gone").await;
+        repository.save_alias(GUILD, "o", "old").await.unwrap();
+
+        let requirement = Requirement::Permission(Permissions::MANAGE_MESSAGES);
+        let config = GuildConfig {
+            prefixes: vec!["?".to_owned(), "!".to_owned()],
+            commands: vec![CommandConfig {
+                command: CommandRow {
+                    name: "new".to_owned(),
+                    content: Some("here".to_owned()),
+                    kind: CustomKind::Text.to_string(),
+                    embed: None,
+                    image_url: None,
+                    reply: true,
+                },
+                aliases: vec!["n".to_owned()],
+                requirements: vec![requirement],
+            }],
+            sender_channels: SenderChannels {
+                mod_log: Some(ChannelId(9)),
+                ..Default::default()
+            },
+            starboard_threshold: Some(3),
+        };
+
+        repository.restore_guild(GUILD, &config).await.unwrap();
+
+        let names = repository
+            .fetch_commands(GUILD)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|x| x.name)
+            .collect::<Vec<String>>();
+        assert_eq!(names, ["new"]);
+
+        assert_eq!(
+            repository.fetch_aliases(GUILD).await.unwrap(),
+            [("n".to_owned(), "new".to_owned())]
+        );
+        assert_eq!(
+            repository.fetch_requirements(GUILD, "new").await.unwrap(),
+            [requirement]
+        );
+        assert_eq!(repository.fetch_prefixes(GUILD).await.unwrap(), ["!", "?"]);
+        assert_eq!(
+            repository.fetch_starboard_threshold(GUILD).await.unwrap(),
+            Some(3)
+        );
+        assert_eq!(
+            repository
+                .fetch_sender_channels(GUILD)
+                .await
+                .unwrap()
+                .unwrap()
+                .mod_log,
+            Some(ChannelId(9))
+        );
+    }
+}
@@ -0,0 +1,369 @@
+#[cfg(test)]
+pub mod memory;
+pub mod sql;
+
+use serenity::{
+    async_trait,
+    framework::standard::CommandResult,
+    model::id::{ChannelId, GuildId, MessageId, UserId},
+};
+use sqlx::FromRow;
+
+use crate::helpers::{
+    automod::{AutomodAction, AutomodExemption, FilterList, RuleKind},
+    cooldowns::Cooldown,
+    custom_commands::{CustomKind, Requirement},
+    moderation::ModAction,
+};
+
+/*
+ * All guild data goes through these traits instead of inline SQL
+ * The SQL repository is used by the bot, and the memory repository lets command logic run without a database
+ */
+pub trait Repository:
+    GuildSettingsRepository
+    + CustomCommandRepository
+    + SenderChannelRepository
+    + StarboardRepository
+    + ModerationRepository
+    + AutomodRepository
+    + CommandRuleRepository
+{
+}
+
+impl<T> Repository for T where
+    T: GuildSettingsRepository
+        + CustomCommandRepository
+        + SenderChannelRepository
+        + StarboardRepository
+        + ModerationRepository
+        + AutomodRepository
+        + CommandRuleRepository
+{
+}
+
+// Everything a backup restores, with channels and roles from other servers already left out
+#[derive(Clone)]
+pub struct GuildConfig {
+    pub prefixes: Vec<String>,
+    pub commands: Vec<CommandConfig>,
+    pub sender_channels: SenderChannels,
+    pub starboard_threshold: Option<i32>,
+}
+
+#[derive(Clone)]
+pub struct CommandConfig {
+    pub command: CommandRow,
+    pub aliases: Vec<String>,
+    pub requirements: Vec<Requirement>,
+}
+
+#[async_trait]
+pub trait GuildSettingsRepository: Send + Sync {
+    async fn add_guild(&self, guild_id: GuildId) -> CommandResult;
+
+    // Everything stored for the guild is removed with it
+    async fn remove_guild(&self, guild_id: GuildId) -> CommandResult;
+
+    async fn fetch_guild_ids(&self) -> CommandResult<Vec<GuildId>>;
+
+    // Sorted, and empty if the guild uses the default prefix
+    async fn fetch_prefixes(&self, guild_id: GuildId) -> CommandResult<Vec<String>>;
+
+    // An empty list removes the custom prefixes
+    async fn set_prefixes(&self, guild_id: GuildId, prefixes: &[String]) -> CommandResult;
+
+    // None if the guild uses the default locale
+    async fn fetch_locale(&self, guild_id: GuildId) -> CommandResult<Option<String>>;
+
+    async fn set_locale(&self, guild_id: GuildId, locale: Option<&str>) -> CommandResult;
+
+    async fn fetch_starboard_threshold(&self, guild_id: GuildId) -> CommandResult<Option<i32>>;
+
+    async fn set_starboard_threshold(
+        &self,
+        guild_id: GuildId,
+        threshold: Option<i32>,
+    ) -> CommandResult;
+
+    /*
+     * Replaces the guild's prefixes, custom commands, sender channels, and starboard threshold
+     * Nothing is changed if any part of the restore fails
+     */
+    async fn restore_guild(&self, guild_id: GuildId, config: &GuildConfig) -> CommandResult;
+}
+
+#[derive(Clone, FromRow)]
+pub struct CommandRow {
+    pub name: String,
+    pub content: Option<String>,
+    pub kind: String,
+    pub embed: Option<String>,
+    pub image_url: Option<String>,
+    pub reply: bool,
+}
+
+// Names are always a command's real name. Aliases are resolved with resolve_name first
+#[async_trait]
+pub trait CustomCommandRepository: Send + Sync {
+    async fn resolve_name(&self, guild_id: GuildId, alias: &str) -> CommandResult<Option<String>>;
+
+    async fn fetch_command(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+    ) -> CommandResult<Option<CommandRow>>;
+
+    // Sorted by name
+    async fn fetch_commands(&self, guild_id: GuildId) -> CommandResult<Vec<CommandRow>>;
+
+    /*
+     * Setting a command replaces its content and type
+     * The use counter, reply setting, and aliases stay the same
+     */
+    async fn save_command(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        content: &str,
+        kind: CustomKind,
+        embed: Option<&str>,
+        image_url: Option<&str>,
+    ) -> CommandResult;
+
+    // Aliases and requirements are removed with the command
+    async fn remove_command(&self, guild_id: GuildId, name: &str) -> CommandResult<bool>;
+
+    async fn set_reply(&self, guild_id: GuildId, name: &str, enabled: bool) -> CommandResult<bool>;
+
+    // Returns the command's uses including this one
+    async fn increment_uses(&self, guild_id: GuildId, name: &str) -> CommandResult<i64>;
+
+    // Pairs of (alias, name) sorted by alias
+    async fn fetch_aliases(&self, guild_id: GuildId) -> CommandResult<Vec<(String, String)>>;
+
+    async fn save_alias(&self, guild_id: GuildId, alias: &str, name: &str) -> CommandResult;
+
+    async fn remove_alias(&self, guild_id: GuildId, alias: &str) -> CommandResult<bool>;
+
+    async fn fetch_requirements(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+    ) -> CommandResult<Vec<Requirement>>;
+
+    // Names of the commands with at least one requirement
+    async fn fetch_restricted_commands(&self, guild_id: GuildId) -> CommandResult<Vec<String>>;
+
+    async fn add_requirement(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        requirement: Requirement,
+    ) -> CommandResult;
+
+    // Without a requirement, every requirement of the command is removed
+    async fn remove_requirement(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        requirement: Option<Requirement>,
+    ) -> CommandResult;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SenderKind {
+    Nice,
+    Bruh,
+    Quote,
+    ModLog,
+}
+
+impl SenderKind {
+    pub fn column(&self) -> &'static str {
+        match *self {
+            SenderKind::Nice => "nice_id",
+            SenderKind::Bruh => "bruh_id",
+            SenderKind::Quote => "quote_id",
+            SenderKind::ModLog => "mod_log_id",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SenderChannels {
+    pub nice: Option<ChannelId>,
+    pub bruh: Option<ChannelId>,
+    pub quote: Option<ChannelId>,
+    pub mod_log: Option<ChannelId>,
+}
+
+#[async_trait]
+pub trait SenderChannelRepository: Send + Sync {
+    // None if no channel was ever set up for the guild
+    async fn fetch_sender_channels(
+        &self,
+        guild_id: GuildId,
+    ) -> CommandResult<Option<SenderChannels>>;
+
+    async fn set_sender_channel(
+        &self,
+        guild_id: GuildId,
+        kind: SenderKind,
+        channel_id: Option<ChannelId>,
+    ) -> CommandResult;
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct StarboardEntry {
+    pub guild_id: GuildId,
+    pub reaction_message_id: MessageId,
+    pub sent_message_id: MessageId,
+    pub delete_time: i64,
+}
+
+#[async_trait]
+pub trait StarboardRepository: Send + Sync {
+    async fn fetch_starboard_entries(&self) -> CommandResult<Vec<StarboardEntry>>;
+
+    // Returns the starboard message sent for the reacted message
+    async fn fetch_starboard_message(
+        &self,
+        guild_id: GuildId,
+        reaction_message_id: MessageId,
+    ) -> CommandResult<Option<MessageId>>;
+
+    // An existing entry for the same message is kept
+    async fn add_starboard_entry(&self, entry: StarboardEntry) -> CommandResult;
+
+    async fn remove_starboard_entry(
+        &self,
+        guild_id: GuildId,
+        reaction_message_id: MessageId,
+    ) -> CommandResult;
+}
+
+#[derive(Clone, Debug)]
+pub struct ModCase {
+    pub guild_id: GuildId,
+    pub case_number: i64,
+    pub action: ModAction,
+    pub user_id: UserId,
+    pub moderator_id: UserId,
+    pub reason: Option<String>,
+    pub duration_secs: Option<i64>,
+    pub created_at: i64,
+}
+
+// Case numbers count up from 1 in every guild
+#[async_trait]
+pub trait ModerationRepository: Send + Sync {
+    // The case's number is ignored, and the number it was saved with is returned
+    async fn add_case(&self, case: ModCase) -> CommandResult<i64>;
+
+    async fn fetch_case(
+        &self,
+        guild_id: GuildId,
+        case_number: i64,
+    ) -> CommandResult<Option<ModCase>>;
+
+    // Sorted with the newest case first
+    async fn fetch_user_cases(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> CommandResult<Vec<ModCase>>;
+
+    async fn set_case_reason(
+        &self,
+        guild_id: GuildId,
+        case_number: i64,
+        reason: &str,
+    ) -> CommandResult<bool>;
+}
+
+// Thresholds and intervals fall back to the rule kind's defaults when they're None
+#[derive(Clone, Debug)]
+pub struct AutomodRule {
+    pub kind: RuleKind,
+    pub action: AutomodAction,
+    pub threshold: Option<i64>,
+    pub interval_secs: Option<i64>,
+    pub timeout_secs: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AutomodSettings {
+    pub rules: Vec<AutomodRule>,
+    pub filters: Vec<(FilterList, String)>,
+    pub exemptions: Vec<AutomodExemption>,
+}
+
+// Each rule kind can only be set once per guild
+#[async_trait]
+pub trait AutomodRepository: Send + Sync {
+    async fn fetch_automod_settings(&self, guild_id: GuildId) -> CommandResult<AutomodSettings>;
+
+    // Replaces the guild's rule of the same kind
+    async fn set_automod_rule(&self, guild_id: GuildId, rule: AutomodRule) -> CommandResult;
+
+    async fn remove_automod_rule(&self, guild_id: GuildId, kind: RuleKind) -> CommandResult<bool>;
+
+    // False if the pattern was already in the list
+    async fn add_automod_filter(
+        &self,
+        guild_id: GuildId,
+        list: FilterList,
+        pattern: &str,
+    ) -> CommandResult<bool>;
+
+    async fn remove_automod_filter(
+        &self,
+        guild_id: GuildId,
+        list: FilterList,
+        pattern: &str,
+    ) -> CommandResult<bool>;
+
+    // Returns true if the role or channel is now exempt, or false if its exemption was removed
+    async fn toggle_automod_exemption(
+        &self,
+        guild_id: GuildId,
+        exemption: AutomodExemption,
+    ) -> CommandResult<bool>;
+}
+
+// Names are top level command names or group keys
+#[derive(Clone, Debug, Default)]
+pub struct CommandRules {
+    // Sorted
+    pub disabled: Vec<String>,
+    // Sorted by name
+    pub channels: Vec<(String, ChannelId)>,
+    // Sorted by name. An override of 0 seconds turns the command's cooldown off
+    pub cooldowns: Vec<(String, Cooldown)>,
+}
+
+#[async_trait]
+pub trait CommandRuleRepository: Send + Sync {
+    async fn fetch_command_rules(&self, guild_id: GuildId) -> CommandResult<CommandRules>;
+
+    async fn set_disabled(&self, guild_id: GuildId, name: &str, disabled: bool) -> CommandResult;
+
+    // The channels are added to any the name is already restricted to
+    async fn add_command_channels(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        channels: &[ChannelId],
+    ) -> CommandResult;
+
+    async fn clear_command_channels(&self, guild_id: GuildId, name: &str) -> CommandResult;
+
+    // None resets the command to its default cooldown
+    async fn set_cooldown(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        cooldown: Option<Cooldown>,
+    ) -> CommandResult;
+}
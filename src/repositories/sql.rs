@@ -0,0 +1,1124 @@
+use serenity::{
+    async_trait,
+    framework::standard::CommandResult,
+    model::id::{ChannelId, GuildId, MessageId, UserId},
+};
+use sqlx::{AnyPool, FromRow};
+
+use super::{
+    AutomodRepository, AutomodRule, AutomodSettings, CommandRow, CommandRuleRepository,
+    CommandRules, CustomCommandRepository, GuildConfig, GuildSettingsRepository, ModCase,
+    ModerationRepository, SenderChannelRepository, SenderChannels, SenderKind, StarboardEntry,
+    StarboardRepository,
+};
+use crate::helpers::{
+    automod::{AutomodExemption, FilterList, RuleKind},
+    cooldowns::Cooldown,
+    custom_commands::{CustomKind, Requirement},
+};
+
+// Works with both Postgres and SQLite through the connection pool
+pub struct SqlRepository {
+    pool: AnyPool,
+}
+
+impl SqlRepository {
+    pub fn new(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(FromRow)]
+struct TextChannelRow {
+    nice_id: Option<i64>,
+    bruh_id: Option<i64>,
+    quote_id: Option<i64>,
+    mod_log_id: Option<i64>,
+}
+
+#[derive(FromRow)]
+struct StarboardRow {
+    guild_id: i64,
+    reaction_message_id: i64,
+    sent_message_id: i64,
+    delete_time: i64,
+}
+
+#[derive(FromRow)]
+struct ModCaseRow {
+    guild_id: i64,
+    case_number: i64,
+    action: String,
+    user_id: i64,
+    moderator_id: i64,
+    reason: Option<String>,
+    duration_secs: Option<i64>,
+    created_at: i64,
+}
+
+impl TryFrom<ModCaseRow> for ModCase {
+    type Error = String;
+
+    fn try_from(row: ModCaseRow) -> Result<Self, Self::Error> {
+        Ok(ModCase {
+            guild_id: GuildId(row.guild_id as u64),
+            case_number: row.case_number,
+            action: row.action.parse()?,
+            user_id: UserId(row.user_id as u64),
+            moderator_id: UserId(row.moderator_id as u64),
+            reason: row.reason,
+            duration_secs: row.duration_secs,
+            created_at: row.created_at,
+        })
+    }
+}
+
+#[derive(FromRow)]
+struct AutomodRuleRow {
+    kind: String,
+    action: String,
+    threshold: Option<i64>,
+    interval_secs: Option<i64>,
+    timeout_secs: Option<i64>,
+}
+
+impl TryFrom<AutomodRuleRow> for AutomodRule {
+    type Error = String;
+
+    fn try_from(row: AutomodRuleRow) -> Result<Self, Self::Error> {
+        Ok(AutomodRule {
+            kind: row.kind.parse()?,
+            action: row.action.parse()?,
+            threshold: row.threshold,
+            interval_secs: row.interval_secs,
+            timeout_secs: row.timeout_secs,
+        })
+    }
+}
+
+#[async_trait]
+impl GuildSettingsRepository for SqlRepository {
+    async fn add_guild(&self, guild_id: GuildId) -> CommandResult {
+        sqlx::query("INSERT INTO guild_info(guild_id) VALUES($1) ON CONFLICT DO NOTHING")
+            .bind(guild_id.0 as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_guild(&self, guild_id: GuildId) -> CommandResult {
+        sqlx::query("DELETE FROM guild_info WHERE guild_id = $1")
+            .bind(guild_id.0 as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_guild_ids(&self) -> CommandResult<Vec<GuildId>> {
+        let guild_data = sqlx::query_scalar::<_, i64>("SELECT guild_id FROM guild_info")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(guild_data
+            .into_iter()
+            .map(|x| GuildId::from(x as u64))
+            .collect())
+    }
+
+    async fn fetch_prefixes(&self, guild_id: GuildId) -> CommandResult<Vec<String>> {
+        let prefixes = sqlx::query_scalar::<_, String>(
+            "SELECT prefix FROM guild_prefixes WHERE guild_id = $1 ORDER BY prefix",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(prefixes)
+    }
+
+    async fn set_prefixes(&self, guild_id: GuildId, prefixes: &[String]) -> CommandResult {
+        let mut transaction = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM guild_prefixes WHERE guild_id = $1")
+            .bind(guild_id.0 as i64)
+            .execute(&mut transaction)
+            .await?;
+
+        for prefix in prefixes {
+            sqlx::query("INSERT INTO guild_prefixes(guild_id, prefix) VALUES($1, $2)")
+                .bind(guild_id.0 as i64)
+                .bind(prefix)
+                .execute(&mut transaction)
+                .await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn fetch_locale(&self, guild_id: GuildId) -> CommandResult<Option<String>> {
+        let locale = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT locale FROM guild_info WHERE guild_id = $1",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_optional(&self.pool)
+        .await?
+        .flatten();
+
+        Ok(locale)
+    }
+
+    async fn set_locale(&self, guild_id: GuildId, locale: Option<&str>) -> CommandResult {
+        sqlx::query("UPDATE guild_info SET locale = $1 WHERE guild_id = $2")
+            .bind(locale)
+            .bind(guild_id.0 as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_starboard_threshold(&self, guild_id: GuildId) -> CommandResult<Option<i32>> {
+        let threshold = sqlx::query_scalar::<_, Option<i32>>(
+            "SELECT starboard_threshold FROM guild_info WHERE guild_id = $1",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(threshold.flatten())
+    }
+
+    async fn set_starboard_threshold(
+        &self,
+        guild_id: GuildId,
+        threshold: Option<i32>,
+    ) -> CommandResult {
+        sqlx::query("UPDATE guild_info SET starboard_threshold = $1 WHERE guild_id = $2")
+            .bind(threshold)
+            .bind(guild_id.0 as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn restore_guild(&self, guild_id: GuildId, config: &GuildConfig) -> CommandResult {
+        let mut transaction = self.pool.begin().await?;
+
+        sqlx::query("INSERT INTO guild_info(guild_id) VALUES($1) ON CONFLICT DO NOTHING")
+            .bind(guild_id.0 as i64)
+            .execute(&mut transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM guild_prefixes WHERE guild_id = $1")
+            .bind(guild_id.0 as i64)
+            .execute(&mut transaction)
+            .await?;
+
+        for prefix in &config.prefixes {
+            sqlx::query("INSERT INTO guild_prefixes(guild_id, prefix) VALUES($1, $2)")
+                .bind(guild_id.0 as i64)
+                .bind(prefix)
+                .execute(&mut transaction)
+                .await?;
+        }
+
+        // Aliases and requirements are removed with their commands
+        sqlx::query("DELETE FROM commands WHERE guild_id = $1")
+            .bind(guild_id.0 as i64)
+            .execute(&mut transaction)
+            .await?;
+
+        for x in &config.commands {
+            let command = &x.command;
+
+            sqlx::query(
+                "INSERT INTO commands(guild_id, name, content, kind, embed, image_url, reply)
+                    VALUES($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .bind(guild_id.0 as i64)
+            .bind(&command.name)
+            .bind(command.content.as_deref())
+            .bind(&command.kind)
+            .bind(command.embed.as_deref())
+            .bind(command.image_url.as_deref())
+            .bind(command.reply)
+            .execute(&mut transaction)
+            .await?;
+
+            for alias in &x.aliases {
+                sqlx::query(
+                    "INSERT INTO command_aliases(guild_id, alias, name) VALUES($1, $2, $3)",
+                )
+                .bind(guild_id.0 as i64)
+                .bind(alias)
+                .bind(&command.name)
+                .execute(&mut transaction)
+                .await?;
+            }
+
+            for requirement in &x.requirements {
+                sqlx::query(
+                    "INSERT INTO command_requirements(guild_id, name, kind, value)
+                        VALUES($1, $2, $3, $4)
+                        ON CONFLICT DO NOTHING",
+                )
+                .bind(guild_id.0 as i64)
+                .bind(&command.name)
+                .bind(requirement.kind())
+                .bind(requirement.value())
+                .execute(&mut transaction)
+                .await?;
+            }
+        }
+
+        let channels = &config.sender_channels;
+
+        sqlx::query(
+            "INSERT INTO text_channels(guild_id, nice_id, bruh_id, quote_id, mod_log_id)
+                VALUES($1, $2, $3, $4, $5)
+                ON CONFLICT (guild_id)
+                DO UPDATE SET nice_id = $2, bruh_id = $3, quote_id = $4, mod_log_id = $5",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(channels.nice.map(|x| x.0 as i64))
+        .bind(channels.bruh.map(|x| x.0 as i64))
+        .bind(channels.quote.map(|x| x.0 as i64))
+        .bind(channels.mod_log.map(|x| x.0 as i64))
+        .execute(&mut transaction)
+        .await?;
+
+        sqlx::query("UPDATE guild_info SET starboard_threshold = $1 WHERE guild_id = $2")
+            .bind(config.starboard_threshold)
+            .bind(guild_id.0 as i64)
+            .execute(&mut transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CustomCommandRepository for SqlRepository {
+    async fn resolve_name(&self, guild_id: GuildId, alias: &str) -> CommandResult<Option<String>> {
+        let alias_data = sqlx::query_scalar::<_, String>(
+            "SELECT name FROM command_aliases WHERE guild_id = $1 AND alias = $2",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(alias)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(alias_data)
+    }
+
+    async fn fetch_command(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+    ) -> CommandResult<Option<CommandRow>> {
+        let cmd_data = sqlx::query_as::<_, CommandRow>(
+            "SELECT name, content, kind, embed, image_url, reply FROM commands WHERE guild_id = $1 AND name = $2",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(cmd_data)
+    }
+
+    async fn fetch_commands(&self, guild_id: GuildId) -> CommandResult<Vec<CommandRow>> {
+        let command_data = sqlx::query_as::<_, CommandRow>(
+            "SELECT name, content, kind, embed, image_url, reply FROM commands WHERE guild_id = $1 ORDER BY name",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(command_data)
+    }
+
+    async fn save_command(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        content: &str,
+        kind: CustomKind,
+        embed: Option<&str>,
+        image_url: Option<&str>,
+    ) -> CommandResult {
+        sqlx::query(
+            "INSERT INTO commands(guild_id, name, content, kind, embed, image_url)
+                VALUES($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (guild_id, name)
+                DO UPDATE
+                SET content = EXCLUDED.content, kind = EXCLUDED.kind, embed = EXCLUDED.embed, image_url = EXCLUDED.image_url",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(name)
+        .bind(content)
+        .bind(kind.to_string())
+        .bind(embed)
+        .bind(image_url)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_command(&self, guild_id: GuildId, name: &str) -> CommandResult<bool> {
+        let result = sqlx::query("DELETE FROM commands WHERE guild_id = $1 AND name = $2")
+            .bind(guild_id.0 as i64)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn set_reply(&self, guild_id: GuildId, name: &str, enabled: bool) -> CommandResult<bool> {
+        let result =
+            sqlx::query("UPDATE commands SET reply = $1 WHERE guild_id = $2 AND name = $3")
+                .bind(enabled)
+                .bind(guild_id.0 as i64)
+                .bind(name)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn increment_uses(&self, guild_id: GuildId, name: &str) -> CommandResult<i64> {
+        let uses = sqlx::query_scalar::<_, i64>(
+            "UPDATE commands SET uses = uses + 1 WHERE guild_id = $1 AND name = $2 RETURNING uses",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(name)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(uses)
+    }
+
+    async fn fetch_aliases(&self, guild_id: GuildId) -> CommandResult<Vec<(String, String)>> {
+        let alias_data = sqlx::query_as::<_, (String, String)>(
+            "SELECT alias, name FROM command_aliases WHERE guild_id = $1 ORDER BY alias",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(alias_data)
+    }
+
+    async fn save_alias(&self, guild_id: GuildId, alias: &str, name: &str) -> CommandResult {
+        sqlx::query(
+            "INSERT INTO command_aliases(guild_id, alias, name)
+                VALUES($1, $2, $3)
+                ON CONFLICT (guild_id, alias)
+                DO UPDATE
+                SET name = EXCLUDED.name",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(alias)
+        .bind(name)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_alias(&self, guild_id: GuildId, alias: &str) -> CommandResult<bool> {
+        let result = sqlx::query("DELETE FROM command_aliases WHERE guild_id = $1 AND alias = $2")
+            .bind(guild_id.0 as i64)
+            .bind(alias)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn fetch_requirements(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+    ) -> CommandResult<Vec<Requirement>> {
+        let requirement_data = sqlx::query_as::<_, (String, i64)>(
+            "SELECT kind, value FROM command_requirements WHERE guild_id = $1 AND name = $2",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(requirement_data
+            .into_iter()
+            .filter_map(|(kind, value)| Requirement::from_row(&kind, value))
+            .collect())
+    }
+
+    async fn fetch_restricted_commands(&self, guild_id: GuildId) -> CommandResult<Vec<String>> {
+        let requirement_data = sqlx::query_scalar::<_, String>(
+            "SELECT DISTINCT name FROM command_requirements WHERE guild_id = $1",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(requirement_data)
+    }
+
+    async fn add_requirement(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        requirement: Requirement,
+    ) -> CommandResult {
+        sqlx::query(
+            "INSERT INTO command_requirements(guild_id, name, kind, value)
+                VALUES($1, $2, $3, $4)
+                ON CONFLICT DO NOTHING",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(name)
+        .bind(requirement.kind())
+        .bind(requirement.value())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_requirement(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        requirement: Option<Requirement>,
+    ) -> CommandResult {
+        match requirement {
+            Some(requirement) => {
+                sqlx::query(
+                    "DELETE FROM command_requirements WHERE guild_id = $1 AND name = $2 AND kind = $3 AND value = $4",
+                )
+                .bind(guild_id.0 as i64)
+                .bind(name)
+                .bind(requirement.kind())
+                .bind(requirement.value())
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM command_requirements WHERE guild_id = $1 AND name = $2")
+                    .bind(guild_id.0 as i64)
+                    .bind(name)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SenderChannelRepository for SqlRepository {
+    async fn fetch_sender_channels(
+        &self,
+        guild_id: GuildId,
+    ) -> CommandResult<Option<SenderChannels>> {
+        let channel_data = sqlx::query_as::<_, TextChannelRow>(
+            "SELECT nice_id, bruh_id, quote_id, mod_log_id FROM text_channels WHERE guild_id = $1",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(channel_data.map(|x| SenderChannels {
+            nice: x.nice_id.map(|id| ChannelId(id as u64)),
+            bruh: x.bruh_id.map(|id| ChannelId(id as u64)),
+            quote: x.quote_id.map(|id| ChannelId(id as u64)),
+            mod_log: x.mod_log_id.map(|id| ChannelId(id as u64)),
+        }))
+    }
+
+    // Clearing a channel never creates the guild's row
+    async fn set_sender_channel(
+        &self,
+        guild_id: GuildId,
+        kind: SenderKind,
+        channel_id: Option<ChannelId>,
+    ) -> CommandResult {
+        let column = kind.column();
+
+        match channel_id {
+            Some(channel_id) => {
+                sqlx::query(&format!(
+                    "INSERT INTO text_channels(guild_id, {0}) VALUES($1, $2)
+                        ON CONFLICT (guild_id)
+                        DO UPDATE SET {0} = EXCLUDED.{0}",
+                    column
+                ))
+                .bind(guild_id.0 as i64)
+                .bind(channel_id.0 as i64)
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query(&format!(
+                    "UPDATE text_channels SET {} = null WHERE guild_id = $1",
+                    column
+                ))
+                .bind(guild_id.0 as i64)
+                .execute(&self.pool)
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StarboardRepository for SqlRepository {
+    async fn fetch_starboard_entries(&self) -> CommandResult<Vec<StarboardEntry>> {
+        let entry_data = sqlx::query_as::<_, StarboardRow>(
+            "SELECT guild_id, reaction_message_id, sent_message_id, delete_time FROM starboard",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entry_data
+            .into_iter()
+            .map(|x| StarboardEntry {
+                guild_id: GuildId(x.guild_id as u64),
+                reaction_message_id: MessageId(x.reaction_message_id as u64),
+                sent_message_id: MessageId(x.sent_message_id as u64),
+                delete_time: x.delete_time,
+            })
+            .collect())
+    }
+
+    async fn fetch_starboard_message(
+        &self,
+        guild_id: GuildId,
+        reaction_message_id: MessageId,
+    ) -> CommandResult<Option<MessageId>> {
+        let message_data = sqlx::query_scalar::<_, i64>(
+            "SELECT sent_message_id FROM starboard WHERE guild_id = $1 AND reaction_message_id = $2",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(reaction_message_id.0 as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(message_data.map(|x| MessageId(x as u64)))
+    }
+
+    async fn add_starboard_entry(&self, entry: StarboardEntry) -> CommandResult {
+        sqlx::query("INSERT INTO starboard VALUES($1, $2, $3, $4) ON CONFLICT DO NOTHING")
+            .bind(entry.guild_id.0 as i64)
+            .bind(entry.reaction_message_id.0 as i64)
+            .bind(entry.sent_message_id.0 as i64)
+            .bind(entry.delete_time)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_starboard_entry(
+        &self,
+        guild_id: GuildId,
+        reaction_message_id: MessageId,
+    ) -> CommandResult {
+        sqlx::query("DELETE FROM starboard WHERE guild_id = $1 AND reaction_message_id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(reaction_message_id.0 as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ModerationRepository for SqlRepository {
+    /*
+     * Case numbers come from a per-guild counter row
+     * The upsert locks that row, so concurrent cases in a guild can't get the same number
+     */
+    async fn add_case(&self, case: ModCase) -> CommandResult<i64> {
+        let mut transaction = self.pool.begin().await?;
+
+        let case_number = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO mod_case_counters(guild_id, last_case) VALUES($1, 1)
+                ON CONFLICT (guild_id) DO UPDATE SET last_case = mod_case_counters.last_case + 1
+                RETURNING last_case",
+        )
+        .bind(case.guild_id.0 as i64)
+        .fetch_one(&mut transaction)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO mod_cases(guild_id, case_number, action, user_id, moderator_id, reason, duration_secs, created_at)
+                VALUES($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(case.guild_id.0 as i64)
+        .bind(case_number)
+        .bind(case.action.to_string())
+        .bind(case.user_id.0 as i64)
+        .bind(case.moderator_id.0 as i64)
+        .bind(case.reason)
+        .bind(case.duration_secs)
+        .bind(case.created_at)
+        .execute(&mut transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(case_number)
+    }
+
+    async fn fetch_case(
+        &self,
+        guild_id: GuildId,
+        case_number: i64,
+    ) -> CommandResult<Option<ModCase>> {
+        let case_data = sqlx::query_as::<_, ModCaseRow>(
+            "SELECT guild_id, case_number, action, user_id, moderator_id, reason, duration_secs, created_at
+                FROM mod_cases WHERE guild_id = $1 AND case_number = $2",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(case_number)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match case_data {
+            Some(row) => Ok(Some(ModCase::try_from(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn fetch_user_cases(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> CommandResult<Vec<ModCase>> {
+        let case_data = sqlx::query_as::<_, ModCaseRow>(
+            "SELECT guild_id, case_number, action, user_id, moderator_id, reason, duration_secs, created_at
+                FROM mod_cases WHERE guild_id = $1 AND user_id = $2
+                ORDER BY case_number DESC",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(user_id.0 as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let cases = case_data
+            .into_iter()
+            .map(ModCase::try_from)
+            .collect::<Result<Vec<ModCase>, String>>()?;
+
+        Ok(cases)
+    }
+
+    async fn set_case_reason(
+        &self,
+        guild_id: GuildId,
+        case_number: i64,
+        reason: &str,
+    ) -> CommandResult<bool> {
+        let result = sqlx::query(
+            "UPDATE mod_cases SET reason = $1 WHERE guild_id = $2 AND case_number = $3",
+        )
+        .bind(reason)
+        .bind(guild_id.0 as i64)
+        .bind(case_number)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[async_trait]
+impl AutomodRepository for SqlRepository {
+    async fn fetch_automod_settings(&self, guild_id: GuildId) -> CommandResult<AutomodSettings> {
+        let rule_data = sqlx::query_as::<_, AutomodRuleRow>(
+            "SELECT kind, action, threshold, interval_secs, timeout_secs FROM automod_rules WHERE guild_id = $1",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let rules = rule_data
+            .into_iter()
+            .map(AutomodRule::try_from)
+            .collect::<Result<Vec<AutomodRule>, String>>()?;
+
+        let filter_data = sqlx::query_as::<_, (String, String)>(
+            "SELECT list, pattern FROM automod_filters WHERE guild_id = $1",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let filters = filter_data
+            .into_iter()
+            .filter_map(|(list, pattern)| list.parse::<FilterList>().ok().map(|x| (x, pattern)))
+            .collect();
+
+        let exemption_data = sqlx::query_as::<_, (String, i64)>(
+            "SELECT kind, exempt_id FROM automod_exemptions WHERE guild_id = $1",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let exemptions = exemption_data
+            .into_iter()
+            .filter_map(|(kind, id)| AutomodExemption::from_row(&kind, id))
+            .collect();
+
+        Ok(AutomodSettings {
+            rules,
+            filters,
+            exemptions,
+        })
+    }
+
+    async fn set_automod_rule(&self, guild_id: GuildId, rule: AutomodRule) -> CommandResult {
+        sqlx::query(
+            "INSERT INTO automod_rules(guild_id, kind, action, threshold, interval_secs, timeout_secs)
+                VALUES($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (guild_id, kind)
+                DO UPDATE SET action = $3, threshold = $4, interval_secs = $5, timeout_secs = $6",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(rule.kind.to_string())
+        .bind(rule.action.to_string())
+        .bind(rule.threshold)
+        .bind(rule.interval_secs)
+        .bind(rule.timeout_secs)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_automod_rule(&self, guild_id: GuildId, kind: RuleKind) -> CommandResult<bool> {
+        let result = sqlx::query("DELETE FROM automod_rules WHERE guild_id = $1 AND kind = $2")
+            .bind(guild_id.0 as i64)
+            .bind(kind.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn add_automod_filter(
+        &self,
+        guild_id: GuildId,
+        list: FilterList,
+        pattern: &str,
+    ) -> CommandResult<bool> {
+        let result = sqlx::query(
+            "INSERT INTO automod_filters(guild_id, list, pattern)
+                VALUES($1, $2, $3)
+                ON CONFLICT DO NOTHING",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(list.to_string())
+        .bind(pattern)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn remove_automod_filter(
+        &self,
+        guild_id: GuildId,
+        list: FilterList,
+        pattern: &str,
+    ) -> CommandResult<bool> {
+        let result = sqlx::query(
+            "DELETE FROM automod_filters WHERE guild_id = $1 AND list = $2 AND pattern = $3",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(list.to_string())
+        .bind(pattern)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn toggle_automod_exemption(
+        &self,
+        guild_id: GuildId,
+        exemption: AutomodExemption,
+    ) -> CommandResult<bool> {
+        let removed = sqlx::query(
+            "DELETE FROM automod_exemptions WHERE guild_id = $1 AND kind = $2 AND exempt_id = $3",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(exemption.kind())
+        .bind(exemption.id())
+        .execute(&self.pool)
+        .await?;
+
+        if removed.rows_affected() > 0 {
+            return Ok(false);
+        }
+
+        sqlx::query("INSERT INTO automod_exemptions(guild_id, kind, exempt_id) VALUES($1, $2, $3)")
+            .bind(guild_id.0 as i64)
+            .bind(exemption.kind())
+            .bind(exemption.id())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl CommandRuleRepository for SqlRepository {
+    async fn fetch_command_rules(&self, guild_id: GuildId) -> CommandResult<CommandRules> {
+        let disabled = sqlx::query_scalar::<_, String>(
+            "SELECT name FROM disabled_commands WHERE guild_id = $1 ORDER BY name",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let channel_data = sqlx::query_as::<_, (String, i64)>(
+            "SELECT name, channel_id FROM command_channels WHERE guild_id = $1 ORDER BY name",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let cooldown_data = sqlx::query_as::<_, (String, String, i64)>(
+            "SELECT name, scope, seconds FROM command_cooldowns WHERE guild_id = $1 ORDER BY name",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut cooldowns = Vec::new();
+
+        for (name, scope, seconds) in cooldown_data {
+            let cooldown = Cooldown {
+                scope: scope.parse()?,
+                seconds: seconds.max(0) as u64,
+            };
+
+            cooldowns.push((name, cooldown));
+        }
+
+        Ok(CommandRules {
+            disabled,
+            channels: channel_data
+                .into_iter()
+                .map(|(name, channel_id)| (name, ChannelId::from(channel_id as u64)))
+                .collect(),
+            cooldowns,
+        })
+    }
+
+    async fn set_disabled(&self, guild_id: GuildId, name: &str, disabled: bool) -> CommandResult {
+        let query = if disabled {
+            "INSERT INTO disabled_commands(guild_id, name) VALUES($1, $2) ON CONFLICT DO NOTHING"
+        } else {
+            "DELETE FROM disabled_commands WHERE guild_id = $1 AND name = $2"
+        };
+
+        sqlx::query(query)
+            .bind(guild_id.0 as i64)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn add_command_channels(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        channels: &[ChannelId],
+    ) -> CommandResult {
+        let mut transaction = self.pool.begin().await?;
+
+        for channel_id in channels {
+            sqlx::query(
+                "INSERT INTO command_channels(guild_id, name, channel_id)
+                    VALUES($1, $2, $3)
+                    ON CONFLICT DO NOTHING",
+            )
+            .bind(guild_id.0 as i64)
+            .bind(name)
+            .bind(channel_id.0 as i64)
+            .execute(&mut transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn clear_command_channels(&self, guild_id: GuildId, name: &str) -> CommandResult {
+        sqlx::query("DELETE FROM command_channels WHERE guild_id = $1 AND name = $2")
+            .bind(guild_id.0 as i64)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_cooldown(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        cooldown: Option<Cooldown>,
+    ) -> CommandResult {
+        let cooldown = match cooldown {
+            Some(cooldown) => cooldown,
+            None => {
+                sqlx::query("DELETE FROM command_cooldowns WHERE guild_id = $1 AND name = $2")
+                    .bind(guild_id.0 as i64)
+                    .bind(name)
+                    .execute(&self.pool)
+                    .await?;
+
+                return Ok(());
+            }
+        };
+
+        sqlx::query(
+            "INSERT INTO command_cooldowns(guild_id, name, scope, seconds)
+                VALUES($1, $2, $3, $4)
+                ON CONFLICT (guild_id, name)
+                DO UPDATE
+                SET scope = EXCLUDED.scope, seconds = EXCLUDED.seconds",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(name)
+        .bind(cooldown.scope.to_string())
+        .bind(cooldown.seconds as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{cooldowns::CooldownScope, database_helper, moderation::ModAction};
+    use std::sync::Arc;
+
+    const GUILD: GuildId = GuildId(1);
+
+    fn new_case(user_id: u64) -> ModCase {
+        ModCase {
+            guild_id: GUILD,
+            case_number: 0,
+            action: ModAction::Warn,
+            user_id: UserId(user_id),
+            moderator_id: UserId(10),
+            reason: None,
+            duration_secs: None,
+            created_at: 0,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_cases_get_unique_numbers() {
+        let pool = database_helper::obtain_test_pool("concurrent-cases").await;
+        let repository = Arc::new(SqlRepository::new(pool));
+        repository.add_guild(GUILD).await.unwrap();
+
+        let tasks = (0..20)
+            .map(|user_id| {
+                let repository = repository.clone();
+                tokio::spawn(async move { repository.add_case(new_case(user_id)).await.unwrap() })
+            })
+            .collect::<Vec<_>>();
+
+        let mut case_numbers = Vec::new();
+        for task in tasks {
+            case_numbers.push(task.await.unwrap());
+        }
+        case_numbers.sort_unstable();
+
+        assert_eq!(case_numbers, (1..=20).collect::<Vec<_>>());
+        assert_eq!(
+            repository
+                .fetch_case(GUILD, 20)
+                .await
+                .unwrap()
+                .unwrap()
+                .case_number,
+            20
+        );
+    }
+
+    #[tokio::test]
+    async fn command_rules_are_saved() {
+        let pool = database_helper::obtain_test_pool("command-rules").await;
+        let repository = SqlRepository::new(pool);
+        repository.add_guild(GUILD).await.unwrap();
+
+        let cooldown = Cooldown {
+            scope: CooldownScope::Guild,
+            seconds: 15,
+        };
+
+        repository.set_disabled(GUILD, "fun", true).await.unwrap();
+        repository.set_disabled(GUILD, "fun", true).await.unwrap();
+        repository
+            .add_command_channels(GUILD, "ping", &[ChannelId(6), ChannelId(5)])
+            .await
+            .unwrap();
+        repository
+            .set_cooldown(GUILD, "ping", Some(cooldown))
+            .await
+            .unwrap();
+        repository
+            .set_cooldown(GUILD, "ping", Some(cooldown))
+            .await
+            .unwrap();
+
+        let rules = repository.fetch_command_rules(GUILD).await.unwrap();
+
+        assert_eq!(rules.disabled, ["fun"]);
+        assert_eq!(rules.channels.len(), 2);
+        assert_eq!(rules.cooldowns, [("ping".to_owned(), cooldown)]);
+
+        repository.set_disabled(GUILD, "fun", false).await.unwrap();
+        repository
+            .clear_command_channels(GUILD, "ping")
+            .await
+            .unwrap();
+        repository.set_cooldown(GUILD, "ping", None).await.unwrap();
+
+        let rules = repository.fetch_command_rules(GUILD).await.unwrap();
+
+        assert!(
+            rules.disabled.is_empty() && rules.channels.is_empty() && rules.cooldowns.is_empty()
+        );
+    }
+}
@@ -5,11 +5,23 @@ use futures::future::AbortHandle;
 use reqwest::Client as Reqwest;
 use serenity::{
     client::bridge::gateway::ShardManager,
-    model::id::{GuildId, UserId},
+    model::id::{GuildId, MessageId, UserId},
     prelude::{Mutex, TypeMapKey},
 };
-use sqlx::PgPool;
-use std::{collections::HashMap, sync::Arc};
+use sqlx::AnyPool;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::Instant,
+};
+
+use crate::{
+    helpers::{
+        command_rules::CommandScope, cooldowns::CooldownScope, guild_settings::GuildSettings,
+        mod_log::BoundedMessageCache,
+    },
+    repositories::Repository,
+};
 
 // All command context data structures
 pub struct ShardManagerContainer;
@@ -21,7 +33,14 @@ impl TypeMapKey for ShardManagerContainer {
 pub struct ConnectionPool;
 
 impl TypeMapKey for ConnectionPool {
-    type Value = PgPool;
+    type Value = AnyPool;
+}
+
+// Guild settings, custom commands, sender channels, and starboard entries are accessed through here
+pub struct RepositoryContainer;
+
+impl TypeMapKey for RepositoryContainer {
+    type Value = Arc<dyn Repository>;
 }
 
 /*
@@ -44,6 +63,25 @@ impl TypeMapKey for CommandNameMap {
     type Value = Arc<Vec<String>>;
 }
 
+pub struct CommandScopeMap;
+
+impl TypeMapKey for CommandScopeMap {
+    type Value = Arc<HashMap<String, Vec<CommandScope>>>;
+}
+
+pub struct CooldownMap;
+
+impl TypeMapKey for CooldownMap {
+    type Value = Arc<DashMap<(String, CooldownScope, u64), Instant>>;
+}
+
+// Start times of running prefix commands for usage analytics
+pub struct CommandTimers;
+
+impl TypeMapKey for CommandTimers {
+    type Value = Arc<DashMap<MessageId, Instant>>;
+}
+
 pub struct ReqwestClient;
 
 impl TypeMapKey for ReqwestClient {
@@ -62,10 +100,37 @@ impl TypeMapKey for BotId {
     type Value = UserId;
 }
 
-pub struct PrefixMap;
+pub struct BotOwners;
+
+impl TypeMapKey for BotOwners {
+    type Value = Arc<HashSet<UserId>>;
+}
+
+// Commands turned off because their API key isn't configured
+pub struct UnavailableCommands;
+
+impl TypeMapKey for UnavailableCommands {
+    type Value = Arc<HashSet<String>>;
+}
+
+pub struct GuildSettingsMap;
+
+impl TypeMapKey for GuildSettingsMap {
+    type Value = Arc<DashMap<GuildId, GuildSettings>>;
+}
+
+// Recent guild messages for logging edits and deletes in the mod log
+pub struct MessageCache;
+
+impl TypeMapKey for MessageCache {
+    type Value = Arc<BoundedMessageCache>;
+}
+
+// Send times and content hashes of recent messages for automod rate limits and duplicates
+pub struct AutomodTracker;
 
-impl TypeMapKey for PrefixMap {
-    type Value = Arc<DashMap<GuildId, String>>;
+impl TypeMapKey for AutomodTracker {
+    type Value = Arc<DashMap<(GuildId, UserId), VecDeque<(Instant, u64)>>>;
 }
 
 pub struct EmergencyCommands;
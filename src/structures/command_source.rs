@@ -0,0 +1,135 @@
+use serenity::{
+    builder::CreateEmbed,
+    client::Context,
+    framework::standard::CommandResult,
+    model::{
+        application::interaction::application_command::ApplicationCommandInteraction,
+        channel::{AttachmentType, Message},
+        id::{ChannelId, GuildId, MessageId},
+        user::User,
+    },
+};
+use std::fmt::Display;
+
+use crate::helpers::command_utils;
+
+/*
+ * Where a command was invoked from
+ * Prefix and slash commands share the same logic by replying through this enum
+ * Slash commands are deferred before they run, so every reply is a followup message
+ */
+pub enum CommandSource<'a> {
+    Prefix(&'a Message),
+    Slash(&'a ApplicationCommandInteraction),
+}
+
+impl CommandSource<'_> {
+    pub fn author(&self) -> &User {
+        match self {
+            CommandSource::Prefix(msg) => &msg.author,
+            CommandSource::Slash(command) => &command.user,
+        }
+    }
+
+    pub fn channel_id(&self) -> ChannelId {
+        match self {
+            CommandSource::Prefix(msg) => msg.channel_id,
+            CommandSource::Slash(command) => command.channel_id,
+        }
+    }
+
+    pub fn guild_id(&self) -> Option<GuildId> {
+        match self {
+            CommandSource::Prefix(msg) => msg.guild_id,
+            CommandSource::Slash(command) => command.guild_id,
+        }
+    }
+
+    // For slash commands, this is the bot's deferred response
+    pub async fn message_id(&self, ctx: &Context) -> CommandResult<MessageId> {
+        match self {
+            CommandSource::Prefix(msg) => Ok(msg.id),
+            CommandSource::Slash(command) => {
+                let response = command.get_interaction_response(&ctx.http).await?;
+
+                Ok(response.id)
+            }
+        }
+    }
+
+    pub async fn message_url(&self, ctx: &Context) -> CommandResult<String> {
+        let message_id = self.message_id(ctx).await?;
+
+        Ok(command_utils::get_message_url(
+            self.guild_id().unwrap(),
+            self.channel_id(),
+            message_id,
+        ))
+    }
+
+    pub async fn say(&self, ctx: &Context, content: impl Display) -> CommandResult<Message> {
+        let message = match self {
+            CommandSource::Prefix(msg) => msg.channel_id.say(ctx, content).await?,
+            CommandSource::Slash(command) => {
+                command
+                    .create_followup_message(&ctx.http, |m| m.content(content))
+                    .await?
+            }
+        };
+
+        Ok(message)
+    }
+
+    // Slash commands need a visible response even when the prefix version stays silent
+    pub async fn acknowledge(&self, ctx: &Context, content: impl Display) -> CommandResult {
+        if let CommandSource::Slash(_) = self {
+            self.say(ctx, content).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn send_embed(&self, ctx: &Context, embed: CreateEmbed) -> CommandResult<Message> {
+        let message = match self {
+            CommandSource::Prefix(msg) => {
+                msg.channel_id
+                    .send_message(ctx, |m| {
+                        m.embed(|e| {
+                            e.0 = embed.0;
+                            e
+                        })
+                    })
+                    .await?
+            }
+            CommandSource::Slash(command) => {
+                command
+                    .create_followup_message(&ctx.http, |m| m.add_embed(embed))
+                    .await?
+            }
+        };
+
+        Ok(message)
+    }
+
+    pub async fn send_file(
+        &self,
+        ctx: &Context,
+        content: impl Display,
+        file: AttachmentType<'_>,
+    ) -> CommandResult<Message> {
+        let message = match self {
+            CommandSource::Prefix(msg) => {
+                msg.channel_id
+                    .send_message(ctx, |m| m.content(content).add_file(file))
+                    .await?
+            }
+            CommandSource::Slash(command) => {
+                command
+                    .create_followup_message(&ctx.http, |m| m.content(content).add_file(file))
+                    .await?
+            }
+        };
+
+        Ok(message)
+    }
+}
@@ -1,9 +1,9 @@
 use serenity::framework::standard::macros::group;
 
 use crate::commands::{
-    ciphers::*, config::*, images::*, japan::*,
-    /* music::REMOVE_COMMAND, music::*,*/ other::*, starboard::*, support::*,
-    textchannel_send::*, textmod::*, utility::*,
+    automod::*, backup::*, ciphers::*, config::*, images::*, japan::*, moderation::*,
+    /* music::REMOVE_COMMAND, music::*,*/ other::*, owner::*, polls::*, reminders::*,
+    rules::*, starboard::*, stats::*, support::*, textchannel_send::*, textmod::*, utility::*,
 };
 
 // All command groups
@@ -20,10 +20,15 @@ use crate::commands::{
     //Voice,
     Images,
     //Music
+    Japan,
+    Utility,
+    Moderation,
+    Owner,
 )]
 pub struct Master;
 
 #[group]
+#[only_in(guilds)]
 #[help_available(false)]
 #[commands(ping)]
 pub struct General;
@@ -46,21 +51,37 @@ pub struct TextLast;
 pub struct Ciphers;
 
 #[group("Senders")]
+#[only_in(guilds)]
 #[description = "Commands that send certain messages to channels"]
 #[commands(nice, bruh, quote, vibecheck)]
 pub struct TextChannelSend;
 
 #[group("Bot Configuration")]
+#[only_in(guilds)]
 #[description = "Admin/Moderator commands that configure the bot"]
-#[commands(prefix, command, resetprefix)]
+#[commands(
+    prefix,
+    language,
+    command,
+    resetprefix,
+    disable,
+    enable,
+    restrict,
+    unrestrict,
+    cooldown,
+    rules,
+    export,
+    import
+)]
 pub struct Config;
 
 #[group("Support")]
 #[description = "Support commands for the bot"]
-#[commands(help, support, info)]
+#[commands(help, support, info, stats)]
 pub struct Support;
 
 #[group("Starboard")]
+#[only_in(guilds)]
 #[description = "Starboard admin commands"]
 #[commands(starboard)]
 pub struct Starboard;
@@ -78,6 +99,7 @@ pub struct Music;
 */
 
 #[group("Images")]
+#[only_in(guilds)]
 #[description = "Commands for fetching/sending images"]
 #[commands(hug, kiss, pat, slap, disgust, cry, cringe, gifsearch)]
 pub struct Images;
@@ -89,5 +111,17 @@ pub struct Japan;
 
 #[group("Utility")]
 #[description("Server utility commands")]
-#[commands(guild_avatar, avatar, kang, emoji_info, spoiler, banner)]
+#[commands(guild_avatar, avatar, kang, emoji_info, spoiler, banner, remind, poll)]
 pub struct Utility;
+
+#[group("Moderation")]
+#[only_in(guilds)]
+#[description("Moderator commands that keep a record of every action")]
+#[commands(warn, kick, ban, unban, timeout, history, case, modlog, automod)]
+pub struct Moderation;
+
+#[group("Owner")]
+#[owners_only(true)]
+#[help_available(false)]
+#[commands(incident, shards)]
+pub struct Owner;
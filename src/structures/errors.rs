@@ -1,6 +1,9 @@
-use std::fmt;
+use crate::helpers::locale;
 
-#[allow(dead_code)]
+/*
+ * Errors are only turned into text with the guild's locale
+ * MissingError holds the catalog key (after `missing.`) of what the user forgot
+ */
 #[derive(Debug)]
 pub enum JesterError<'a> {
     PermissionError(PermissionType<'a>),
@@ -8,44 +11,73 @@ pub enum JesterError<'a> {
     UnsuccessfulError(&'a str),
 }
 
-impl fmt::Display for JesterError<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl JesterError<'_> {
+    pub fn localized(&self, locale: &str) -> String {
         match *self {
-            JesterError::PermissionError(perm) =>
-                write!(f, "{}", perm),
-            JesterError::MissingError(missing) => write!(f, "Please provide a {}!", missing),
-            JesterError::UnsuccessfulError(cmd) =>
-                write!(f, "{} unsuccessful. The user must be in the guild and the bot must be above the user's role!", cmd)
+            JesterError::PermissionError(perm) => perm.localized(locale),
+            JesterError::MissingError(missing) => {
+                let missing = locale::get_text(locale, &format!("missing.{}", missing));
+
+                locale::get_formatted(locale, "error.missing", &[("missing", &missing)])
+            }
+            JesterError::UnsuccessfulError(cmd) => {
+                locale::get_formatted(locale, "error.unsuccessful", &[("command", cmd)])
+            }
         }
     }
 }
 
-#[allow(dead_code)]
 #[derive(Clone, Copy, Debug)]
 pub enum PermissionType<'b> {
     UserPerm(&'b str),
     Mention(&'b str, &'b str),
     External(&'b str),
+    Role(&'b str),
+    Channel(&'b str),
 }
 
-impl fmt::Display for PermissionType<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl PermissionType<'_> {
+    pub fn localized(&self, locale: &str) -> String {
         match *self {
-            PermissionType::UserPerm(perm) => writeln!(
-                f,
-                "You can't execute this command because you do not have the `{}` permission!",
-                perm
-            ),
-            PermissionType::Mention(cmd, perm) => write!(
-                f,
-                "I can't {} an {}! Please demote the user and try again",
-                cmd, perm
+            PermissionType::UserPerm(perm) => {
+                locale::get_formatted(locale, "permission.user", &[("permission", perm)])
+            }
+            PermissionType::Mention(cmd, perm) => locale::get_formatted(
+                locale,
+                "permission.mention",
+                &[("command", cmd), ("target", perm)],
             ),
-            PermissionType::External(perm) => write!(
-                f,
-                "The bot can't execute this because it doesn't have the `{}` permission! Please grant it on the bot's role.",
-                perm
-            )
+            PermissionType::External(perm) => {
+                locale::get_formatted(locale, "permission.external", &[("permission", perm)])
+            }
+            PermissionType::Role(roles) => {
+                locale::get_formatted(locale, "permission.role", &[("roles", roles)])
+            }
+            PermissionType::Channel(channels) => {
+                locale::get_formatted(locale, "permission.channel", &[("channels", channels)])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_errors_are_translated() {
+        let english = JesterError::MissingError("user").localized("en");
+        let spanish = JesterError::MissingError("user").localized("es");
+
+        assert_eq!(english, "Please provide a user ID/mention!");
+        assert_eq!(spanish, "¡Por favor proporciona un ID/mención de usuario!");
+
+        for missing in ["anime_title", "manga_title", "biggspace", "automod_list"] {
+            for locale in ["en", "es"] {
+                let message = JesterError::MissingError(missing).localized(locale);
+
+                assert!(!message.contains("missing."), "{} in {}", missing, locale);
+            }
         }
     }
 }
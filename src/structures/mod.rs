@@ -1,4 +1,5 @@
 pub mod cmd_data;
+pub mod command_source;
 pub mod commands;
 pub mod errors;
 